@@ -18,6 +18,10 @@ struct Cli {
     #[arg(short, long)]
     json: bool,
 
+    /// Print Exif values in a human-readable form, e.g. `1/125`, `f/2.8`
+    #[arg(short, long)]
+    print: bool,
+
     #[arg(long)]
     debug: bool,
 }
@@ -59,7 +63,7 @@ fn run(cli: &Cli) -> Result<(), Box<dyn Error>> {
 
     let path = Path::new(&cli.file);
     if path.is_file() {
-        let _ = parse_file(&mut parser, path, cli.json);
+        let _ = parse_file(&mut parser, path, cli);
     } else if path.is_dir() {
         parse_dir(path, parser, cli)?;
     }
@@ -85,7 +89,7 @@ fn parse_dir(path: &Path, mut parser: MediaParser, cli: &Cli) -> Result<(), Box<
                 }
                 println!("File: {:?}", entry.path().as_os_str());
                 println!("------------------------------------------------");
-                let _ = parse_file(&mut parser, entry.path(), cli.json);
+                let _ = parse_file(&mut parser, entry.path(), cli);
             }
             Err(e) => {
                 eprintln!("Read dir entry failed: {e}");
@@ -99,20 +103,21 @@ fn parse_dir(path: &Path, mut parser: MediaParser, cli: &Cli) -> Result<(), Box<
 fn parse_file<P: AsRef<Path>>(
     parser: &mut MediaParser,
     path: P,
-    json: bool,
+    cli: &Cli,
 ) -> Result<(), nom_exif::Error> {
     let ms = MediaSource::file_path(path).inspect_err(handle_parsing_error)?;
     let values = if ms.has_exif() {
         let iter: ExifIter = parser.parse(ms).inspect_err(handle_parsing_error)?;
         iter.into_iter()
-            .filter_map(|mut x| {
-                let res = x.take_result();
+            .filter_map(|x| {
+                let res = x.get_result();
                 match res {
                     Ok(v) => Some((
                         x.tag()
                             .map(|x| x.to_string())
                             .unwrap_or_else(|| format!("Unknown(0x{:04x})", x.tag_code())),
-                        v,
+                        v.to_owned(),
+                        x.print_value().filter(|_| cli.print).map(|x| x.to_string()),
                     )),
                     Err(e) => {
                         tracing::warn!(?e);
@@ -124,10 +129,10 @@ fn parse_file<P: AsRef<Path>>(
     } else {
         let info: TrackInfo = parser.parse(ms)?;
         info.into_iter()
-            .map(|x| (x.0.to_string(), x.1))
+            .map(|x| (x.0.to_string(), x.1, None))
             .collect::<Vec<_>>()
     };
-    if json {
+    if cli.json {
         #[cfg(feature = "json_dump")]
         use std::collections::HashMap;
 
@@ -144,8 +149,9 @@ fn parse_file<P: AsRef<Path>>(
             Err(e) => eprintln!("Error: {e}"),
         }
     } else {
-        values.iter().for_each(|x| {
-            println!("{:<32}=> {}", x.0, x.1);
+        values.iter().for_each(|x| match &x.2 {
+            Some(s) => println!("{:<32}=> {}", x.0, s),
+            None => println!("{:<32}=> {}", x.0, x.1),
         });
     };
     Ok(())
@@ -124,6 +124,11 @@ impl ParseBody<InfeBox> for InfeBox {
 
 impl InfeBox {
     fn key(&self) -> &String {
-        self.item_type.as_ref().unwrap_or(&self.item_name)
+        match self.item_type.as_deref() {
+            // There may be several `mime` items, e.g. XMP & other metadata,
+            // distinguish them by content type.
+            Some("mime") => self.content_type.as_ref().unwrap_or(&self.item_name),
+            _ => self.item_type.as_ref().unwrap_or(&self.item_name),
+        }
     }
 }
@@ -2,7 +2,7 @@ use std::{collections::HashMap, fmt::Debug, ops::Range};
 
 use nom::{combinator::fail, multi::many0, IResult, Needed};
 
-use crate::bbox::FullBoxHeader;
+use crate::{bbox::FullBoxHeader, slice::SubsliceRange};
 
 use super::{iinf::IinfBox, iloc::IlocBox, BoxHolder, ParseBody, ParseBox};
 
@@ -12,7 +12,8 @@ pub struct MetaBox {
     header: FullBoxHeader,
     iinf: Option<IinfBox>,
     iloc: Option<IlocBox>,
-    // idat: Option<IdatBox<'a>>,
+    // range of the idat box body, relative to the start of the meta box
+    idat: Option<Range<usize>>,
 }
 
 impl Debug for MetaBox {
@@ -29,7 +30,7 @@ impl Debug for MetaBox {
 }
 
 impl ParseBody<MetaBox> for MetaBox {
-    fn parse_body<'a>(remain: &'a [u8], header: FullBoxHeader) -> IResult<&'a [u8], MetaBox> {
+    fn parse_body<'a>(body: &'a [u8], header: FullBoxHeader) -> IResult<&'a [u8], MetaBox> {
         let (remain, boxes) = many0(|remain: &'a [u8]| {
             if remain.is_empty() {
                 // stop many0 parsing to prevent Incomplete error
@@ -37,7 +38,7 @@ impl ParseBody<MetaBox> for MetaBox {
             }
             let (remain, bbox) = BoxHolder::parse(remain)?;
             Ok((remain, bbox))
-        })(remain)?;
+        })(body)?;
 
         let boxes = boxes
             .into_iter()
@@ -58,12 +59,11 @@ impl ParseBody<MetaBox> for MetaBox {
             .transpose()?
             .map(|x| x.1);
 
-        // parse idat box
-        // let idat = boxes
-        //     .get("idat")
-        //     .and_then(|idat| Some(IdatBox::parse(idat.data)))
-        //     .transpose()?
-        //     .map(|x| x.1);
+        // locate idat box
+        let idat = boxes
+            .get("idat")
+            .and_then(|idat| body.subslice_in_range(idat.body_data()))
+            .map(|x| x.start + header.header_size..x.end + header.header_size);
 
         Ok((
             remain,
@@ -71,70 +71,108 @@ impl ParseBody<MetaBox> for MetaBox {
                 header,
                 iinf,
                 iloc,
-                // idat,
+                idat,
             },
         ))
     }
 }
 
 impl MetaBox {
+    /// Returns the data of the Exif item, `meta_offset` is the offset of this
+    /// meta box in `input`.
     #[tracing::instrument(skip_all)]
-    pub fn exif_data<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Option<&'a [u8]>> {
+    pub fn exif_data<'a>(
+        &self,
+        input: &'a [u8],
+        meta_offset: usize,
+    ) -> IResult<&'a [u8], Option<&'a [u8]>> {
+        self.item_data(input, "Exif", meta_offset)
+    }
+
+    /// Returns the data of the XMP item (`mime` item with content type
+    /// `application/rdf+xml`), if any.
+    #[tracing::instrument(skip_all)]
+    pub fn xmp_data<'a>(
+        &self,
+        input: &'a [u8],
+        meta_offset: usize,
+    ) -> IResult<&'a [u8], Option<&'a [u8]>> {
+        self.item_data(input, "application/rdf+xml", meta_offset)
+    }
+
+    fn item_data<'a>(
+        &self,
+        input: &'a [u8],
+        key: &'static str,
+        meta_offset: usize,
+    ) -> IResult<&'a [u8], Option<&'a [u8]>> {
+        let Some(range) = self.item_range(key, meta_offset) else {
+            return Ok((input, None));
+        };
+        if range.end > input.len() {
+            Err(nom::Err::Incomplete(Needed::new(range.end - input.len())))
+        } else {
+            Ok((&input[range.end..], Some(&input[range]))) // Safe-slice
+        }
+    }
+
+    /// Returns the item ID of the Exif item, if any.
+    pub fn exif_item_id(&self) -> Option<u32> {
         self.iinf
             .as_ref()
             .and_then(|iinf| iinf.get_infe("Exif"))
-            .and_then(|exif_infe| {
-                self.iloc
-                    .as_ref()
-                    .and_then(|iloc| iloc.item_offset_len(exif_infe.id))
-            })
-            .map(|(construction_method, offset, length)| {
-                let start = offset as usize;
-                let end = (offset + length) as usize;
-                if construction_method == 0 {
-                    // file offset
-                    if end > input.len() {
-                        Err(nom::Err::Incomplete(Needed::new(end - input.len())))
-                    } else {
-                        Ok((&input[end..], Some(&input[start..end]))) // Safe-slice
-                    }
-                } else if construction_method == 1 {
-                    // idat offset
-                    tracing::debug!("idat offset construction method is not supported yet");
-                    fail(input)
-                } else {
-                    tracing::debug!("item offset construction method is not supported yet");
-                    fail(input)
-                }
-            })
-            .unwrap_or(Ok((input, None)))
+            .map(|infe| infe.id)
     }
 
+    /// Returns the absolute range of the Exif item data, `meta_offset` is the
+    /// offset of this meta box in the file.
     #[tracing::instrument(skip_all)]
-    pub fn exif_data_offset(&self) -> Option<Range<usize>> {
+    pub fn exif_data_offset(&self, meta_offset: usize) -> Option<Range<usize>> {
+        self.item_range("Exif", meta_offset)
+    }
+
+    /// Returns the item ID of the XMP item, if any.
+    pub fn xmp_item_id(&self) -> Option<u32> {
         self.iinf
             .as_ref()
-            .and_then(|iinf| iinf.get_infe("Exif"))
-            .and_then(|exif_infe| {
-                self.iloc
-                    .as_ref()
-                    .and_then(|iloc| iloc.item_offset_len(exif_infe.id))
-            })
-            .and_then(|(construction_method, offset, length)| {
-                let start = offset as usize;
-                let end = (offset + length) as usize;
-                if construction_method == 0 {
-                    // file offset
-                    Some(start..end)
-                } else if construction_method == 1 {
-                    // idat offset
-                    tracing::debug!("idat offset construction method is not supported yet");
-                    None
-                } else {
-                    tracing::debug!("item offset construction method is not supported yet");
-                    None
+            .and_then(|iinf| iinf.get_infe("application/rdf+xml"))
+            .map(|infe| infe.id)
+    }
+
+    /// Returns the absolute range of the XMP item data, `meta_offset` is the
+    /// offset of this meta box in the file.
+    #[tracing::instrument(skip_all)]
+    pub fn xmp_data_offset(&self, meta_offset: usize) -> Option<Range<usize>> {
+        self.item_range("application/rdf+xml", meta_offset)
+    }
+
+    fn item_range(&self, key: &'static str, meta_offset: usize) -> Option<Range<usize>> {
+        let infe = self.iinf.as_ref()?.get_infe(key)?;
+        let (construction_method, offset, length) = self.iloc.as_ref()?.item_offset_len(infe.id)?;
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(length).ok()?)?;
+
+        match construction_method {
+            // file offset
+            0 => Some(start..end),
+            // idat offset
+            1 => {
+                let Some(idat) = self.idat.as_ref() else {
+                    tracing::debug!("idat box not found");
+                    return None;
+                };
+                if end > idat.len() {
+                    tracing::debug!(?idat, end, "item is out of the idat box");
+                    return None;
                 }
-            })
+                let base = meta_offset + idat.start;
+                Some(base + start..base + end)
+            }
+            _ => {
+                tracing::debug!("item offset construction method is not supported yet");
+                None
+            }
+        }
     }
 }
 
@@ -167,17 +205,21 @@ mod tests {
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
 
         let buf = read_sample(path).unwrap();
-        let (_, bbox) = travel_while(&buf, |bbox| {
+        let (remain, bbox) = travel_while(&buf, |bbox| {
             tracing::info!(bbox.header.box_type, "Got");
             bbox.box_type() != "meta"
         })
         .unwrap();
         let bbox = bbox.unwrap();
+        let offset = buf.len() - remain.len() - bbox.data.len();
 
         assert_eq!(bbox.data.len() as u64, bbox.box_size());
         let (remain, meta) = MetaBox::parse_box(bbox.data).unwrap();
         assert_eq!(remain, b"");
         assert_eq!(meta.header.box_type, "meta");
-        assert_eq!(meta.exif_data(&buf).unwrap().1.unwrap().len(), meta_size);
+        assert_eq!(
+            meta.exif_data(&buf, offset).unwrap().1.unwrap().len(),
+            meta_size
+        );
     }
 }
@@ -1,6 +1,6 @@
 use nom::{
     bytes::complete::take,
-    number::complete::{be_u16, be_u32, be_u64},
+    number::complete::{be_i32, be_u16, be_u32, be_u64},
     sequence::tuple,
 };
 
@@ -31,8 +31,10 @@ pub struct TkhdBox {
     alt_group: u16,
     volume: u16,
     // reserved3: u16,
+    /// Clockwise rotation in degrees (0, 90, 180 or 270) of the display
+    /// matrix, other transformations (e.g. mirroring) are ignored.
+    pub rotation: u32,
 
-    // matrix: [u8; 36],
     pub width: u32,
     pub height: u32,
 }
@@ -52,7 +54,7 @@ impl ParseBody<TkhdBox> for TkhdBox {
                 alt_group,
                 volume,
                 _,
-                _,
+                matrix,
                 width,
                 _,
                 height,
@@ -69,7 +71,7 @@ impl ParseBody<TkhdBox> for TkhdBox {
             be_u16,
             be_u16,
             be_u16,
-            take(36usize),
+            tuple((be_i32, be_i32, take(4usize), be_i32, be_i32, take(16usize))),
             be_u16,
             be_u16,
             be_u16,
@@ -87,6 +89,7 @@ impl ParseBody<TkhdBox> for TkhdBox {
                 layer,
                 alt_group,
                 volume,
+                rotation: matrix_rotation(matrix.0, matrix.1, matrix.3, matrix.4),
                 width: width as u32,
                 height: height as u32,
             },
@@ -94,6 +97,17 @@ impl ParseBody<TkhdBox> for TkhdBox {
     }
 }
 
+/// Returns the clockwise rotation of the display matrix `[a b u; c d v; x y
+/// w]`, e.g. `[0 1 0; -1 0 0; ...]` means rotating by 90 degrees.
+fn matrix_rotation(a: i32, b: i32, c: i32, d: i32) -> u32 {
+    match (a.signum(), b.signum(), c.signum(), d.signum()) {
+        (0, 1, -1, 0) => 90,
+        (-1, 0, 0, -1) => 180,
+        (0, -1, 1, 0) => 270,
+        _ => 0,
+    }
+}
+
 /// Try to find a video track's tkhd in moov body. atom-path: "moov/trak/tkhd".
 pub fn parse_video_tkhd_in_moov(input: &[u8]) -> crate::Result<Option<TkhdBox>> {
     let Some(bbox) = find_video_track(input)? else {
@@ -147,9 +161,9 @@ mod tests {
     use super::*;
     use test_case::test_case;
 
-    #[test_case("meta.mov", 720, 1280)]
-    #[test_case("meta.mp4", 1920, 1080)]
-    fn tkhd_box(path: &str, width: u32, height: u32) {
+    #[test_case("meta.mov", 720, 1280, 0)]
+    #[test_case("meta.mp4", 1920, 1080, 90)]
+    fn tkhd_box(path: &str, width: u32, height: u32, rotation: u32) {
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
 
         let buf = read_sample(path).unwrap();
@@ -160,5 +174,16 @@ mod tests {
 
         assert_eq!(tkhd.width, width);
         assert_eq!(tkhd.height, height);
+        assert_eq!(tkhd.rotation, rotation);
+    }
+
+    #[test_case(0x10000, 0, 0, 0x10000 => 0)]
+    #[test_case(0, 0x10000, -0x10000, 0 => 90)]
+    #[test_case(-0x10000, 0, 0, -0x10000 => 180)]
+    #[test_case(0, -0x10000, 0x10000, 0 => 270)]
+    // mirrored
+    #[test_case(-0x10000, 0, 0, 0x10000 => 0)]
+    fn rotation(a: i32, b: i32, c: i32, d: i32) -> u32 {
+        matrix_rotation(a, b, c, d)
     }
 }
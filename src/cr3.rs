@@ -0,0 +1,230 @@
+use crate::{
+    bbox::{find_box, travel_while},
+    error::ParsingError,
+    exif::input_into_iter,
+    mov::extract_moov_body_from_buf,
+    EntryValue, ExifIter, ExifTag, IfdGroup,
+};
+
+/// UUID of the Canon metadata box in `moov`.
+const CANON_UUID: [u8; 16] = [
+    0x85, 0xC0, 0xB6, 0x87, 0x82, 0x0F, 0x11, 0xE0, 0x81, 0x11, 0xF4, 0xCE, 0x46, 0x2B, 0x6A, 0x48,
+];
+
+/// Extract the Exif data of a Canon CR3 file, returns an `ExifIter` of it.
+///
+/// The Exif data is split into four TIFF blocks, which are stored in the
+/// `CMT1`..`CMT4` boxes of the Canon `uuid` box in `moov`:
+///
+/// - `CMT1`: IFD0
+/// - `CMT2`: Exif sub-IFD
+/// - `CMT3`: Canon MakerNote
+/// - `CMT4`: GPS sub-IFD
+///
+/// Each block is iterated as-is (the `ExifIter`s are chained), so nothing is
+/// re-encoded. A block which can't be parsed is skipped. The MakerNote block
+/// is kept as the `MakerNote` entry.
+pub(crate) fn extract_exif_iter(input: &[u8]) -> Result<Option<ExifIter>, ParsingError> {
+    let range = extract_moov_body_from_buf(input)?;
+    let moov_body = &input[range];
+
+    let (_, uuid) = travel_while(moov_body, |bbox| {
+        bbox.box_type() != "uuid" || !bbox.body_data().starts_with(&CANON_UUID)
+    })?;
+    let Some(uuid) = uuid else {
+        tracing::debug!("Canon uuid box not found");
+        return Ok(None);
+    };
+
+    let boxes = &uuid.body_data()[CANON_UUID.len()..];
+    let mut blocks = [None; 4];
+    for (i, block) in blocks.iter_mut().enumerate() {
+        let (_, bbox) = find_box(boxes, &format!("CMT{}", i + 1))?;
+        *block = bbox.map(|x| x.body_data());
+    }
+
+    Ok(chain_cmt_blocks(blocks))
+}
+
+fn chain_cmt_blocks(blocks: [Option<&[u8]>; 4]) -> Option<ExifIter> {
+    let [cmt1, cmt2, cmt3, cmt4] = blocks;
+
+    let mut iter: Option<ExifIter> = None;
+    for (name, data, tag) in [
+        ("CMT1", cmt1, None),
+        ("CMT2", cmt2, Some(ExifTag::ExifOffset)),
+        ("CMT4", cmt4, Some(ExifTag::GPSInfo)),
+    ] {
+        let Some(data) = data else {
+            continue;
+        };
+        // The entries are located in IFD0 of each block
+        let mut block = match input_into_iter(data.to_vec(), None) {
+            Ok(x) => x,
+            Err(e) => {
+                tracing::warn!(name, ?e, "Ignored: invalid CMT box");
+                continue;
+            }
+        };
+        if let Some(tag) = tag {
+            block = block.ifd0_as_sub_ifd(tag);
+        }
+        iter = Some(match iter {
+            Some(x) => x.chain(block),
+            None => block,
+        });
+    }
+
+    match (iter, cmt3) {
+        (Some(iter), Some(data)) => Some(iter.with_entry(
+            IfdGroup::Exif,
+            ExifTag::MakerNote,
+            EntryValue::Undefined(data.to_vec()),
+        )),
+        (iter, _) => iter,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::{bmff_box, open_sample, parse_sample};
+    use crate::{Exif, ExifWriter, MediaParser, MediaSource};
+    use test_case::test_case;
+
+    #[test]
+    fn cr3_exif() {
+        let mut parser = MediaParser::new();
+        let ms = MediaSource::file(open_sample("exif.cr3").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let gps = iter.parse_gps_info().unwrap().unwrap();
+        assert_eq!(gps.format_iso6709(), "+22.53113+114.02148/");
+
+        let exif: Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap().to_string(), "Canon");
+        assert_eq!(
+            exif.get(ExifTag::Model).unwrap().to_string(),
+            "Canon EOS R5"
+        );
+        assert_eq!(
+            exif.get(ExifTag::ExposureTime).unwrap().to_string(),
+            "1/250 (0.0040)"
+        );
+        assert_eq!(
+            exif.get(ExifTag::ISOSpeedRatings).unwrap().as_u16(),
+            Some(400)
+        );
+        assert!(matches!(
+            exif.get(ExifTag::MakerNote),
+            Some(EntryValue::Undefined(x)) if x.starts_with(b"II*\0")
+        ));
+    }
+
+    fn cmt(tag: ExifTag, value: EntryValue) -> Vec<u8> {
+        let mut writer = ExifWriter::little_endian();
+        writer.set(IfdGroup::Ifd0, tag, value);
+        writer.write().unwrap()
+    }
+
+    fn cr3_sample(cmt_boxes: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut uuid = CANON_UUID.to_vec();
+        for (t, data) in cmt_boxes {
+            uuid.extend(bmff_box(t, data));
+        }
+        let moov = bmff_box(b"moov", &bmff_box(b"uuid", &uuid));
+        [
+            bmff_box(b"ftyp", b"crx \0\0\0\x01crx isom"),
+            moov,
+            bmff_box(b"mdat", &[0; 256]),
+        ]
+        .concat()
+    }
+
+    // CMT boxes may be missing, e.g. no GPS info
+    #[test_case(&[b"CMT1", b"CMT2"])]
+    #[test_case(&[b"CMT2"])]
+    fn cr3_partial_cmt(types: &[&[u8; 4]]) {
+        let boxes: Vec<_> = types
+            .iter()
+            .map(|t| {
+                let data = match &t[..] {
+                    b"CMT1" => cmt(ExifTag::Make, "Canon".into()),
+                    _ => cmt(ExifTag::FNumber, (28, 10).into()),
+                };
+                (*t, data)
+            })
+            .collect();
+        let iter: ExifIter = parse_sample(cr3_sample(&boxes)).unwrap();
+        let exif: Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).is_some(), types.contains(&b"CMT1"));
+        assert_eq!(
+            exif.get(ExifTag::FNumber).unwrap().to_string(),
+            "28/10 (2.8000)"
+        );
+    }
+
+    // Each block is parsed as-is, in its own byte order
+    #[test]
+    fn cr3_chained_blocks() {
+        let t = chrono::NaiveDateTime::parse_from_str("2024-03-01 11:59:15", "%Y-%m-%d %H:%M:%S")
+            .unwrap();
+        let mut cmt1 = ExifWriter::new();
+        cmt1.set(IfdGroup::Ifd0, ExifTag::Make, "Canon".into()).set(
+            IfdGroup::Ifd0,
+            ExifTag::ModifyDate,
+            t.into(),
+        );
+        let mut cmt2 = ExifWriter::little_endian();
+        cmt2.set(IfdGroup::Ifd0, ExifTag::DateTimeOriginal, t.into())
+            .set(IfdGroup::Ifd0, ExifTag::OffsetTime, "+08:00".into())
+            .set(IfdGroup::Ifd0, ExifTag::OffsetTimeOriginal, "+08:00".into());
+        let mut cmt4 = ExifWriter::new();
+        cmt4.set_by_tag_code(IfdGroup::Ifd0, 0, EntryValue::U8Array(vec![2, 3, 0, 0]))
+            .set_by_tag_code(IfdGroup::Ifd0, 1, "N".into());
+        let data = cr3_sample(&[
+            (b"CMT1", cmt1.write().unwrap()),
+            (b"CMT2", cmt2.write().unwrap()),
+            (b"CMT3", b"II*\0maker note".to_vec()),
+            (b"CMT4", cmt4.write().unwrap()),
+        ]);
+
+        let iter: ExifIter = parse_sample(data).unwrap();
+        let entries: Vec<_> = iter.clone_and_rewind().collect();
+        assert_eq!(entries.len(), 8);
+        assert!(entries.iter().all(|x| x.get_result().is_ok()));
+
+        let exif: Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap(), &"Canon".into());
+        for tag in [ExifTag::ModifyDate, ExifTag::DateTimeOriginal] {
+            assert_eq!(
+                exif.date_time(tag)
+                    .unwrap()
+                    .to_datetime()
+                    .unwrap()
+                    .to_rfc3339(),
+                "2024-03-01T11:59:15+08:00"
+            );
+        }
+        assert_eq!(
+            exif.get(ExifTag::GPSVersionID).unwrap(),
+            &EntryValue::U8Array(vec![2, 3, 0, 0])
+        );
+        assert_eq!(exif.get(ExifTag::GPSLatitudeRef).unwrap(), &"N".into());
+        assert_eq!(
+            exif.get(ExifTag::MakerNote).unwrap(),
+            &EntryValue::Undefined(b"II*\0maker note".to_vec())
+        );
+    }
+
+    // An invalid block is skipped
+    #[test]
+    fn cr3_invalid_cmt() {
+        let data = cr3_sample(&[
+            (b"CMT1", cmt(ExifTag::Make, "Canon".into())),
+            (b"CMT2", b"II*\0\xff\xff\xff\xff".to_vec()),
+        ]);
+        let iter: ExifIter = parse_sample(data).unwrap();
+        let exif: Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap(), &"Canon".into());
+    }
+}
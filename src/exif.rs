@@ -4,28 +4,44 @@ use crate::parser::{BufParser, ParsingState, ShareBuf};
 use crate::raf::RafInfo;
 use crate::skip::Skip;
 use crate::slice::SubsliceRange;
-use crate::{heif, jpeg, MediaParser, MediaSource};
+use crate::{cr3, heif, jpeg, jxl, png, webp, MediaParser, MediaSource};
 #[allow(deprecated)]
 use crate::{partial_vec::PartialVec, FileFormat};
-use exif_exif::check_exif_header2;
+pub use capture::{CaptureSettings, LensSpec};
+pub use datetime::{ExifDateTime, OffsetSource};
+pub use enums::{
+    ColorSpace, Contrast, CustomRendered, ExifEnum, ExposureMode, ExposureProgram, Flash,
+    FlashMode, FlashReturn, GainControl, LightSource, MeteringMode, Orientation, ResolutionUnit,
+    Saturation, SceneCaptureType, SensingMethod, Sharpness, WhiteBalance,
+};
 pub use exif_exif::Exif;
-use exif_iter::input_into_iter;
+pub(crate) use exif_iter::{input_into_iter, MAX_SUB_IFDS};
 pub use exif_iter::{ExifIter, ParsedExifEntry};
 pub use gps::{GPSInfo, LatLng};
+pub use ifd::{IfdGroup, IfdId};
+pub use print::PrintValue;
 pub use tags::ExifTag;
+pub use writer::ExifWriter;
 
 use std::io::Read;
 use std::ops::Range;
 
 pub(crate) mod ifd;
-pub(crate) use exif_exif::{check_exif_header, TiffHeader};
+pub(crate) use exif_exif::{
+    check_exif_header, ifd_entry_size, offset_size, TiffHeader, EXIF_IDENT, IFD_ENTRY_SIZE,
+};
 pub(crate) use travel::IfdHeaderTravel;
 
+mod capture;
+mod datetime;
+mod enums;
 mod exif_exif;
 mod exif_iter;
 mod gps;
+mod print;
 mod tags;
 mod travel;
+mod writer;
 
 /// *Deprecated*: Please use [`crate::MediaParser`] instead.
 ///
@@ -66,12 +82,29 @@ pub fn parse_exif<T: Read>(reader: T, _: Option<FileFormat>) -> crate::Result<Op
     Ok(Some(iter))
 }
 
-#[tracing::instrument(skip(reader))]
 pub(crate) fn parse_exif_iter<R: Read, S: Skip<R>>(
     parser: &mut MediaParser,
     mime_img: MimeImage,
     reader: &mut R,
 ) -> Result<ExifIter, crate::Error> {
+    find_exif_iter::<R, S>(parser, mime_img, reader)?.ok_or_else(|| "Exif not found".into())
+}
+
+/// Same as [`parse_exif_iter`], except that `None` is returned if the file
+/// has no Exif data.
+#[tracing::instrument(skip(reader))]
+pub(crate) fn find_exif_iter<R: Read, S: Skip<R>>(
+    parser: &mut MediaParser,
+    mime_img: MimeImage,
+    reader: &mut R,
+) -> Result<Option<ExifIter>, crate::Error> {
+    if let Some(extract) = owned_exif_extractor(mime_img) {
+        let iter = parser.load_and_parse::<R, S, _, _>(reader, |buf, _| {
+            extract(buf).map_err(|e| ParsingErrorState::new(e, None))
+        })?;
+        return Ok(owned_to_iter(iter));
+    }
+
     let out = parser.load_and_parse::<R, S, _, _>(reader, |buf, state| {
         extract_exif_range(mime_img, buf, state)
     })?;
@@ -95,21 +128,50 @@ fn extract_exif_range(img: MimeImage, buf: &[u8], state: Option<ParsingState>) -
 fn range_to_iter(
     parser: &mut impl ShareBuf,
     out: Option<(Range<usize>, Option<TiffHeader>)>,
-) -> Result<ExifIter, crate::Error> {
+) -> Result<Option<ExifIter>, crate::Error> {
     if let Some((range, header)) = out {
         tracing::debug!(?range, ?header, "Got Exif data");
         let input: PartialVec = parser.share_buf(range);
         let iter = input_into_iter(input, header)?;
 
-        Ok(iter)
+        Ok(Some(iter))
     } else {
         tracing::debug!("Exif not found");
-        Err("Exif not found".into())
+        Ok(None)
+    }
+}
+
+type OwnedExifExtractor = fn(&[u8]) -> Result<Option<ExifIter>, ParsingError>;
+
+/// For some file types, the Exif data can't be shared from the parsing buffer
+/// (e.g. the data is compressed, or split into several blocks), these
+/// extractors return an `ExifIter` of the owned Exif data.
+fn owned_exif_extractor(mime_img: MimeImage) -> Option<OwnedExifExtractor> {
+    match mime_img {
+        MimeImage::Jxl => Some(jxl_exif_iter),
+        MimeImage::Cr3 => Some(cr3::extract_exif_iter),
+        _ => None,
+    }
+}
+
+fn jxl_exif_iter(buf: &[u8]) -> Result<Option<ExifIter>, ParsingError> {
+    let Some(data) = jxl::extract_exif_data(buf)? else {
+        return Ok(None);
+    };
+    tracing::debug!(len = data.len(), "Got Exif data");
+    input_into_iter(data, None)
+        .map(Some)
+        .map_err(|e| ParsingError::Failed(e.to_string()))
+}
+
+fn owned_to_iter(iter: Option<ExifIter>) -> Option<ExifIter> {
+    if iter.is_none() {
+        tracing::debug!("Exif not found");
     }
+    iter
 }
 
 #[cfg(feature = "async")]
-#[tracing::instrument(skip(reader))]
 pub(crate) async fn parse_exif_iter_async<
     R: AsyncRead + Unpin + Send,
     S: crate::skip::AsyncSkip<R>,
@@ -118,8 +180,33 @@ pub(crate) async fn parse_exif_iter_async<
     mime_img: MimeImage,
     reader: &mut R,
 ) -> Result<ExifIter, crate::Error> {
+    find_exif_iter_async::<R, S>(parser, mime_img, reader)
+        .await?
+        .ok_or_else(|| "Exif not found".into())
+}
+
+/// An async version of [`find_exif_iter`].
+#[cfg(feature = "async")]
+#[tracing::instrument(skip(reader))]
+pub(crate) async fn find_exif_iter_async<
+    R: AsyncRead + Unpin + Send,
+    S: crate::skip::AsyncSkip<R>,
+>(
+    parser: &mut crate::AsyncMediaParser,
+    mime_img: MimeImage,
+    reader: &mut R,
+) -> Result<Option<ExifIter>, crate::Error> {
     use crate::parser_async::AsyncBufParser;
 
+    if let Some(extract) = owned_exif_extractor(mime_img) {
+        let iter = parser
+            .load_and_parse::<R, S, _, _>(reader, |buf, _| {
+                extract(buf).map_err(|e| ParsingErrorState::new(e, None))
+            })
+            .await?;
+        return Ok(owned_to_iter(iter));
+    }
+
     let out = parser
         .load_and_parse::<R, S, _, _>(reader, |buf, state| {
             extract_exif_range(mime_img, buf, state)
@@ -138,8 +225,16 @@ pub(crate) fn extract_exif_with_mime(
         MimeImage::Jpeg => jpeg::extract_exif_data(buf)
             .map(|res| (res.1, state.clone()))
             .map_err(|e| nom_error_to_parsing_error_with_state(e, state))?,
-        MimeImage::Heic | crate::file::MimeImage::Heif => heif_extract_exif(state, buf)?,
-        MimeImage::Tiff => {
+        MimeImage::Heic | MimeImage::Heif | MimeImage::Avif => heif_extract_exif(state, buf)?,
+        MimeImage::Tiff
+        | MimeImage::Dng
+        | MimeImage::Nef
+        | MimeImage::Arw
+        | MimeImage::Cr2
+        | MimeImage::Orf
+        | MimeImage::Rw2
+        | MimeImage::Pef
+        | MimeImage::Srw => {
             let (header, data_start) = match state {
                 Some(ParsingState::TiffHeader(ref h)) => (h.to_owned(), 0),
                 None => {
@@ -157,10 +252,14 @@ pub(crate) fn extract_exif_with_mime(
                 _ => unreachable!(),
             };
 
-            // full fill TIFF data
-            let mut iter =
-                IfdHeaderTravel::new(&buf[data_start..], header.ifd0_offset, header.endian);
-            iter.travel_ifd(0)
+            // full fill TIFF data, including all the IFDs in the IFD chain
+            let mut iter = IfdHeaderTravel::new(
+                &buf[data_start..],
+                header.ifd0_offset,
+                header.endian,
+                header.bigtiff,
+            );
+            iter.travel_ifd_chain()
                 .map_err(|e| ParsingErrorState::new(e, state.clone()))?;
 
             (Some(buf), state)
@@ -168,10 +267,50 @@ pub(crate) fn extract_exif_with_mime(
         MimeImage::Raf => RafInfo::parse(buf)
             .map(|res| (res.1.exif_data, state.clone()))
             .map_err(|e| nom_error_to_parsing_error_with_state(e, state))?,
+        MimeImage::Png => png::extract_exif_data(buf)
+            .map(|res| (res, state.clone()))
+            .map_err(|e| ParsingErrorState::new(e, state))?,
+        MimeImage::Webp => webp::extract_exif_data(buf)
+            .map(|res| (res, state.clone()))
+            .map_err(|e| ParsingErrorState::new(e, state))?,
+        MimeImage::Jxl | MimeImage::Cr3 => {
+            let msg = format!("Exif data of {img_type:?} can't be borrowed from the buffer");
+            return Err(ParsingErrorState::new(ParsingError::Failed(msg), state));
+        }
     };
     Ok((exif_data, state))
 }
 
+/// Extract the data of entry `tag` from TIFF data `input`, IFD0 and its
+/// sub-IFDs are searched.
+pub(crate) fn extract_tiff_tag_data(input: &[u8], tag: u16) -> Result<Option<&[u8]>, ParsingError> {
+    let (_, header) = TiffHeader::parse(input)?;
+    let start = header.ifd0_offset as usize;
+    if start > input.len() {
+        return Err(ParsingError::Need(start - input.len()));
+    }
+
+    let mut range = None;
+    let mut travel = IfdHeaderTravel::new(
+        &input[start..],
+        header.ifd0_offset,
+        header.endian,
+        header.bigtiff,
+    );
+    travel.travel_ifd_with(0, &mut |entry| {
+        if entry.tag == tag && range.is_none() {
+            let size = entry.components_num as usize * entry.data_format.component_size();
+            range = Some(entry.data_offset as usize..entry.data_offset as usize + size);
+        }
+    })?;
+
+    match range {
+        Some(range) if range.end > input.len() => Err(ParsingError::Need(range.end - input.len())),
+        Some(range) => Ok(Some(&input[range])),
+        None => Ok(None),
+    }
+}
+
 fn heif_extract_exif(
     state: Option<ParsingState>,
     buf: &[u8],
@@ -186,8 +325,8 @@ fn heif_extract_exif(
             let (_, meta) = heif::parse_meta_box(buf)
                 .map_err(|e| nom_error_to_parsing_error_with_state(e, state))?;
 
-            if let Some(meta) = meta {
-                if let Some(range) = meta.exif_data_offset() {
+            if let Some((offset, meta)) = meta {
+                if let Some(range) = meta.exif_data_offset(offset) {
                     if range.end > buf.len() {
                         let state = ParsingState::HeifExifSize(range.len());
                         let clear_and_skip = ParsingError::ClearAndSkip(range.start);
@@ -208,7 +347,7 @@ fn heif_extract_exif(
         _ => unreachable!(),
     };
 
-    let data = data.and_then(|x| check_exif_header2(x).map(|x| x.0).ok());
+    let data = data.and_then(heif::exif_item_tiff_data);
 
     Ok((data, state))
 }
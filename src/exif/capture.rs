@@ -0,0 +1,330 @@
+use std::time::Duration;
+
+use crate::{EntryValue, Exif, ExifTag, Flash, URational};
+
+/// A summary of the camera settings of a shot, built from the Exif entries of
+/// the main image, see [`Exif::capture_settings`].
+///
+/// Missing or invalid (e.g. zero) values are `None`.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+///     let iter: ExifIter = parser.parse(ms)?;
+///     let exif: Exif = iter.into();
+///
+///     let settings = exif.capture_settings();
+///     assert_eq!(settings.exposure_time, Some((9997, 1000000).into()));
+///     assert_eq!(settings.aperture, Some(1.75));
+///     assert_eq!(settings.iso, Some(454));
+///     assert_eq!(settings.focal_length_35mm, Some(23));
+///     assert_eq!(settings.flash_fired, Some(false));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CaptureSettings {
+    /// Exposure time in seconds, from `ExposureTime`, or derived from the
+    /// APEX `ShutterSpeedValue`.
+    pub exposure_time: Option<URational>,
+
+    /// F number, from `FNumber`, or derived from the APEX `ApertureValue`
+    /// (rounded to 1 decimal place).
+    pub aperture: Option<f64>,
+
+    /// ISO sensitivity, which is:
+    ///
+    /// - `RecommendedExposureIndex` if `SensitivityType` says it's recorded,
+    /// - otherwise `ISOSpeedRatings` (`PhotographicSensitivity`), unless it's
+    ///   saturated (65535) by a sensitivity which doesn't fit in a `SHORT`,
+    /// - otherwise the first one of `RecommendedExposureIndex`, `ISOSpeed`
+    ///   and `StandardOutputSensitivity`.
+    pub iso: Option<u32>,
+
+    /// Focal length in mm.
+    pub focal_length: Option<f64>,
+
+    /// Equivalent focal length assuming a 35mm film camera, in mm.
+    pub focal_length_35mm: Option<u16>,
+
+    /// Exposure bias in EV.
+    pub exposure_bias: Option<f64>,
+
+    pub flash_fired: Option<bool>,
+
+    pub lens_make: Option<String>,
+    pub lens_model: Option<String>,
+
+    /// From `LensSpecification`, or the DNG `LensInfo`.
+    pub lens_spec: Option<LensSpec>,
+
+    pub body_serial_number: Option<String>,
+}
+
+/// Focal length & F number ranges of a lens, unknown values are `None`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LensSpec {
+    /// Minimum focal length in mm
+    pub min_focal_length: Option<f64>,
+    /// Maximum focal length in mm
+    pub max_focal_length: Option<f64>,
+    /// Minimum F number at the minimum focal length
+    pub min_f_number_wide: Option<f64>,
+    /// Minimum F number at the maximum focal length
+    pub min_f_number_tele: Option<f64>,
+}
+
+impl CaptureSettings {
+    /// Returns the exposure time as a `Duration`.
+    pub fn exposure_duration(&self) -> Option<Duration> {
+        self.exposure_time
+            .and_then(|x| Duration::try_from_secs_f64(x.as_float()).ok())
+    }
+}
+
+impl From<&Exif> for CaptureSettings {
+    fn from(exif: &Exif) -> Self {
+        let number = |tag| {
+            exif.get(tag)
+                .and_then(EntryValue::to_f64)
+                .filter(|x| *x > 0.0)
+        };
+        let text = |tag| {
+            exif.get(tag)
+                .and_then(|x| x.as_str())
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty())
+                .map(|x| x.to_owned())
+        };
+
+        let exposure_time = exif
+            .get(ExifTag::ExposureTime)
+            .filter(|x| x.to_f64().is_some_and(|t| t > 0.0))
+            .and_then(|x| match x {
+                EntryValue::URational(v) => Some(*v),
+                // Written as SRATIONAL by some software
+                EntryValue::IRational(v) => Some((*v).into()),
+                _ => None,
+            })
+            .or_else(|| {
+                exif.get(ExifTag::ShutterSpeedValue)
+                    .and_then(EntryValue::to_f64)
+                    .and_then(apex_exposure_time)
+            });
+        let aperture = number(ExifTag::FNumber).or_else(|| {
+            exif.get(ExifTag::ApertureValue)
+                .and_then(EntryValue::to_f64)
+                .map(|av| (2f64.powf(av / 2.0) * 10.0).round() / 10.0)
+        });
+
+        CaptureSettings {
+            exposure_time,
+            aperture,
+            iso: iso(exif),
+            focal_length: number(ExifTag::FocalLength),
+            focal_length_35mm: number(ExifTag::FocalLengthIn35mmFilm).map(|x| x as u16),
+            exposure_bias: exif
+                .get(ExifTag::ExposureBiasValue)
+                .and_then(EntryValue::to_f64),
+            flash_fired: exif.get_enum::<Flash>().ok().flatten().map(|x| x.fired()),
+            lens_make: text(ExifTag::LensMake),
+            lens_model: text(ExifTag::LensModel),
+            lens_spec: exif
+                .get(ExifTag::LensSpecification)
+                .or_else(|| exif.get(ExifTag::LensInfo))
+                .and_then(lens_spec),
+            body_serial_number: text(ExifTag::BodySerialNumber),
+        }
+    }
+}
+
+/// Exposure time of an APEX time value, i.e. `2^-tv` seconds, as `1/n` for
+/// times shorter than a second, rounded to 1/10 second otherwise.
+fn apex_exposure_time(tv: f64) -> Option<URational> {
+    let t = 2f64.powf(-tv);
+    if !t.is_finite() || t <= 0.0 || t > u32::MAX as f64 / 10.0 {
+        return None;
+    }
+    let r = if t < 1.0 {
+        URational::from((1, (1.0 / t).round() as u32))
+    } else {
+        let n = (t * 10.0).round() as u32;
+        if n % 10 == 0 {
+            (n / 10, 1).into()
+        } else {
+            (n, 10).into()
+        }
+    };
+    Some(r)
+}
+
+fn iso(exif: &Exif) -> Option<u32> {
+    let get = |tag| exif.get(tag).and_then(|x| x.as_u32()).filter(|x| *x > 0);
+    let ratings = exif
+        .get(ExifTag::ISOSpeedRatings)
+        .and_then(|x| match x {
+            EntryValue::U16Array(v) => v.first().copied(),
+            x => x.as_u16(),
+        })
+        .filter(|x| *x > 0)
+        .map(u32::from);
+
+    // SensitivityType 2, 4, 6 & 7: RecommendedExposureIndex is recorded
+    let sensitivity_type = exif.get(ExifTag::SensitivityType).and_then(|x| x.as_u16());
+    if matches!(sensitivity_type, Some(2 | 4 | 6 | 7)) {
+        if let Some(v) = get(ExifTag::RecommendedExposureIndex) {
+            return Some(v);
+        }
+    }
+    if ratings.is_some_and(|x| x < u16::MAX as u32) {
+        return ratings;
+    }
+
+    get(ExifTag::RecommendedExposureIndex)
+        .or_else(|| get(ExifTag::ISOSpeed))
+        .or_else(|| get(ExifTag::StandardOutputSensitivity))
+        .or(ratings)
+}
+
+fn lens_spec(v: &EntryValue) -> Option<LensSpec> {
+    let [a, b, c, d] = v.as_urational_array()? else {
+        return None;
+    };
+    let value = |x: &URational| Some(x.as_float()).filter(|x| x.is_finite() && *x > 0.0);
+    Some(LensSpec {
+        min_focal_length: value(a),
+        max_focal_length: value(b),
+        min_f_number_wide: value(c),
+        min_f_number_tele: value(d),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{exif::input_into_iter, ExifWriter, IfdGroup};
+    use test_case::test_case;
+
+    fn settings(entries: &[(ExifTag, EntryValue)]) -> CaptureSettings {
+        let mut writer = ExifWriter::new();
+        for (tag, v) in entries {
+            writer.set(tag.group(), *tag, v.clone());
+        }
+        let exif: Exif = input_into_iter(writer.write().unwrap(), None)
+            .unwrap()
+            .into();
+        exif.capture_settings()
+    }
+
+    #[test]
+    fn capture_settings() {
+        let s = settings(&[
+            (
+                ExifTag::ExposureTime,
+                EntryValue::URational((1, 250).into()),
+            ),
+            (ExifTag::ShutterSpeedValue, (6, 1).into()),
+            (ExifTag::FNumber, (28, 10).into()),
+            (ExifTag::ApertureValue, (4, 1).into()),
+            (ExifTag::FocalLength, (35, 1).into()),
+            (ExifTag::FocalLengthIn35mmFilm, 52_u16.into()),
+            (ExifTag::ExposureBiasValue, (-2, 3).into()),
+            (ExifTag::Flash, 0x19_u16.into()),
+            (ExifTag::LensMake, "Fujifilm".into()),
+            (ExifTag::LensModel, "XF35mmF1.4 R ".into()),
+            (ExifTag::BodySerialNumber, "".into()),
+            (
+                ExifTag::LensSpecification,
+                EntryValue::URationalArray(vec![
+                    (35, 1).into(),
+                    (35, 1).into(),
+                    (14, 10).into(),
+                    (0, 0).into(),
+                ]),
+            ),
+        ]);
+        assert_eq!(s.exposure_time, Some((1, 250).into()));
+        assert_eq!(s.exposure_duration(), Some(Duration::from_millis(4)));
+        assert_eq!(s.aperture, Some(2.8));
+        assert_eq!(s.focal_length, Some(35.0));
+        assert_eq!(s.focal_length_35mm, Some(52));
+        assert_eq!(s.exposure_bias, Some(-2.0 / 3.0));
+        assert_eq!(s.flash_fired, Some(true));
+        assert_eq!(s.lens_make.as_deref(), Some("Fujifilm"));
+        assert_eq!(s.lens_model.as_deref(), Some("XF35mmF1.4 R"));
+        assert_eq!(s.body_serial_number, None);
+        assert_eq!(
+            s.lens_spec,
+            Some(LensSpec {
+                min_focal_length: Some(35.0),
+                max_focal_length: Some(35.0),
+                min_f_number_wide: Some(1.4),
+                min_f_number_tele: None,
+            })
+        );
+    }
+
+    #[test]
+    fn apex_fallback() {
+        let s = settings(&[
+            (ExifTag::ExposureTime, (0, 1).into()),
+            (ExifTag::ShutterSpeedValue, (6965784, 1000000).into()),
+            (ExifTag::ApertureValue, (297, 100).into()),
+        ]);
+        assert_eq!(s.exposure_time, Some((1, 125).into()));
+        assert_eq!(s.aperture, Some(2.8));
+
+        let s = settings(&[(ExifTag::ShutterSpeedValue, (-3, 2).into())]);
+        assert_eq!(s.exposure_time, Some((28, 10).into()));
+        assert_eq!(s.exposure_duration(), Some(Duration::from_millis(2800)));
+
+        let s = settings(&[(ExifTag::ShutterSpeedValue, (-1, 1).into())]);
+        assert_eq!(s.exposure_time, Some((2, 1).into()));
+        assert_eq!(s.aperture, None);
+    }
+
+    #[test_case(None, Some(200), Some(400), None => Some(200); "ratings")]
+    #[test_case(Some(2), Some(200), Some(400), None => Some(400); "rei")]
+    #[test_case(Some(3), Some(200), Some(400), Some(250) => Some(200); "iso speed type")]
+    #[test_case(Some(2), Some(200), None, None => Some(200); "rei missing")]
+    #[test_case(None, Some(65535), Some(102400), None => Some(102400); "saturated")]
+    #[test_case(Some(3), Some(65535), None, Some(80000) => Some(80000); "saturated iso speed")]
+    #[test_case(None, Some(65535), None, None => Some(65535); "saturated only")]
+    #[test_case(None, None, None, Some(800) => Some(800); "iso speed only")]
+    fn iso(
+        sensitivity_type: Option<u16>,
+        ratings: Option<u16>,
+        rei: Option<u32>,
+        iso_speed: Option<u32>,
+    ) -> Option<u32> {
+        let mut entries = Vec::new();
+        if let Some(v) = sensitivity_type {
+            entries.push((ExifTag::SensitivityType, v.into()));
+        }
+        if let Some(v) = ratings {
+            entries.push((ExifTag::ISOSpeedRatings, v.into()));
+        }
+        if let Some(v) = rei {
+            entries.push((ExifTag::RecommendedExposureIndex, v.into()));
+        }
+        if let Some(v) = iso_speed {
+            entries.push((ExifTag::ISOSpeed, v.into()));
+        }
+        settings(&entries).iso
+    }
+
+    #[test]
+    fn empty() {
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, ExifTag::Make, "Canon".into());
+        let exif: Exif = input_into_iter(writer.write().unwrap(), None)
+            .unwrap()
+            .into();
+        assert_eq!(exif.capture_settings(), CaptureSettings::default());
+    }
+}
@@ -0,0 +1,320 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+use crate::{EntryValue, Exif, ExifTag};
+
+/// A date/time of an image, with the sub-seconds of the `SubSecTime*` tags
+/// merged in, see [`Exif::date_time`] & [`Exif::capture_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExifDateTime {
+    /// The date/time recorded by the camera (i.e. in the local time of the
+    /// camera), including sub-seconds.
+    pub local: NaiveDateTime,
+
+    /// UTC offset of the camera, `None` if unknown.
+    pub offset: Option<FixedOffset>,
+
+    /// Where the offset comes from.
+    pub offset_source: OffsetSource,
+}
+
+/// Source of the UTC offset of an [`ExifDateTime`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetSource {
+    /// The offset is unknown.
+    Unknown,
+    /// The `OffsetTime*` tag of the date/time tag.
+    OffsetTime,
+    /// Inferred by comparing the date/time with the GPS date/time (UTC), see
+    /// [`Exif::capture_time`].
+    Gps,
+    /// The date/time is recorded in UTC, e.g. the creation time of a
+    /// QuickTime/MP4 (`mvhd` box) or Matroska file (see
+    /// [`crate::MediaMetadata`]), so `local` is the UTC time rather than the
+    /// local time of the camera.
+    Utc,
+}
+
+impl ExifDateTime {
+    /// Returns the date/time with its offset, `None` if the offset is
+    /// unknown.
+    pub fn to_datetime(self) -> Option<DateTime<FixedOffset>> {
+        self.local.and_local_timezone(self.offset?).single()
+    }
+
+    /// Returns the absolute instant, `None` if the offset is unknown.
+    pub fn to_utc(self) -> Option<DateTime<Utc>> {
+        self.to_datetime().map(|x| x.to_utc())
+    }
+}
+
+/// Maximum offset from UTC (UTC+14:00 & UTC-12:00 in practice).
+const MAX_OFFSET_SECS: i64 = 14 * 3600;
+/// Offsets in use are whole or half hours, except for the ones in
+/// [`QUARTER_HOUR_OFFSETS`].
+const HALF_HOUR_SECS: i64 = 30 * 60;
+/// UTC+05:45, UTC+08:45 & UTC+12:45.
+const QUARTER_HOUR_OFFSETS: [i64; 3] = [20700, 31500, 45900];
+/// Max difference between the inferred offset and the difference of the
+/// date/times, so that GPS date/times which are a few minutes off (e.g. the
+/// last fix) can still be used.
+const OFFSET_TOLERANCE_SECS: i64 = 5 * 60;
+
+impl Exif {
+    /// Get the date/time of a date/time `tag` of the main image
+    /// (`DateTimeOriginal` & `CreateDate` in the Exif sub-IFD, or
+    /// `ModifyDate` in IFD0), with the sub-seconds of its `SubSecTime*` tag
+    /// (`SubSecTimeOriginal`, `SubSecTimeDigitized` or `SubSecTime`), and the
+    /// offset of its `OffsetTime*` tag.
+    ///
+    /// Returns `None` for other tags.
+    pub fn date_time(&self, tag: ExifTag) -> Option<ExifDateTime> {
+        let subsec_tag = match tag {
+            ExifTag::DateTimeOriginal => ExifTag::SubSecTimeOriginal,
+            ExifTag::CreateDate => ExifTag::SubSecTimeDigitized,
+            ExifTag::ModifyDate => ExifTag::SubSecTime,
+            _ => return None,
+        };
+        let (local, offset) = match self.get(tag)? {
+            EntryValue::Time(t) => (t.naive_local(), Some(*t.offset())),
+            EntryValue::NaiveDateTime(t) => (*t, None),
+            _ => return None,
+        };
+        let local = match self.get(subsec_tag).and_then(|x| parse_subsec(x.as_str()?)) {
+            Some(nanos) => local.with_nanosecond(nanos).unwrap_or(local),
+            None => local,
+        };
+        Some(ExifDateTime {
+            local,
+            offset,
+            offset_source: if offset.is_some() {
+                OffsetSource::OffsetTime
+            } else {
+                OffsetSource::Unknown
+            },
+        })
+    }
+
+    /// Get the time of the shot, i.e. [`Self::date_time`] of
+    /// `DateTimeOriginal`, or `CreateDate` if it's missing.
+    ///
+    /// If there is no `OffsetTime*` tag, the offset is inferred from the GPS
+    /// date/time (see [`Self::gps_date_time`]): the difference between the
+    /// two date/times is rounded to the nearest offset in use (a whole or
+    /// half hour, or one of UTC+05:45, UTC+08:45 & UTC+12:45), and used if
+    /// it's within 5 minutes of that offset, and within ±14 hours.
+    ///
+    /// Such an offset is approximate: the GPS date/time is the time of the
+    /// last fix, which may be older than the shot. A fix which is more than 5
+    /// minutes off is usually rejected, but one which is about half an hour
+    /// (or more) off may still yield a wrong offset.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut parser = MediaParser::new();
+    ///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let exif: Exif = iter.into();
+    ///
+    ///     let t = exif.capture_time().unwrap();
+    ///     assert_eq!(t.local.to_string(), "2023-07-09 20:36:33.616");
+    ///     assert_eq!(t.offset_source, OffsetSource::OffsetTime);
+    ///     assert_eq!(
+    ///         t.to_datetime().unwrap().to_rfc3339(),
+    ///         "2023-07-09T20:36:33.616+08:00"
+    ///     );
+    ///     assert_eq!(
+    ///         exif.gps_date_time().unwrap().to_rfc3339(),
+    ///         "2023-07-09T12:36:33+00:00"
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn capture_time(&self) -> Option<ExifDateTime> {
+        let mut t = self
+            .date_time(ExifTag::DateTimeOriginal)
+            .or_else(|| self.date_time(ExifTag::CreateDate))?;
+        if t.offset.is_none() {
+            if let Some(offset) = self.gps_date_time().and_then(|x| infer_offset(t.local, x)) {
+                t.offset = Some(offset);
+                t.offset_source = OffsetSource::Gps;
+            }
+        }
+        Some(t)
+    }
+
+    /// Get the UTC date/time of the GPS fix from `GPSDateStamp` &
+    /// `GPSTimeStamp`, including the fraction of seconds.
+    pub fn gps_date_time(&self) -> Option<DateTime<Utc>> {
+        let date = self.get(ExifTag::GPSDateStamp)?.as_str()?;
+        let date = NaiveDate::parse_from_str(date.trim(), "%Y:%m:%d").ok()?;
+
+        let time: Vec<f64> = match self.get(ExifTag::GPSTimeStamp)? {
+            EntryValue::URationalArray(v) => v.iter().map(|x| x.as_float()).collect(),
+            EntryValue::IRationalArray(v) => v.iter().map(|x| x.as_float()).collect(),
+            _ => return None,
+        };
+        let [h, m, s] = time[..] else {
+            return None;
+        };
+        if !(h.is_finite() && m.is_finite() && s.is_finite()) {
+            return None;
+        }
+        let secs = s.trunc() as u32;
+        let nanos = (s.fract() * 1e9).round() as u32;
+        let time = NaiveTime::from_hms_nano_opt(h as u32, m as u32, secs, nanos.min(999_999_999))?;
+        Some(date.and_time(time).and_utc())
+    }
+}
+
+/// Infers the UTC offset of the local date/time `local`, from the UTC
+/// date/time `utc` of (about) the same moment.
+fn infer_offset(local: NaiveDateTime, utc: DateTime<Utc>) -> Option<FixedOffset> {
+    let diff = (local - utc.naive_utc()).num_seconds();
+    let half_hours = (diff as f64 / HALF_HOUR_SECS as f64).round() as i64 * HALF_HOUR_SECS;
+    let offset = QUARTER_HOUR_OFFSETS
+        .into_iter()
+        .chain([half_hours])
+        .min_by_key(|x| (diff - x).abs())?;
+    if (diff - offset).abs() > OFFSET_TOLERANCE_SECS || offset.abs() > MAX_OFFSET_SECS {
+        return None;
+    }
+    FixedOffset::east_opt(offset as i32)
+}
+
+/// Parses the sub-seconds of a `SubSecTime*` tag, e.g. "616" => 0.616 second,
+/// "06" => 0.06 second, and returns the nanoseconds.
+fn parse_subsec(s: &str) -> Option<u32> {
+    let digits: String = s
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .take(9)
+        .collect();
+    if digits.is_empty() {
+        return None;
+    }
+    let n: u32 = digits.parse().ok()?;
+    Some(n * 10u32.pow(9 - digits.len() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{exif::input_into_iter, ExifWriter};
+    use test_case::test_case;
+
+    fn exif(entries: &[(ExifTag, EntryValue)]) -> Exif {
+        let mut writer = ExifWriter::new();
+        for (tag, v) in entries {
+            writer.set(tag.group(), *tag, v.clone());
+        }
+        input_into_iter(writer.write().unwrap(), None)
+            .unwrap()
+            .into()
+    }
+
+    fn naive(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").unwrap()
+    }
+
+    fn gps_time(h: u32, m: u32, s: (u32, u32)) -> EntryValue {
+        EntryValue::URationalArray(vec![(h, 1).into(), (m, 1).into(), s.into()])
+    }
+
+    #[test_case("616" => Some(616_000_000))]
+    #[test_case("06" => Some(60_000_000))]
+    #[test_case("5 " => Some(500_000_000))]
+    #[test_case("1234567890" => Some(123_456_789))]
+    #[test_case("   " => None)]
+    fn subsec(s: &str) -> Option<u32> {
+        parse_subsec(s)
+    }
+
+    #[test]
+    fn date_time() {
+        let exif = exif(&[
+            (
+                ExifTag::DateTimeOriginal,
+                naive("2024-02-29 23:59:59").into(),
+            ),
+            (ExifTag::SubSecTimeOriginal, "25".into()),
+            (ExifTag::OffsetTimeOriginal, "-03:30".into()),
+            (ExifTag::CreateDate, naive("2024-02-29 23:59:58").into()),
+            (ExifTag::SubSecTimeDigitized, "".into()),
+        ]);
+        let t = exif.date_time(ExifTag::DateTimeOriginal).unwrap();
+        assert_eq!(t.local, naive("2024-02-29 23:59:59.25"));
+        assert_eq!(t.offset_source, OffsetSource::OffsetTime);
+        assert_eq!(
+            t.to_utc().unwrap().to_rfc3339(),
+            "2024-03-01T03:29:59.250+00:00"
+        );
+
+        let t = exif.date_time(ExifTag::CreateDate).unwrap();
+        assert_eq!(t.local, naive("2024-02-29 23:59:58"));
+        assert_eq!(t.offset, None);
+        assert_eq!(t.offset_source, OffsetSource::Unknown);
+        assert_eq!(t.to_utc(), None);
+
+        assert!(exif.date_time(ExifTag::ModifyDate).is_none());
+        assert!(exif.date_time(ExifTag::Make).is_none());
+    }
+
+    #[test_case("2024:03:01", gps_time(2, 59, (1550, 100)), Some(9 * 3600); "east")]
+    // The GPS fix is a few minutes earlier
+    #[test_case("2024:03:01", gps_time(2, 55, (0, 1)), Some(9 * 3600); "late fix")]
+    #[test_case("2024:03:01", gps_time(6, 14, (0, 1)), Some(5 * 3600 + 2700); "quarter hour")]
+    // The GPS fix is too old, +09:15 isn't an offset in use
+    #[test_case("2024:03:01", gps_time(2, 44, (15, 1)), None; "stale fix")]
+    #[test_case("2024:03:01", gps_time(2, 51, (0, 1)), None; "stale fix 8 minutes")]
+    #[test_case("2024:03:01", gps_time(17, 30, (10, 1)), Some(-5 * 3600 - 1800); "west")]
+    #[test_case("2024:02:29", gps_time(20, 0, (0, 1)), None; "out of range")]
+    fn infer_offset_from_gps(date: &str, gps: EntryValue, expect: Option<i32>) {
+        let exif = exif(&[
+            (
+                ExifTag::DateTimeOriginal,
+                naive("2024-03-01 11:59:15").into(),
+            ),
+            (ExifTag::GPSDateStamp, date.into()),
+            (ExifTag::GPSTimeStamp, gps),
+        ]);
+        let t = exif.capture_time().unwrap();
+        assert_eq!(t.offset, expect.and_then(FixedOffset::east_opt));
+        assert_eq!(
+            t.offset_source,
+            if expect.is_some() {
+                OffsetSource::Gps
+            } else {
+                OffsetSource::Unknown
+            }
+        );
+    }
+
+    #[test]
+    fn gps_date_time() {
+        let exif = exif(&[
+            (ExifTag::GPSDateStamp, "2023:07:09".into()),
+            (ExifTag::GPSTimeStamp, gps_time(12, 36, (3350, 100))),
+        ]);
+        assert_eq!(
+            exif.gps_date_time().unwrap().to_rfc3339(),
+            "2023-07-09T12:36:33.500+00:00"
+        );
+        // No date/time tags
+        assert!(exif.capture_time().is_none());
+    }
+
+    #[test]
+    fn capture_time_fallback() {
+        let exif = exif(&[
+            (ExifTag::CreateDate, naive("2024-03-01 11:59:15").into()),
+            (ExifTag::SubSecTimeDigitized, "5".into()),
+        ]);
+        let t = exif.capture_time().unwrap();
+        assert_eq!(t.local, naive("2024-03-01 11:59:15.5"));
+    }
+}
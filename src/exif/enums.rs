@@ -0,0 +1,503 @@
+//! Typed values of the enumerated Exif tags, see
+//! https://exiftool.org/TagNames/EXIF.html
+
+use std::fmt::Display;
+
+use crate::{EntryValue, ExifTag};
+
+/// A typed value of an enumerated Exif tag, such as [`Orientation`] or
+/// [`Flash`].
+///
+/// Values out of the range defined by the specification are rejected when
+/// converting from the raw `u16` value.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+///     let iter: ExifIter = parser.parse(ms)?;
+///     let exif: Exif = iter.into();
+///
+///     let flash = exif.get_enum::<Flash>()?.unwrap();
+///     assert!(!flash.fired());
+///     assert_eq!(flash.mode(), FlashMode::Off);
+///     assert_eq!(exif.get_enum::<MeteringMode>()?, Some(MeteringMode::Average));
+///     assert_eq!(MeteringMode::try_from(2)?, MeteringMode::CenterWeightedAverage);
+///     Orientation::try_from(9).unwrap_err();
+///     Ok(())
+/// }
+/// ```
+pub trait ExifEnum: TryFrom<u16, Error = crate::Error> + Copy {
+    /// The tag the value is stored in.
+    const TAG: ExifTag;
+
+    /// Returns the raw value.
+    fn value(self) -> u16;
+
+    /// Decodes an entry value, e.g. the value of [`Self::TAG`].
+    ///
+    /// Besides `SHORT`, integer values stored in other unsigned types are
+    /// accepted too.
+    fn from_entry(value: &EntryValue) -> crate::Result<Self> {
+        let v = match value {
+            EntryValue::U8(v) => Some(*v as u16),
+            EntryValue::U16(v) => Some(*v),
+            EntryValue::U32(v) => u16::try_from(*v).ok(),
+            _ => None,
+        };
+        match v {
+            Some(v) => Self::try_from(v),
+            None => Err(format!("invalid {} value: {value}", Self::TAG).into()),
+        }
+    }
+}
+
+/// Generates an enum of the values of a tag, the conversions from & to the
+/// raw value, the [`ExifEnum`] impl, and a `Display` impl printing the
+/// description of the value (the same as exiftool).
+///
+/// The enums are `#[non_exhaustive]`, so that values can be added when they
+/// are defined by the specification (or used by vendors).
+macro_rules! exif_enum {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident: $tag:ident {
+            $($(#[$vmeta:meta])* $variant:ident = $value:literal => $text:literal,)*
+        }
+    )*) => {$(
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$vmeta])* $variant = $value,)*
+        }
+
+        impl TryFrom<u16> for $name {
+            type Error = crate::Error;
+
+            fn try_from(v: u16) -> Result<Self, Self::Error> {
+                match v {
+                    $($value => Ok(Self::$variant),)*
+                    _ => Err(format!(concat!("invalid ", stringify!($tag), " value: {}"), v).into()),
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let s = match self {
+                    $(Self::$variant => $text,)*
+                };
+                Display::fmt(s, f)
+            }
+        }
+
+        impl From<$name> for u16 {
+            fn from(v: $name) -> Self {
+                v as u16
+            }
+        }
+
+        impl ExifEnum for $name {
+            const TAG: ExifTag = ExifTag::$tag;
+
+            fn value(self) -> u16 {
+                self as u16
+            }
+        }
+    )*};
+}
+
+exif_enum! {
+    /// [`ExifTag::Orientation`]: how the stored image should be transformed
+    /// to be displayed.
+    Orientation: Orientation {
+        Horizontal = 1 => "Horizontal (normal)",
+        MirrorHorizontal = 2 => "Mirror horizontal",
+        Rotate180 = 3 => "Rotate 180",
+        MirrorVertical = 4 => "Mirror vertical",
+        MirrorHorizontalRotate270 = 5 => "Mirror horizontal and rotate 270 CW",
+        Rotate90 = 6 => "Rotate 90 CW",
+        MirrorHorizontalRotate90 = 7 => "Mirror horizontal and rotate 90 CW",
+        Rotate270 = 8 => "Rotate 270 CW",
+    }
+
+    /// [`ExifTag::ResolutionUnit`], also used by
+    /// [`ExifTag::FocalPlaneResolutionUnit`].
+    ResolutionUnit: ResolutionUnit {
+        None = 1 => "None",
+        Inch = 2 => "inches",
+        Centimeter = 3 => "cm",
+    }
+
+    /// [`ExifTag::ExposureProgram`]
+    ExposureProgram: ExposureProgram {
+        NotDefined = 0 => "Not Defined",
+        Manual = 1 => "Manual",
+        Normal = 2 => "Program AE",
+        AperturePriority = 3 => "Aperture-priority AE",
+        ShutterPriority = 4 => "Shutter speed priority AE",
+        Creative = 5 => "Creative (Slow speed)",
+        Action = 6 => "Action (High speed)",
+        Portrait = 7 => "Portrait",
+        Landscape = 8 => "Landscape",
+    }
+
+    /// [`ExifTag::MeteringMode`]
+    MeteringMode: MeteringMode {
+        Unknown = 0 => "Unknown",
+        Average = 1 => "Average",
+        CenterWeightedAverage = 2 => "Center-weighted average",
+        Spot = 3 => "Spot",
+        MultiSpot = 4 => "Multi-spot",
+        Pattern = 5 => "Multi-segment",
+        Partial = 6 => "Partial",
+        Other = 255 => "Other",
+    }
+
+    /// [`ExifTag::LightSource`]
+    LightSource: LightSource {
+        Unknown = 0 => "Unknown",
+        Daylight = 1 => "Daylight",
+        Fluorescent = 2 => "Fluorescent",
+        Tungsten = 3 => "Tungsten (Incandescent)",
+        Flash = 4 => "Flash",
+        FineWeather = 9 => "Fine Weather",
+        Cloudy = 10 => "Cloudy",
+        Shade = 11 => "Shade",
+        DaylightFluorescent = 12 => "Daylight Fluorescent",
+        DayWhiteFluorescent = 13 => "Day White Fluorescent",
+        CoolWhiteFluorescent = 14 => "Cool White Fluorescent",
+        WhiteFluorescent = 15 => "White Fluorescent",
+        WarmWhiteFluorescent = 16 => "Warm White Fluorescent",
+        StandardLightA = 17 => "Standard Light A",
+        StandardLightB = 18 => "Standard Light B",
+        StandardLightC = 19 => "Standard Light C",
+        D55 = 20 => "D55",
+        D65 = 21 => "D65",
+        D75 = 22 => "D75",
+        D50 = 23 => "D50",
+        IsoStudioTungsten = 24 => "ISO Studio Tungsten",
+        Other = 255 => "Other",
+    }
+
+    /// [`ExifTag::ColorSpace`]
+    ColorSpace: ColorSpace {
+        Srgb = 1 => "sRGB",
+        Uncalibrated = 0xffff => "Uncalibrated",
+    }
+
+    /// [`ExifTag::SensingMethod`]
+    SensingMethod: SensingMethod {
+        NotDefined = 1 => "Not defined",
+        OneChipColorArea = 2 => "One-chip color area",
+        TwoChipColorArea = 3 => "Two-chip color area",
+        ThreeChipColorArea = 4 => "Three-chip color area",
+        ColorSequentialArea = 5 => "Color sequential area",
+        Trilinear = 7 => "Trilinear",
+        ColorSequentialLinear = 8 => "Color sequential linear",
+    }
+
+    /// [`ExifTag::CustomRendered`], 2-8 are written by Apple devices.
+    CustomRendered: CustomRendered {
+        Normal = 0 => "Normal",
+        Custom = 1 => "Custom",
+        HdrNoOriginal = 2 => "HDR (no original saved)",
+        HdrOriginalSaved = 3 => "HDR (original saved)",
+        OriginalForHdr = 4 => "Original (for HDR)",
+        Panorama = 6 => "Panorama",
+        PortraitHdr = 7 => "Portrait HDR",
+        Portrait = 8 => "Portrait",
+    }
+
+    /// [`ExifTag::ExposureMode`]
+    ExposureMode: ExposureMode {
+        Auto = 0 => "Auto",
+        Manual = 1 => "Manual",
+        AutoBracket = 2 => "Auto bracket",
+    }
+
+    /// [`ExifTag::WhiteBalanceMode`]
+    WhiteBalance: WhiteBalanceMode {
+        Auto = 0 => "Auto",
+        Manual = 1 => "Manual",
+    }
+
+    /// [`ExifTag::SceneCaptureType`]
+    SceneCaptureType: SceneCaptureType {
+        Standard = 0 => "Standard",
+        Landscape = 1 => "Landscape",
+        Portrait = 2 => "Portrait",
+        NightScene = 3 => "Night",
+    }
+
+    /// [`ExifTag::GainControl`]
+    GainControl: GainControl {
+        None = 0 => "None",
+        LowGainUp = 1 => "Low gain up",
+        HighGainUp = 2 => "High gain up",
+        LowGainDown = 3 => "Low gain down",
+        HighGainDown = 4 => "High gain down",
+    }
+
+    /// [`ExifTag::Contrast`]
+    Contrast: Contrast {
+        Normal = 0 => "Normal",
+        Soft = 1 => "Low",
+        Hard = 2 => "High",
+    }
+
+    /// [`ExifTag::Saturation`]
+    Saturation: Saturation {
+        Normal = 0 => "Normal",
+        Low = 1 => "Low",
+        High = 2 => "High",
+    }
+
+    /// [`ExifTag::Sharpness`]
+    Sharpness: Sharpness {
+        Normal = 0 => "Normal",
+        Soft = 1 => "Soft",
+        Hard = 2 => "Hard",
+    }
+}
+
+/// [`ExifTag::Flash`]: a bitfield of the flash status & mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Flash(u16);
+
+/// Status of the returned light of a [`Flash`], bits 1-2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FlashReturn {
+    /// No strobe return detection function
+    NotAvailable,
+    /// Strobe return light not detected
+    NotDetected,
+    /// Strobe return light detected
+    Detected,
+}
+
+/// Firing mode of a [`Flash`], bits 3-4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FlashMode {
+    Unknown,
+    /// Compulsory flash firing
+    On,
+    /// Compulsory flash suppression
+    Off,
+    Auto,
+}
+
+impl Flash {
+    /// Whether the flash fired.
+    pub fn fired(self) -> bool {
+        self.0 & 0x01 != 0
+    }
+
+    pub fn return_light(self) -> FlashReturn {
+        match (self.0 >> 1) & 0x03 {
+            2 => FlashReturn::NotDetected,
+            3 => FlashReturn::Detected,
+            _ => FlashReturn::NotAvailable,
+        }
+    }
+
+    pub fn mode(self) -> FlashMode {
+        match (self.0 >> 3) & 0x03 {
+            1 => FlashMode::On,
+            2 => FlashMode::Off,
+            3 => FlashMode::Auto,
+            _ => FlashMode::Unknown,
+        }
+    }
+
+    /// Whether the camera has a flash function.
+    pub fn function_present(self) -> bool {
+        self.0 & 0x20 == 0
+    }
+
+    pub fn red_eye_reduction(self) -> bool {
+        self.0 & 0x40 != 0
+    }
+}
+
+impl TryFrom<u16> for Flash {
+    type Error = crate::Error;
+
+    /// Bits above bit 6, and the reserved return light value (bits 1-2 ==
+    /// 1), are rejected.
+    fn try_from(v: u16) -> Result<Self, Self::Error> {
+        if v & !0x7f != 0 || (v >> 1) & 0x03 == 1 {
+            return Err(format!("invalid Flash value: {v:#x}").into());
+        }
+        Ok(Flash(v))
+    }
+}
+
+impl Display for Flash {
+    /// Prints the flash status like exiftool, e.g. `Fired, Return detected`,
+    /// `Auto, Did not fire`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        match self.mode() {
+            FlashMode::Unknown => (),
+            FlashMode::On => parts.push("On"),
+            FlashMode::Off => parts.push("Off"),
+            FlashMode::Auto => parts.push("Auto"),
+        }
+        if self.fired() {
+            parts.push("Fired");
+        } else if !parts.is_empty() {
+            parts.push("Did not fire");
+        } else if self.function_present() {
+            parts.push("No Flash");
+        }
+        if self.red_eye_reduction() {
+            parts.push("Red-eye reduction");
+        }
+        match self.return_light() {
+            FlashReturn::NotAvailable => (),
+            FlashReturn::NotDetected => parts.push("Return not detected"),
+            FlashReturn::Detected => parts.push("Return detected"),
+        }
+        if !self.function_present() {
+            parts.push("No flash function");
+        }
+        Display::fmt(&parts.join(", "), f)
+    }
+}
+
+impl From<Flash> for u16 {
+    fn from(v: Flash) -> Self {
+        v.0
+    }
+}
+
+impl ExifEnum for Flash {
+    const TAG: ExifTag = ExifTag::Flash;
+
+    fn value(self) -> u16 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(1, Some(Orientation::Horizontal))]
+    #[test_case(6, Some(Orientation::Rotate90))]
+    #[test_case(8, Some(Orientation::Rotate270))]
+    #[test_case(0, None)]
+    #[test_case(9, None)]
+    fn orientation(v: u16, expect: Option<Orientation>) {
+        assert_eq!(Orientation::try_from(v).ok(), expect);
+        if let Some(o) = expect {
+            assert_eq!(o.value(), v);
+            assert_eq!(u16::from(o), v);
+        }
+    }
+
+    #[test_case(0xffff, Some(ColorSpace::Uncalibrated))]
+    #[test_case(2, None)]
+    fn color_space(v: u16, expect: Option<ColorSpace>) {
+        assert_eq!(ColorSpace::try_from(v).ok(), expect);
+    }
+
+    #[test_case(16, Some(LightSource::WarmWhiteFluorescent))]
+    #[test_case(255, Some(LightSource::Other))]
+    #[test_case(5, None)]
+    #[test_case(25, None)]
+    fn light_source(v: u16, expect: Option<LightSource>) {
+        assert_eq!(LightSource::try_from(v).ok(), expect);
+    }
+
+    #[test_case(1, Some(CustomRendered::Custom))]
+    #[test_case(2, Some(CustomRendered::HdrNoOriginal))]
+    #[test_case(8, Some(CustomRendered::Portrait))]
+    #[test_case(5, None)]
+    #[test_case(9, None)]
+    fn custom_rendered(v: u16, expect: Option<CustomRendered>) {
+        assert_eq!(CustomRendered::try_from(v).ok(), expect);
+    }
+
+    #[test_case(
+        0x00,
+        false,
+        FlashReturn::NotAvailable,
+        FlashMode::Unknown,
+        true,
+        false
+    )]
+    #[test_case(0x10, false, FlashReturn::NotAvailable, FlashMode::Off, true, false)]
+    #[test_case(0x19, true, FlashReturn::NotAvailable, FlashMode::Auto, true, false)]
+    #[test_case(0x0f, true, FlashReturn::Detected, FlashMode::On, true, false)]
+    #[test_case(
+        0x20,
+        false,
+        FlashReturn::NotAvailable,
+        FlashMode::Unknown,
+        false,
+        false
+    )]
+    #[test_case(0x5d, true, FlashReturn::NotDetected, FlashMode::Auto, true, true)]
+    fn flash(v: u16, fired: bool, ret: FlashReturn, mode: FlashMode, present: bool, red_eye: bool) {
+        let flash = Flash::try_from(v).unwrap();
+        assert_eq!(flash.fired(), fired);
+        assert_eq!(flash.return_light(), ret);
+        assert_eq!(flash.mode(), mode);
+        assert_eq!(flash.function_present(), present);
+        assert_eq!(flash.red_eye_reduction(), red_eye);
+        assert_eq!(flash.value(), v);
+    }
+
+    #[test_case(0x00 => "No Flash")]
+    #[test_case(0x07 => "Fired, Return detected")]
+    #[test_case(0x10 => "Off, Did not fire")]
+    #[test_case(0x19 => "Auto, Fired")]
+    #[test_case(0x20 => "No flash function")]
+    #[test_case(0x5d => "Auto, Fired, Red-eye reduction, Return not detected")]
+    fn flash_display(v: u16) -> String {
+        Flash::try_from(v).unwrap().to_string()
+    }
+
+    #[test]
+    fn enum_display() {
+        assert_eq!(Orientation::Rotate90.to_string(), "Rotate 90 CW");
+        assert_eq!(ExposureProgram::Normal.to_string(), "Program AE");
+        assert_eq!(MeteringMode::Pattern.to_string(), "Multi-segment");
+        assert_eq!(ColorSpace::Srgb.to_string(), "sRGB");
+        assert_eq!(CustomRendered::PortraitHdr.to_string(), "Portrait HDR");
+    }
+
+    #[test_case(0x80)]
+    #[test_case(0x03)]
+    fn flash_invalid(v: u16) {
+        Flash::try_from(v).unwrap_err();
+    }
+
+    #[test]
+    fn from_entry() {
+        assert_eq!(
+            ExposureProgram::from_entry(&EntryValue::U16(3)).unwrap(),
+            ExposureProgram::AperturePriority
+        );
+        assert_eq!(
+            Contrast::from_entry(&EntryValue::U8(2)).unwrap(),
+            Contrast::Hard
+        );
+        assert_eq!(
+            ResolutionUnit::from_entry(&EntryValue::U32(3)).unwrap(),
+            ResolutionUnit::Centimeter
+        );
+        GainControl::from_entry(&EntryValue::U32(0x10000)).unwrap_err();
+        Sharpness::from_entry(&"0".into()).unwrap_err();
+    }
+}
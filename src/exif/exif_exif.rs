@@ -2,28 +2,46 @@ use nom::{
     branch::alt, bytes::streaming::tag, combinator, number::Endianness, sequence, IResult, Needed,
 };
 
-use crate::{EntryValue, ExifIter, ExifTag, GPSInfo, ParsedExifEntry};
+use std::collections::BTreeMap;
 
-use super::ifd::ParsedImageFileDirectory;
+use crate::{
+    CaptureSettings, EntryValue, ExifEnum, ExifIter, ExifTag, GPSInfo, IfdGroup, IfdId,
+    ParsedExifEntry, PrintValue,
+};
+
+use super::{ifd::ParsedImageFileDirectory, print::gps_ref_tag};
 
 /// Represents parsed Exif information, can be converted from an [`ExifIter`]
 /// like this: `let exif: Exif = iter.into()`.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Exif {
     ifds: Vec<ParsedImageFileDirectory>,
+    // sub-IFDs pointed by `SubIFDs`
+    sub_ifds: BTreeMap<IfdId, ParsedImageFileDirectory>,
     gps_info: Option<GPSInfo>,
+    // byte order of the source data
+    endian: Endianness,
+    // thumbnail image data referenced by IFD1
+    thumbnail: Option<Vec<u8>>,
 }
 
 impl Exif {
-    fn new(gps_info: Option<GPSInfo>) -> Exif {
+    fn new(gps_info: Option<GPSInfo>, endian: Endianness) -> Exif {
         Exif {
             ifds: Vec::new(),
+            sub_ifds: BTreeMap::new(),
             gps_info,
+            endian,
+            thumbnail: None,
         }
     }
 
     /// Get entry value for the specified `tag` in ifd0 (the main image).
     ///
+    /// The tag is looked up in the groups of its namespace, e.g.
+    /// `GPSLatitudeRef` in the GPS sub-IFD, `InteropIndex` in the Interop
+    /// sub-IFD, see [`ExifTag::from_code_in`].
+    ///
     /// *Note*:
     ///
     /// - The parsing error related to this tag won't be reported by this
@@ -53,7 +71,20 @@ impl Exif {
     ///       Ok(())
     ///   }
     pub fn get(&self, tag: ExifTag) -> Option<&EntryValue> {
-        self.get_by_ifd_tag_code(0, tag.code())
+        self.ifds.first().and_then(|x| x.get_tag(tag))
+    }
+
+    /// Get entry value for the specified `tag` in ifd0 (the main image), in
+    /// a human-readable form, see [`PrintValue`].
+    ///
+    /// GPS coordinates are printed with their reference, e.g. `22 deg 31'
+    /// 52.08" N`.
+    pub fn print_value(&self, tag: ExifTag) -> Option<PrintValue<'_>> {
+        let gps_ref = gps_ref_tag(tag)
+            .and_then(|x| self.get(x))
+            .and_then(|x| x.as_str());
+        self.get(tag)
+            .map(|v| PrintValue::new(Some(tag), v).gps_ref(gps_ref))
     }
 
     /// Get entry value for the specified `tag` in the specified `ifd`.
@@ -62,6 +93,11 @@ impl Exif {
     /// - 0: ifd0 (the main image)
     /// - 1: ifd1 (thumbnail image)
     ///
+    /// If the tag code exists in several groups of the IFD (e.g. 0x0001 in
+    /// both GPS & Interop sub-IFDs), the value of the first group in the
+    /// order of IFD0, Exif, GPS, Interop is returned. Use
+    /// [`Self::get_by_group_tag_code`] to get the value of a specific group.
+    ///
     /// *Note*:
     ///
     /// - The parsing error related to this tag won't be reported by this
@@ -92,6 +128,76 @@ impl Exif {
         self.ifds.get(ifd).and_then(|ifd| ifd.get(tag))
     }
 
+    /// Get entry value by a raw tag code in the specified IFD `group`, so that
+    /// the colliding tag codes of different groups can be told apart.
+    ///
+    /// [`IfdGroup::Ifd1`] is looked up in ifd1 (the thumbnail image), the
+    /// others are looked up in ifd0 (the main image) and its sub-IFDs.
+    ///
+    ///   ## Example
+    ///
+    ///   ```rust
+    ///   use nom_exif::*;
+    ///
+    ///   fn main() -> Result<()> {
+    ///       let mut parser = MediaParser::new();
+    ///       
+    ///       let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+    ///       let iter: ExifIter = parser.parse(ms)?;
+    ///       let exif: Exif = iter.into();
+    ///
+    ///       assert_eq!(exif.get_by_group_tag_code(IfdGroup::Gps, 0x0001).unwrap(), &"N".into());
+    ///       assert_eq!(exif.get_by_group_tag_code(IfdGroup::Interop, 0x0001).unwrap(), &"R98".into());
+    ///       Ok(())
+    ///   }
+    ///   ```
+    pub fn get_by_group_tag_code(&self, group: IfdGroup, tag: u16) -> Option<&EntryValue> {
+        let idx = if group == IfdGroup::Ifd1 { 1 } else { 0 };
+        self.ifds.get(idx).and_then(|x| x.get_in(group, tag))
+    }
+
+    /// Get entry value for the specified `tag` in the image directory `ifd`,
+    /// which may be a sub-IFD pointed by `SubIFDs`, see [`IfdId`].
+    ///
+    /// Only the first 4 sub-IFDs of a `SubIFDs` entry are loaded, so `None`
+    /// is returned for the sub-IFDs after them.
+    ///
+    ///   ## Example
+    ///
+    ///   ```rust
+    ///   use nom_exif::*;
+    ///
+    ///   fn main() -> Result<()> {
+    ///       let mut parser = MediaParser::new();
+    ///       
+    ///       let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+    ///       let iter: ExifIter = parser.parse(ms)?;
+    ///       let exif: Exif = iter.into();
+    ///
+    ///       assert_eq!(exif.get_in(IfdId::new(1), ExifTag::ExifImageWidth).unwrap(), &240_u32.into());
+    ///       assert!(exif.get_in(IfdId::IFD0.with_sub_ifd(0), ExifTag::ImageWidth).is_none());
+    ///       Ok(())
+    ///   }
+    ///   ```
+    pub fn get_in(&self, ifd: IfdId, tag: ExifTag) -> Option<&EntryValue> {
+        match ifd.sub_ifd_index() {
+            Some(_) => self.sub_ifds.get(&ifd).and_then(|x| x.get_tag(tag)),
+            None => self.ifds.get(ifd.ifd_index()).and_then(|x| x.get_tag(tag)),
+        }
+    }
+
+    /// Returns the identifiers of all the image directories in order, i.e.
+    /// each IFD in the IFD chain, followed by its sub-IFDs pointed by
+    /// `SubIFDs`.
+    pub fn ifd_ids(&self) -> Vec<IfdId> {
+        let mut ids: Vec<IfdId> = (0..self.ifds.len())
+            .map(IfdId::new)
+            .chain(self.sub_ifds.keys().copied())
+            .collect();
+        ids.sort();
+        ids
+    }
+
     /// Get entry values for the specified `tags` in ifd0 (the main image).
     ///
     /// Please note that this method will ignore errors encountered during the
@@ -126,17 +232,54 @@ impl Exif {
         Ok(self.get_by_ifd_tag_code(0, tag).map(|x| x.to_owned()))
     }
 
+    /// Get the typed value of an enumerated tag in ifd0 (the main image),
+    /// e.g. `exif.get_enum::<Orientation>()`, see [`ExifEnum`].
+    ///
+    /// Returns an error if the value is out of range.
+    pub fn get_enum<T: ExifEnum>(&self) -> crate::Result<Option<T>> {
+        self.get(T::TAG).map(T::from_entry).transpose()
+    }
+
+    /// Get a summary of the camera settings (exposure, aperture, ISO, lens
+    /// etc.) of the main image, see [`CaptureSettings`].
+    pub fn capture_settings(&self) -> CaptureSettings {
+        self.into()
+    }
+
     /// Get parsed GPS information.
     pub fn get_gps_info(&self) -> crate::Result<Option<GPSInfo>> {
         Ok(self.gps_info.clone())
     }
 
+    pub(crate) fn ifds(&self) -> &[ParsedImageFileDirectory] {
+        &self.ifds
+    }
+
+    pub(crate) fn endian(&self) -> Endianness {
+        self.endian
+    }
+
+    pub(crate) fn thumbnail(&self) -> Option<&[u8]> {
+        self.thumbnail.as_deref()
+    }
+
     fn put(&mut self, res: &mut ParsedExifEntry) {
+        let id = res.ifd_id();
+        if id.sub_ifd_index().is_some() {
+            if let Some(v) = res.take_value() {
+                self.sub_ifds
+                    .entry(id)
+                    .or_default()
+                    .put(res.group(), res.tag_code(), v);
+            }
+            return;
+        }
+
         while self.ifds.len() < res.ifd_index() + 1 {
             self.ifds.push(ParsedImageFileDirectory::new());
         }
         if let Some(v) = res.take_value() {
-            self.ifds[res.ifd_index()].put(res.tag_code(), v);
+            self.ifds[res.ifd_index()].put(res.group(), res.tag_code(), v);
         }
     }
 }
@@ -144,12 +287,23 @@ impl Exif {
 impl From<ExifIter> for Exif {
     fn from(iter: ExifIter) -> Self {
         let gps_info = iter.parse_gps_info().ok().flatten();
-        let mut exif = Exif::new(gps_info);
+        let mut exif = Exif::new(gps_info, iter.endian());
+        let data = iter.clone_and_rewind();
 
         for mut it in iter {
             exif.put(&mut it);
         }
 
+        let thumbnail_tag = |tag: ExifTag| {
+            exif.get_by_ifd_tag_code(1, tag.code())
+                .and_then(|x| x.as_u32())
+                .map(|x| x as usize)
+        };
+        exif.thumbnail = thumbnail_tag(ExifTag::ThumbnailOffset)
+            .zip(thumbnail_tag(ExifTag::ThumbnailLength))
+            .and_then(|(offset, len)| data.data().get(offset..offset.checked_add(len)?))
+            .map(|x| x.to_vec());
+
         exif
     }
 }
@@ -158,7 +312,9 @@ impl From<ExifIter> for Exif {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct TiffHeader {
     pub endian: Endianness,
-    pub ifd0_offset: u32,
+    pub ifd0_offset: u64,
+    // BigTIFF (version 43), which uses 8-byte counts & offsets
+    pub bigtiff: bool,
 }
 
 impl Default for TiffHeader {
@@ -166,39 +322,99 @@ impl Default for TiffHeader {
         Self {
             endian: Endianness::Big,
             ifd0_offset: 0,
+            bigtiff: false,
         }
     }
 }
 
 pub(crate) const IFD_ENTRY_SIZE: usize = 12;
+const BIGTIFF_IFD_ENTRY_SIZE: usize = 20;
+
+const BIGTIFF_MAGIC: u16 = 0x2b;
+
+/// Size of an IFD entry: tag (2), data format (2), components num (4 or 8),
+/// then the value or the offset of the value (4 or 8).
+pub(crate) fn ifd_entry_size(bigtiff: bool) -> usize {
+    if bigtiff {
+        BIGTIFF_IFD_ENTRY_SIZE
+    } else {
+        IFD_ENTRY_SIZE
+    }
+}
+
+/// Size of offsets, which is also the max size of entry values that can be
+/// stored in the entry itself.
+pub(crate) fn offset_size(bigtiff: bool) -> usize {
+    if bigtiff {
+        8
+    } else {
+        4
+    }
+}
+
+/// The standard TIFF magic (42), and the non-standard ones used by some RAW
+/// formats, which share the same structure.
+const TIFF_MAGICS: &[u16] = &[
+    0x2a, 0x4f52, // ORF: "IIRO", "MMOR"
+    0x5352, // ORF: "IIRS"
+    0x55,   // RW2: "IIU\0"
+];
 
 impl TiffHeader {
     pub fn parse(input: &[u8]) -> IResult<&[u8], TiffHeader> {
-        use nom::number::streaming::{u16, u32};
+        use nom::number::streaming::{u16, u32, u64};
         let (remain, endian) = TiffHeader::parse_endian(input)?;
-        let (_, (_, offset)) = sequence::tuple((
-            combinator::verify(u16(endian), |magic| *magic == 0x2a),
-            u32(endian),
-        ))(remain)?;
-
-        let header = Self {
-            endian,
-            ifd0_offset: offset,
+        let (_, magic) = combinator::verify(u16(endian), |magic| {
+            *magic == BIGTIFF_MAGIC || TIFF_MAGICS.contains(magic)
+        })(remain)?;
+
+        let header = if magic == BIGTIFF_MAGIC {
+            // offset size (always 8), reserved (always 0), then IFD0 offset
+            let (_, (_, offset)) = sequence::tuple((
+                combinator::verify(sequence::pair(u16(endian), u16(endian)), |x| *x == (8, 0)),
+                u64(endian),
+            ))(&remain[2..])?;
+            Self {
+                endian,
+                ifd0_offset: offset,
+                bigtiff: true,
+            }
+        } else {
+            let (_, offset) = u32(endian)(&remain[2..])?;
+            Self {
+                endian,
+                ifd0_offset: offset.into(),
+                bigtiff: false,
+            }
         };
 
         Ok((remain, header))
     }
 
-    pub fn parse_ifd_entry_num(input: &[u8], endian: Endianness) -> IResult<&[u8], u16> {
-        let (remain, num) = nom::number::streaming::u16(endian)(input)?; // Safe-slice
+    pub fn parse_ifd_entry_num(
+        input: &[u8],
+        endian: Endianness,
+        bigtiff: bool,
+    ) -> IResult<&[u8], u64> {
+        use nom::number::streaming::{u16, u64};
+        let (remain, num) = if bigtiff {
+            u64(endian)(input)?
+        } else {
+            combinator::map(u16(endian), u64::from)(input)? // Safe-slice
+        };
         if num == 0 {
             return Ok((remain, 0));
         }
 
-        // 12 bytes per entry
-        let size = (num as usize)
-            .checked_mul(IFD_ENTRY_SIZE)
-            .expect("should fit");
+        let size = usize::try_from(num)
+            .ok()
+            .and_then(|x| x.checked_mul(ifd_entry_size(bigtiff)))
+            .ok_or_else(|| {
+                nom::Err::Failure(nom::error::Error::new(
+                    input,
+                    nom::error::ErrorKind::TooLarge,
+                ))
+            })?;
 
         if size > remain.len() {
             return Err(nom::Err::Incomplete(Needed::new(size - remain.len())));
@@ -207,6 +423,34 @@ impl TiffHeader {
         Ok((remain, num))
     }
 
+    /// Parses an IFD entry, returns (tag, data format, components num, value
+    /// or offset). The value starts at `ifd_entry_size - offset_size` of the
+    /// entry if it's stored in the entry itself.
+    pub fn parse_ifd_entry(
+        input: &[u8],
+        endian: Endianness,
+        bigtiff: bool,
+    ) -> IResult<&[u8], (u16, u16, u64, u64)> {
+        use nom::number::streaming::{u16, u32, u64};
+        if bigtiff {
+            sequence::tuple((u16(endian), u16(endian), u64(endian), u64(endian)))(input)
+        } else {
+            let (remain, (tag, data_format, num, value)) =
+                sequence::tuple((u16(endian), u16(endian), u32(endian), u32(endian)))(input)?;
+            Ok((remain, (tag, data_format, num.into(), value.into())))
+        }
+    }
+
+    /// Parses an offset, e.g. the next IFD offset.
+    pub fn parse_offset(input: &[u8], endian: Endianness, bigtiff: bool) -> IResult<&[u8], u64> {
+        use nom::number::streaming::{u32, u64};
+        if bigtiff {
+            u64(endian)(input)
+        } else {
+            combinator::map(u32(endian), u64::from)(input)
+        }
+    }
+
     // pub fn first_ifd<'a>(&self, input: &'a [u8], tag_ids: HashSet<u16>) -> IResult<&'a [u8], IFD> {
     //     // ifd0_offset starts from the beginning of Header, so we should
     //     // subtract the header size, which is 8
@@ -233,14 +477,6 @@ pub(crate) fn check_exif_header(data: &[u8]) -> Result<bool, nom::Err<nom::error
     tag::<_, _, nom::error::Error<_>>(EXIF_IDENT)(data).map(|_| true)
 }
 
-pub(crate) fn check_exif_header2(i: &[u8]) -> IResult<&[u8], ()> {
-    let (remain, _) = nom::sequence::tuple((
-        nom::number::complete::be_u32,
-        nom::bytes::complete::tag(EXIF_IDENT),
-    ))(i)?;
-    Ok((remain, ()))
-}
-
 pub(crate) const EXIF_IDENT: &str = "Exif\0\0";
 
 #[cfg(test)]
@@ -271,10 +507,22 @@ mod tests {
             TiffHeader {
                 endian: Endianness::Big,
                 ifd0_offset: 8,
+                bigtiff: false,
             }
         );
     }
 
+    #[test_case(b"II+\0\x08\0\0\0\x10\0\0\0\0\0\0\0", Some((Endianness::Little, 16)))]
+    #[test_case(b"MM\0+\0\x08\0\0\0\0\0\0\0\0\0\x10", Some((Endianness::Big, 16)))]
+    #[test_case(b"II+\0\x04\0\0\0\x10\0\0\0\0\0\0\0", None)]
+    fn bigtiff_header(buf: &[u8], expect: Option<(Endianness, u64)>) {
+        let res = TiffHeader::parse(buf).ok().map(|(_, header)| {
+            assert!(header.bigtiff);
+            (header.endian, header.ifd0_offset)
+        });
+        assert_eq!(res, expect);
+    }
+
     #[test_case("exif.jpg")]
     fn exif_iter_gps(path: &str) {
         let buf = read_sample(path).unwrap();
@@ -318,6 +566,52 @@ mod tests {
         assert_eq!(jh.join().unwrap().trim(), expect.trim());
     }
 
+    #[test]
+    fn colliding_tag_codes() {
+        let mut writer = crate::ExifWriter::new();
+        writer
+            .set(IfdGroup::Gps, ExifTag::GPSLatitudeRef, "N".into())
+            .set(IfdGroup::Interop, ExifTag::InteropIndex, "R98".into())
+            .set_by_tag_code(IfdGroup::Ifd0, 0x0002, 7_u16.into());
+        let iter = input_into_iter(writer.write().unwrap(), None).unwrap();
+
+        let entries: Vec<_> = iter
+            .clone()
+            .filter(|x| x.tag_code() <= 2)
+            .map(|x| (x.group(), x.tag()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (IfdGroup::Ifd0, None),
+                (IfdGroup::Interop, Some(ExifTag::InteropIndex)),
+                (IfdGroup::Gps, Some(ExifTag::GPSLatitudeRef)),
+            ]
+        );
+
+        let exif: Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::GPSLatitudeRef).unwrap(), &"N".into());
+        assert_eq!(exif.get(ExifTag::InteropIndex).unwrap(), &"R98".into());
+        assert!(exif.get(ExifTag::GPSLatitude).is_none());
+        assert!(exif.get(ExifTag::InteropVersion).is_none());
+        assert_eq!(
+            exif.get_by_group_tag_code(IfdGroup::Interop, 0x0001),
+            Some(&"R98".into())
+        );
+        assert_eq!(
+            exif.get_by_group_tag_code(IfdGroup::Ifd0, 0x0002),
+            Some(&7_u16.into())
+        );
+        assert_eq!(exif.get_by_ifd_tag_code(0, 0x0001), Some(&"N".into()));
+
+        // Entries are written back to their own groups
+        let writer = crate::ExifWriter::from(&exif);
+        assert_eq!(
+            writer.get(IfdGroup::Interop, ExifTag::InteropIndex),
+            Some(&"R98".into())
+        );
+    }
+
     fn iter_to_str(it: impl Iterator<Item = ParsedExifEntry>) -> String {
         let ss = it
             .map(|x| {
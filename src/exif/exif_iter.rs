@@ -1,28 +1,30 @@
 use std::{fmt::Debug, sync::Arc};
 
-use nom::{
-    number::{complete, Endianness},
-    sequence::tuple,
-};
+use nom::number::{complete, Endianness};
 use thiserror::Error;
 
 use crate::{
     partial_vec::{AssociatedInput, PartialVec},
     slice::SliceChecked,
-    values::{DataFormat, EntryData, IRational, ParseEntryError, URational},
+    values::{DataFormat, EntryData, IRational, ParseEntryError, TimeOffsets, URational},
     EntryValue, ExifTag,
 };
 
-use super::{exif_exif::IFD_ENTRY_SIZE, tags::ExifTagCode, GPSInfo, TiffHeader};
+use super::{
+    exif_exif::{ifd_entry_size, offset_size},
+    print::gps_ref_tag,
+    tags::ExifTagCode,
+    GPSInfo, IfdGroup, IfdId, PrintValue, TiffHeader,
+};
 
 /// Parses header from input data, and returns an [`ExifIter`].
 ///
 /// All entries are lazy-parsed. That is, only when you iterate over
 /// [`ExifIter`] will the IFD entries be parsed one by one.
 ///
-/// The one exception is the time zone entries. The method will try to find
-/// and parse the time zone data first, so we can correctly parse all time
-/// information in subsequent iterates.
+/// The one exception is the time zone entries (`OffsetTime*`). The method will
+/// try to find and parse the time zone data first, so we can correctly parse
+/// all time information in subsequent iterates.
 #[tracing::instrument]
 pub(crate) fn input_into_iter(
     input: impl Into<PartialVec> + Debug,
@@ -54,12 +56,13 @@ pub(crate) fn input_into_iter(
         input.partial(&data[start..]),
         header.ifd0_offset,
         header.endian,
-        None,
+        header.bigtiff,
+        TimeOffsets::default(),
     )?;
 
-    let tz = ifd0.find_tz_offset();
-    ifd0.tz = tz.clone();
-    let iter: ExifIter = ExifIter::new(input, header, tz, ifd0);
+    let offsets = ifd0.find_time_offsets();
+    ifd0.offsets = offsets.clone();
+    let iter: ExifIter = ExifIter::new(input, header, offsets, ifd0);
 
     tracing::debug!(?iter, "got IFD0");
 
@@ -82,11 +85,18 @@ pub struct ExifIter {
     // Use Arc to make sure we won't clone the owned data.
     input: Arc<PartialVec>,
     tiff_header: TiffHeader,
-    tz: Option<String>,
+    offsets: TimeOffsets,
     ifd0: IfdIter,
+    // Iterators of other TIFF data blocks, which are iterated after this one,
+    // e.g. the CMT* blocks of CR3 files
+    chained: Vec<ExifIter>,
+    // Entries which aren't stored in any IFD, yielded at last
+    extra_entries: Vec<ParsedExifEntry>,
 
     // Iterating status
     ifds: Vec<IfdIter>,
+    // index of `chained` (then `extra_entries`) being iterated
+    chain_pos: usize,
 }
 
 impl Debug for ExifIter {
@@ -97,6 +107,7 @@ impl Debug for ExifIter {
             .field("ifd0", &self.ifd0)
             .field("state", &self.ifds.first().map(|x| (x.index, x.pos)))
             .field("ifds num", &self.ifds.len())
+            .field("chained", &self.chained)
             .finish_non_exhaustive()
     }
 }
@@ -111,16 +122,19 @@ impl ExifIter {
     pub(crate) fn new(
         input: impl Into<PartialVec>,
         tiff_header: TiffHeader,
-        tz: Option<String>,
+        offsets: TimeOffsets,
         ifd0: IfdIter,
     ) -> ExifIter {
         let ifds = vec![ifd0.clone()];
         ExifIter {
             input: Arc::new(input.into()),
             tiff_header,
-            tz,
+            offsets,
             ifd0,
+            chained: Vec::new(),
+            extra_entries: Vec::new(),
             ifds,
+            chain_pos: 0,
         }
     }
 
@@ -134,9 +148,12 @@ impl ExifIter {
         Self {
             input: self.input.clone(),
             tiff_header: self.tiff_header.clone(),
-            tz: self.tz.clone(),
+            offsets: self.offsets.clone(),
             ifd0,
+            chained: self.chained.iter().map(|x| x.clone_and_rewind()).collect(),
+            extra_entries: self.extra_entries.clone(),
             ifds,
+            chain_pos: 0,
         }
     }
 
@@ -151,7 +168,28 @@ impl ExifIter {
     /// - An `Err` if gps info is found but parsing failed.
     #[tracing::instrument(skip_all)]
     pub fn parse_gps_info(&self) -> crate::Result<Option<GPSInfo>> {
+        if let Some(gps) = self.parse_block_gps_info()? {
+            return Ok(Some(gps));
+        }
+        for iter in self.chained.iter() {
+            if let Some(gps) = iter.parse_gps_info()? {
+                return Ok(Some(gps));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Parse the GPS info of this TIFF data block, the chained ones are not
+    /// included.
+    fn parse_block_gps_info(&self) -> crate::Result<Option<GPSInfo>> {
+        if self.ifd0.tag_code == Some(ExifTag::GPSInfo.code().into()) {
+            // A standalone GPS IFD
+            return Ok(self.ifd0.clone_and_rewind().parse_gps_info());
+        }
+
         let mut iter = self.clone_and_rewind();
+        iter.chained.clear();
+        iter.extra_entries.clear();
         let Some(gps) = iter.find(|x| {
             tracing::info!(?x, "find");
             x.tag.tag().is_some_and(|t| t == ExifTag::GPSInfo)
@@ -162,7 +200,7 @@ impl ExifIter {
 
         let offset = match gps.get_result() {
             Ok(v) => {
-                if let Some(offset) = v.as_u32() {
+                if let Some(offset) = v.as_u32().map(u64::from).or_else(|| v.as_u64()) {
                     offset
                 } else {
                     return Err(EntryError(ParseEntryError::InvalidData(
@@ -180,25 +218,103 @@ impl ExifIter {
         }
 
         let mut gps_subifd = match IfdIter::try_new(
-            gps.ifd,
+            gps.ifd.ifd_index(),
             iter.input.partial(&iter.input[offset as usize..]), // Safe-slice
             offset,
             iter.tiff_header.endian,
-            iter.tz.clone(),
+            iter.tiff_header.bigtiff,
+            iter.offsets.clone(),
         ) {
-            Ok(ifd0) => ifd0.tag_code(ExifTag::GPSInfo.code()),
+            Ok(ifd0) => ifd0
+                .sub_ifd(gps.ifd.sub_ifd_index())
+                .tag_code(ExifTag::GPSInfo.code()),
             Err(e) => return Err(e),
         };
         Ok(gps_subifd.parse_gps_info())
     }
 
+    /// Treat IFD0 as the sub-IFD pointed by `tag`, e.g. a standalone GPS IFD
+    /// stored in its own TIFF data block.
+    pub(crate) fn ifd0_as_sub_ifd(mut self, tag: ExifTag) -> ExifIter {
+        self.ifd0 = self.ifd0.tag_code(tag.code());
+        if tag == ExifTag::ExifOffset {
+            // The `OffsetTime*` entries are in IFD0 itself
+            let offsets = self.ifd0.clone_and_rewind().time_offsets();
+            self.set_time_offsets(offsets);
+        }
+        self.ifds = vec![self.ifd0.clone()];
+        self
+    }
+
+    /// Iterate the entries of `other` (another TIFF data block) after the
+    /// entries of this one. The `OffsetTime*` entries of both blocks are
+    /// used to parse the date/time entries.
+    pub(crate) fn chain(mut self, mut other: ExifIter) -> ExifIter {
+        let offsets = self.offsets.clone().or(other.offsets.clone());
+        other.set_time_offsets(offsets.clone());
+        self.chained.push(other);
+        self.set_time_offsets(offsets);
+        self
+    }
+
+    /// Yield an extra entry of IFD0 after all the other entries, e.g. a
+    /// MakerNote stored in its own data block.
+    pub(crate) fn with_entry(mut self, group: IfdGroup, tag: ExifTag, value: EntryValue) -> Self {
+        self.extra_entries.push(ParsedExifEntry::make_ok(
+            IfdId::IFD0,
+            group,
+            ExifTagCode::Tag(tag),
+            value,
+        ));
+        self
+    }
+
+    fn set_time_offsets(&mut self, offsets: TimeOffsets) {
+        for iter in self.chained.iter_mut() {
+            iter.set_time_offsets(offsets.clone());
+        }
+        self.ifd0.offsets = offsets.clone();
+        for ifd in self.ifds.iter_mut() {
+            ifd.offsets = offsets.clone();
+        }
+        self.offsets = offsets;
+    }
+
+    /// Yield the entries of the chained iterators, then the extra entries.
+    fn next_chained(&mut self) -> Option<ParsedExifEntry> {
+        while let Some(iter) = self.chained.get_mut(self.chain_pos) {
+            if let Some(entry) = iter.next() {
+                return Some(entry);
+            }
+            self.chain_pos += 1;
+        }
+        let entry = self
+            .extra_entries
+            .get(self.chain_pos - self.chained.len())
+            .cloned()?;
+        self.chain_pos += 1;
+        Some(entry)
+    }
+
+    pub(crate) fn endian(&self) -> Endianness {
+        self.tiff_header.endian
+    }
+
+    /// The TIFF data, offsets of entries are relative to it.
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.input[..]
+    }
+
     pub(crate) fn to_owned(&self) -> ExifIter {
-        ExifIter::new(
+        let mut iter = ExifIter::new(
             self.input.to_vec(),
             self.tiff_header.clone(),
-            self.tz.clone(),
+            self.offsets.clone(),
             self.ifd0.clone_and_rewind(),
-        )
+        );
+        iter.chained = self.chained.iter().map(|x| x.to_owned()).collect();
+        iter.extra_entries = self.extra_entries.clone();
+        iter
     }
 }
 
@@ -215,23 +331,49 @@ impl From<EntryError> for crate::Error {
 /// Represents a parsed IFD entry. Used as iterator items in [`ExifIter`].
 #[derive(Clone)]
 pub struct ParsedExifEntry {
-    // 0: ifd0, 1: ifd1
-    ifd: usize,
+    ifd: IfdId,
+    group: IfdGroup,
     tag: ExifTagCode,
     res: Option<Result<EntryValue, EntryError>>,
+    // The value of the `*Ref` entry of GPS coordinates, e.g. "N"
+    gps_ref: Option<String>,
 }
 
 impl ParsedExifEntry {
     /// Get the IFD index value where this entry is located.
     /// - 0: ifd0 (main image)
     /// - 1: ifd1 (thumbnail)
+    /// - 2, 3...: the following IFDs in the IFD chain, e.g. pages of a
+    ///   multi-page TIFF file
+    ///
+    /// Entries located in the sub-IFDs pointed by `SubIFDs` have the same IFD
+    /// index as the IFD holding the `SubIFDs` entry, use [`Self::ifd_id`] to
+    /// tell them apart.
     pub fn ifd_index(&self) -> usize {
+        self.ifd.ifd_index()
+    }
+
+    /// Get the identifier of the image directory where this entry is
+    /// located, see [`IfdId`].
+    pub fn ifd_id(&self) -> IfdId {
         self.ifd
     }
 
+    /// Get the IFD group where this entry is located, e.g.
+    /// [`IfdGroup::Gps`] for the entries in the GPS sub-IFD.
+    ///
+    /// Tag codes are only unique in a group (or to be precise, in the tag
+    /// namespace of a group, see [`ExifTag::from_code_in`]).
+    pub fn group(&self) -> IfdGroup {
+        self.group
+    }
+
     /// Get recognized Exif tag of this entry, maybe return `None` if the tag
     /// is unrecognized.
     ///
+    /// The tag is recognized in the namespace of the group where this entry
+    /// is located, see [`Self::group`].
+    ///
     /// If you have any custom defined tag which does not exist in [`ExifTag`],
     /// then you should use [`Self::tag_code`] to get the raw tag code.
     ///
@@ -271,6 +413,16 @@ impl ParsedExifEntry {
         }
     }
 
+    /// Get the parsed entry value in a human-readable form, e.g.
+    /// `ExposureTime` as `1/125`, see [`PrintValue`].
+    ///
+    /// GPS coordinates are printed with their `N`/`S`/`E`/`W` reference,
+    /// which is taken from the preceding `*Ref` entry of the GPS sub-IFD.
+    pub fn print_value(&self) -> Option<PrintValue<'_>> {
+        self.get_value()
+            .map(|v| PrintValue::new(self.tag(), v).gps_ref(self.gps_ref.as_deref()))
+    }
+
     /// Takes out the parsed entry value of this entry.
     ///
     /// If you need to convert this `ExifIter` to an [`crate::Exif`], please
@@ -322,19 +474,23 @@ impl ParsedExifEntry {
         }
     }
 
-    fn make_ok(ifd: usize, tag: ExifTagCode, v: EntryValue) -> Self {
+    fn make_ok(ifd: IfdId, group: IfdGroup, tag: ExifTagCode, v: EntryValue) -> Self {
         Self {
             ifd,
+            group,
             tag,
             res: Some(Ok(v)),
+            gps_ref: None,
         }
     }
 
-    fn make_err(ifd: usize, tag: ExifTagCode, e: ParseEntryError) -> Self {
+    fn make_err(ifd: IfdId, group: IfdGroup, tag: ExifTagCode, e: ParseEntryError) -> Self {
         Self {
             ifd,
+            group,
             tag,
             res: Some(Err(EntryError(e))),
+            gps_ref: None,
         }
     }
 }
@@ -346,7 +502,8 @@ impl Debug for ParsedExifEntry {
             Err(e) => format!("{e:?}"),
         };
         f.debug_struct("IfdEntryResult")
-            .field("ifd", &format!("ifd{}", self.ifd))
+            .field("ifd", &self.ifd.to_string())
+            .field("group", &self.group)
             .field("tag", &self.tag)
             .field("value", &value)
             .finish()
@@ -363,7 +520,7 @@ impl Iterator for ExifIter {
         loop {
             if self.ifds.is_empty() {
                 tracing::debug!(?self, "all IFDs has been parsed");
-                return None;
+                return self.next_chained();
             }
 
             if self.ifds.len() > MAX_IFD_DEPTH {
@@ -391,6 +548,8 @@ impl Iterator for ExifIter {
                                 );
                                 continue;
                             }
+                            // The pointer entry belongs to the current IFD
+                            let group = ifd.group();
                             let is_subifd = if new_ifd.ifd_idx == ifd.ifd_idx {
                                 // Push the current ifd before enter sub-ifd.
                                 self.ifds.push(ifd);
@@ -405,28 +564,55 @@ impl Iterator for ExifIter {
                                 false
                             };
 
-                            let (ifd_idx, offset) = (new_ifd.ifd_idx, new_ifd.offset);
+                            let (ifd_id, offset) = (new_ifd.id(), new_ifd.offset);
+                            let offset = match u32::try_from(offset) {
+                                Ok(x) if !new_ifd.bigtiff => EntryValue::U32(x),
+                                _ => EntryValue::U64(offset),
+                            };
                             self.ifds.push(new_ifd);
 
                             if is_subifd {
                                 // Return sub-ifd as an entry
                                 return Some(ParsedExifEntry::make_ok(
-                                    ifd_idx,
+                                    ifd_id,
+                                    group,
                                     tag_code.unwrap(),
-                                    EntryValue::U32(offset),
+                                    offset,
                                 ));
                             }
                         }
                         IfdEntry::Entry(v) => {
-                            let res =
-                                Some(ParsedExifEntry::make_ok(ifd.ifd_idx, tag_code.unwrap(), v));
+                            let tag_code = tag_code.unwrap();
+                            let gps_ref = ifd.track_gps_ref(tag_code, &v);
+                            let mut entry =
+                                ParsedExifEntry::make_ok(ifd.id(), ifd.group(), tag_code, v);
+                            entry.gps_ref = gps_ref;
+                            self.ifds.push(ifd);
+                            return Some(entry);
+                        }
+                        IfdEntry::SubIfds(v, sub_ifds) => {
+                            let res = Some(ParsedExifEntry::make_ok(
+                                ifd.id(),
+                                ifd.group(),
+                                tag_code.unwrap(),
+                                v,
+                            ));
+                            // Continue with the remaining entries after all
+                            // the sub-IFDs have been parsed in order.
                             self.ifds.push(ifd);
+                            self.ifds.extend(sub_ifds.into_iter().rev());
                             return res;
                         }
                         IfdEntry::Err(e) => {
                             tracing::warn!(?tag_code, ?e, "parse ifd entry error");
-                            let res =
-                                Some(ParsedExifEntry::make_err(ifd.ifd_idx, tag_code.unwrap(), e));
+                            let res = Some(ParsedExifEntry::make_err(
+                                ifd.id(),
+                                ifd.group(),
+                                tag_code.unwrap(),
+                                e,
+                            ));
+                            // Continue with the remaining entries of this IFD
+                            self.ifds.push(ifd);
                             return res;
                         }
                     }
@@ -440,32 +626,39 @@ impl Iterator for ExifIter {
 #[derive(Clone)]
 pub(crate) struct IfdIter {
     ifd_idx: usize,
+    // index in the `SubIFDs` array of the parent IFD
+    sub_ifd: Option<usize>,
     tag_code: Option<ExifTagCode>,
 
     // starts from "ifd/sub-ifd entries" (two bytes of ifd/sub-ifd entry num)
     input: AssociatedInput,
 
     // IFD data offset relative to the TIFF header.
-    offset: u32,
+    offset: u64,
 
-    pub tz: Option<String>,
+    pub offsets: TimeOffsets,
     endian: Endianness,
-    entry_num: u16,
+    bigtiff: bool,
+    entry_num: u64,
 
     // Iterating status
-    index: u16,
+    index: u64,
     pos: usize,
+    // `*Ref` entries of GPS coordinates seen so far
+    gps_refs: Vec<(ExifTag, String)>,
 }
 
 impl Debug for IfdIter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("IfdIter")
             .field("ifd_idx", &self.ifd_idx)
+            .field("sub_ifd", &self.sub_ifd)
             .field("tag", &self.tag_code)
             .field("data len", &self.input.len())
             .field("offset", &self.offset)
-            .field("tz", &self.tz)
+            .field("offsets", &self.offsets)
             .field("endian", &self.endian)
+            .field("bigtiff", &self.bigtiff)
             .field("entry_num", &self.entry_num)
             .field("index", &self.index)
             .field("pos", &self.pos)
@@ -476,8 +669,17 @@ impl Debug for IfdIter {
 impl IfdIter {
     pub fn rewind(&mut self) {
         self.index = 0;
-        // Skip the first two bytes, which is the entry num
-        self.pos = 2;
+        // Skip the entry num
+        self.pos = self.entry_num_size();
+        self.gps_refs.clear();
+    }
+
+    fn entry_num_size(&self) -> usize {
+        if self.bigtiff {
+            8
+        } else {
+            2
+        }
     }
 
     pub fn clone_and_rewind(&self) -> Self {
@@ -486,6 +688,30 @@ impl IfdIter {
         it
     }
 
+    pub fn sub_ifd(mut self, index: Option<usize>) -> Self {
+        self.sub_ifd = index;
+        self
+    }
+
+    fn id(&self) -> IfdId {
+        match self.sub_ifd {
+            Some(sub) => IfdId::new(self.ifd_idx).with_sub_ifd(sub),
+            None => IfdId::new(self.ifd_idx),
+        }
+    }
+
+    /// The IFD group, which is determined by the pointer tag of a sub-IFD.
+    fn group(&self) -> IfdGroup {
+        match self.tag_code.map(|x| x.code()) {
+            Some(code) if code == ExifTag::ExifOffset.code() => IfdGroup::Exif,
+            Some(code) if code == ExifTag::GPSInfo.code() => IfdGroup::Gps,
+            Some(code) if code == ExifTag::InteropOffset.code() => IfdGroup::Interop,
+            Some(_) => IfdGroup::Ifd0,
+            None if self.ifd_idx == 1 && self.sub_ifd.is_none() => IfdGroup::Ifd1,
+            None => IfdGroup::Ifd0,
+        }
+    }
+
     pub fn tag_code_maybe(mut self, code: Option<u16>) -> Self {
         self.tag_code = code.map(|x| x.into());
         self
@@ -506,50 +732,80 @@ impl IfdIter {
     pub fn try_new(
         ifd_idx: usize,
         input: AssociatedInput,
-        offset: u32,
+        offset: u64,
         endian: Endianness,
-        tz: Option<String>,
+        bigtiff: bool,
+        offsets: TimeOffsets,
     ) -> crate::Result<Self> {
-        if input.len() < 2 {
+        let entry_num_size = if bigtiff { 8 } else { 2 };
+        if input.len() < entry_num_size {
             return Err(crate::Error::ParseFailed(
                 "ifd data is too small to decode entry num".into(),
             ));
         }
         // should use the complete header data to parse ifd entry num
-        let (_, entry_num) = TiffHeader::parse_ifd_entry_num(&input[..], endian)?;
+        let (_, entry_num) = TiffHeader::parse_ifd_entry_num(&input[..], endian, bigtiff)?;
 
         Ok(Self {
             ifd_idx,
+            sub_ifd: None,
             tag_code: None,
             input,
             offset,
             entry_num,
-            tz,
+            offsets,
             endian,
-            // Skip the first two bytes, which is the entry num
-            pos: 2,
+            bigtiff,
+            // Skip the entry num
+            pos: entry_num_size,
             index: 0,
+            gps_refs: Vec::new(),
         })
     }
 
+    /// Remembers the `*Ref` entries of the GPS sub-IFD, and returns the
+    /// reference of a GPS coordinate entry. The `*Ref` tags always precede
+    /// their coordinates, since IFD entries are sorted by tag code.
+    fn track_gps_ref(&mut self, tag: ExifTagCode, v: &EntryValue) -> Option<String> {
+        let ExifTagCode::Tag(tag) = tag else {
+            return None;
+        };
+        if self.group() != IfdGroup::Gps {
+            return None;
+        }
+        if let Some(r) = gps_ref_tag(tag) {
+            return self
+                .gps_refs
+                .iter()
+                .find(|(t, _)| *t == r)
+                .map(|(_, s)| s.clone());
+        }
+        if let (
+            ExifTag::GPSLatitudeRef
+            | ExifTag::GPSLongitudeRef
+            | ExifTag::GPSDestLatitudeRef
+            | ExifTag::GPSDestLongitudeRef,
+            Some(s),
+        ) = (tag, v.as_str())
+        {
+            self.gps_refs.push((tag, s.to_owned()));
+        }
+        None
+    }
+
     fn parse_tag_entry(&self, entry_data: &[u8]) -> Option<(u16, IfdEntry)> {
-        let endian = self.endian;
-        let (_, (tag, data_format, components_num, value_or_offset)) = tuple((
-            complete::u16::<_, nom::error::Error<_>>(endian),
-            complete::u16(endian),
-            complete::u32(endian),
-            complete::u32(endian),
-        ))(entry_data)
-        .ok()?;
-
-        if tag == 0 {
+        let (_, (tag, data_format, components_num, value_or_offset)) =
+            TiffHeader::parse_ifd_entry(entry_data, self.endian, self.bigtiff).ok()?;
+
+        // GPSVersionID is the only valid entry with tag code 0
+        if tag == 0 && self.tag_code != Some(ExifTag::GPSInfo.code().into()) {
             return None;
         }
 
         let df: DataFormat = match data_format.try_into() {
             Ok(df) => df,
             Err(e) => {
-                let t: ExifTagCode = tag.into();
+                let t = ExifTagCode::from_code_in(self.group(), tag);
                 tracing::warn!(tag = ?t, ?e, "invalid entry data format");
                 return Some((tag, IfdEntry::Err(e)));
             }
@@ -558,7 +814,7 @@ impl IfdIter {
         Some((tag, res))
     }
 
-    fn get_data_pos(&self, value_or_offset: u32) -> u32 {
+    fn get_data_pos(&self, value_or_offset: u64) -> u64 {
         value_or_offset.saturating_sub(self.offset)
     }
 
@@ -566,20 +822,26 @@ impl IfdIter {
         &self,
         tag: u16,
         data_format: DataFormat,
-        components_num: u32,
+        components_num: u64,
         entry_data: &[u8],
-        value_or_offset: u32,
+        value_or_offset: u64,
     ) -> (u16, IfdEntry) {
         // get component_size according to data format
         let component_size = data_format.component_size();
 
         // get entry data
-        let size = components_num as usize * component_size;
-        let data = if size <= 4 {
-            &entry_data[8..8 + size] // Safe-slice
+        let Some(size) = usize::try_from(components_num)
+            .ok()
+            .and_then(|x| x.checked_mul(component_size))
+        else {
+            return (tag, IfdEntry::Err(ParseEntryError::EntrySizeTooBig));
+        };
+        let data = if size <= offset_size(self.bigtiff) {
+            let start = ifd_entry_size(self.bigtiff) - offset_size(self.bigtiff);
+            &entry_data[start..start + size] // Safe-slice
         } else {
             let start = self.get_data_pos(value_or_offset) as usize;
-            let end = start + size;
+            let end = start.saturating_add(size);
             let Some(data) = self.input.slice_checked(start..end) else {
                 tracing::warn!(
                     "entry data overflow, self.offset: {:08x} tag: {:04x} start: {:08x} end: {:08x} ifd data len {:08x}",
@@ -601,23 +863,78 @@ impl IfdIter {
             }
         }
 
+        if let Some(t) = ExifTag::from_code_in(self.group(), tag) {
+            if !t.spec().accepts(data_format, components_num) {
+                tracing::debug!(
+                    tag = ?t,
+                    ?data_format,
+                    components_num,
+                    "entry doesn't conform to the tag definition"
+                );
+            }
+        }
+
         let entry = EntryData {
             endian: self.endian,
             tag,
             data,
             data_format,
-            components_num,
+            // the data has been loaded, so it must fit
+            components_num: components_num as u32,
         };
-        match EntryValue::parse(&entry, &self.tz) {
+        match EntryValue::parse(&entry, &self.offsets) {
+            Ok(v) if tag == ExifTag::SubIFDs.code() && self.is_image_ifd() => {
+                let sub_ifds = self.new_sub_ifd_iters(&v);
+                (tag, IfdEntry::SubIfds(v, sub_ifds))
+            }
             Ok(v) => (tag, IfdEntry::Entry(v)),
             Err(e) => (tag, IfdEntry::Err(e)),
         }
     }
 
+    /// Returns true if this is an IFD in the IFD chain, rather than a sub-IFD.
+    /// Nested `SubIFDs` are not followed.
+    fn is_image_ifd(&self) -> bool {
+        self.tag_code.is_none() && self.sub_ifd.is_none()
+    }
+
+    /// Creates the sub-IFD iterators for the offsets in a `SubIFDs` entry.
+    fn new_sub_ifd_iters(&self, value: &EntryValue) -> Vec<IfdIter> {
+        let offsets: Vec<u64> = match value {
+            EntryValue::U32(v) => vec![(*v).into()],
+            EntryValue::U32Array(v) => v.iter().map(|&x| x.into()).collect(),
+            EntryValue::U64(v) => vec![*v],
+            EntryValue::U64Array(v) => v.clone(),
+            _ => {
+                tracing::warn!(?value, "invalid SubIFDs value");
+                return Vec::new();
+            }
+        };
+        if offsets.len() > MAX_SUB_IFDS {
+            tracing::warn!(
+                num = offsets.len(),
+                "too many SubIFDs, the rest are ignored"
+            );
+        }
+
+        offsets
+            .iter()
+            .take(MAX_SUB_IFDS)
+            .enumerate()
+            .filter(|(_, &offset)| offset > self.offset)
+            .filter_map(
+                |(i, &offset)| match self.new_ifd_iter(self.ifd_idx, offset, None)? {
+                    IfdEntry::IfdNew(iter) => Some(iter.sub_ifd(Some(i))),
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+
     fn new_ifd_iter(
         &self,
         ifd_idx: usize,
-        value_or_offset: u32,
+        value_or_offset: u64,
         tag: Option<u16>,
     ) -> Option<IfdEntry> {
         let pos = self.get_data_pos(value_or_offset) as usize;
@@ -627,9 +944,19 @@ impl IfdIter {
                 self.input.partial(&self.input[pos..]),
                 value_or_offset,
                 self.endian,
-                self.tz.clone(),
+                self.bigtiff,
+                self.offsets.clone(),
             ) {
-                Ok(iter) => return Some(IfdEntry::IfdNew(iter.tag_code_maybe(tag))),
+                Ok(iter) => {
+                    let iter = iter.tag_code_maybe(tag);
+                    // Sub-IFDs (e.g. Exif) of a `SubIFDs` image belong to it
+                    let iter = if ifd_idx == self.ifd_idx {
+                        iter.sub_ifd(self.sub_ifd)
+                    } else {
+                        iter
+                    };
+                    return Some(IfdEntry::IfdNew(iter));
+                }
                 Err(e) => {
                     tracing::warn!(?tag, ?e, "Create next/sub IFD failed");
                 }
@@ -648,40 +975,48 @@ impl IfdIter {
 
     pub fn find_exif_iter(&self) -> Option<IfdIter> {
         let endian = self.endian;
+        let entry_size = ifd_entry_size(self.bigtiff);
         // find ExifOffset
-        for i in 0..self.entry_num {
-            let pos = self.pos + i as usize * IFD_ENTRY_SIZE;
+        for i in 0..self.entry_num as usize {
+            let pos = self.pos + i * entry_size;
             let (_, tag) =
                 complete::u16::<_, nom::error::Error<_>>(endian)(&self.input[pos..]).ok()?;
             if tag == ExifTag::ExifOffset.code() {
-                let entry_data = self.input.slice_checked(pos..pos + IFD_ENTRY_SIZE)?;
+                let entry_data = self.input.slice_checked(pos..pos + entry_size)?;
                 let (_, entry) = self.parse_tag_entry(entry_data)?;
                 match entry {
                     IfdEntry::IfdNew(iter) => return Some(iter),
-                    IfdEntry::Entry(_) | IfdEntry::Err(_) => return None,
+                    IfdEntry::Entry(_) | IfdEntry::SubIfds(..) | IfdEntry::Err(_) => return None,
                 }
             }
         }
         None
     }
 
-    pub fn find_tz_offset(&self) -> Option<String> {
-        let iter = self.find_exif_iter()?;
-        let mut offset = None;
-        for entry in iter {
-            let Some(tag) = entry.0 else {
+    /// Finds the `OffsetTime*` entries in the Exif sub-IFD.
+    pub fn find_time_offsets(&self) -> TimeOffsets {
+        match self.find_exif_iter() {
+            Some(iter) => iter.time_offsets(),
+            None => TimeOffsets::default(),
+        }
+    }
+
+    /// Collects the `OffsetTime*` entries of this IFD.
+    pub fn time_offsets(self) -> TimeOffsets {
+        let mut offsets = TimeOffsets::default();
+        for entry in self {
+            let Some(tag) = entry.0.and_then(|x| x.tag()) else {
                 continue;
             };
-            if tag.code() == ExifTag::OffsetTimeOriginal.code()
-                || tag.code() == ExifTag::OffsetTimeDigitized.code()
-            {
-                return entry.1.as_str().map(|x| x.to_owned());
-            } else if tag.code() == ExifTag::OffsetTime.code() {
-                offset = entry.1.as_str().map(|x| x.to_owned());
+            let offset = entry.1.as_str().map(|x| x.to_owned());
+            match tag {
+                ExifTag::OffsetTime => offsets.offset_time = offset,
+                ExifTag::OffsetTimeOriginal => offsets.original = offset,
+                ExifTag::OffsetTimeDigitized => offsets.digitized = offset,
+                _ => (),
             }
         }
-
-        offset
+        offsets
     }
 
     // Assume the current ifd is GPSInfo subifd.
@@ -766,6 +1101,8 @@ impl IfdIter {
 pub(crate) enum IfdEntry {
     IfdNew(IfdIter), // ifd index
     Entry(EntryValue),
+    // `SubIFDs` entry value, and the sub-IFDs pointed by it
+    SubIfds(EntryValue, Vec<IfdIter>),
     Err(ParseEntryError),
 }
 
@@ -827,7 +1164,16 @@ impl IfdEntry {
     }
 }
 
-pub(crate) const SUBIFD_TAGS: &[u16] = &[ExifTag::ExifOffset.code(), ExifTag::GPSInfo.code()];
+pub(crate) const SUBIFD_TAGS: &[u16] = &[
+    ExifTag::ExifOffset.code(),
+    ExifTag::GPSInfo.code(),
+    ExifTag::InteropOffset.code(),
+];
+
+/// Max number of sub-IFDs followed for a `SubIFDs` entry (the rest are
+/// ignored), which keeps the IFD stack of `ExifIter` below `MAX_IFD_DEPTH`.
+/// It's also the number of sub-IFDs checked when detecting RAW formats.
+pub(crate) const MAX_SUB_IFDS: usize = 4;
 
 impl Iterator for IfdIter {
     type Item = (Option<ExifTagCode>, IfdEntry);
@@ -841,11 +1187,11 @@ impl Iterator for IfdIter {
         //     pos = format!("{:08x}", self.pos),
         //     "next IFD entry"
         // );
-        if self.input.len() < self.pos + IFD_ENTRY_SIZE {
+        let entry_size = ifd_entry_size(self.bigtiff);
+        if self.input.len() < self.pos + entry_size {
             return None;
         }
 
-        let endian = self.endian;
         if self.index > self.entry_num {
             return None;
         }
@@ -860,39 +1206,47 @@ impl Iterator for IfdIter {
 
             // next IFD offset
             let (_, offset) =
-                complete::u32::<_, nom::error::Error<_>>(endian)(&self.input[self.pos..]).ok()?;
+                TiffHeader::parse_offset(&self.input[self.pos..], self.endian, self.bigtiff)
+                    .ok()?;
 
             if offset == 0 {
                 // IFD parsing completed
                 tracing::debug!(?self, "IFD parsing completed");
                 return None;
             }
+            if self.sub_ifd.is_some() {
+                tracing::debug!(?self, "ignore the next IFD of a sub-IFD");
+                return None;
+            }
 
             return self
                 .new_ifd_iter(self.ifd_idx + 1, offset, None)
                 .map(|x| (None, x));
         }
 
-        let entry_data = self
-            .input
-            .slice_checked(self.pos..self.pos + IFD_ENTRY_SIZE)?;
+        let entry_data = self.input.slice_checked(self.pos..self.pos + entry_size)?;
         self.index += 1;
-        self.pos += IFD_ENTRY_SIZE;
+        self.pos += entry_size;
 
         let (tag, res) = self.parse_tag_entry(entry_data)?;
 
-        Some((Some(tag.into()), res)) // Safe-slice
+        Some((Some(ExifTagCode::from_code_in(self.group(), tag)), res))
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use std::io::Cursor;
+
     use crate::exif::extract_exif_with_mime;
     use crate::exif::input_into_iter;
     use crate::file::MimeImage;
     use crate::slice::SubsliceRange;
-    use crate::testkit::read_sample;
+    use crate::testkit::{parse_sample, read_sample};
+    use crate::{
+        EntryValue, Exif, ExifIter, ExifTag, ExifWriter, IfdGroup, IfdId, MediaParser, MediaSource,
+    };
     use test_case::test_case;
 
     #[test_case("exif.jpg", "+08:00", MimeImage::Jpeg)]
@@ -900,7 +1254,7 @@ mod tests {
     #[test_case("exif.heic", "+08:00", MimeImage::Heic)]
     #[test_case("tif.tif", "", MimeImage::Tiff)]
     #[test_case("fujifilm_x_t1_01.raf.meta", "", MimeImage::Raf)]
-    fn exif_iter_tz(path: &str, tz: &str, img_type: MimeImage) {
+    fn exif_iter_time_offsets(path: &str, tz: &str, img_type: MimeImage) {
         let buf = read_sample(path).unwrap();
         let (data, _) = extract_exif_with_mime(img_type, &buf, None).unwrap();
         let subslice_in_range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
@@ -910,6 +1264,332 @@ mod tests {
         } else {
             Some(tz.to_string())
         };
-        assert_eq!(iter.tz, expect);
+        assert_eq!(iter.offsets.original, expect);
+    }
+
+    // Little endian IFD with LONG entries, the values are stored inline
+    // unless they are arrays.
+    fn ifd(offset: u32, entries: &[(ExifTag, &[u32])], next: u32) -> Vec<u8> {
+        let size = 2 + entries.len() * 12 + 4;
+        let mut data_offset = offset + size as u32;
+        let mut ifd = (entries.len() as u16).to_le_bytes().to_vec();
+        let mut data = Vec::new();
+        for (tag, values) in entries {
+            ifd.extend(tag.code().to_le_bytes());
+            ifd.extend(4u16.to_le_bytes());
+            ifd.extend((values.len() as u32).to_le_bytes());
+            if values.len() == 1 {
+                ifd.extend(values[0].to_le_bytes());
+            } else {
+                ifd.extend(data_offset.to_le_bytes());
+                data.extend(values.iter().flat_map(|x| x.to_le_bytes()));
+                data_offset += values.len() as u32 * 4;
+            }
+        }
+        ifd.extend(next.to_le_bytes());
+        [ifd, data].concat()
+    }
+
+    // IFD0 with two sub-IFDs (RAW & preview) pointed by `SubIFDs`, followed
+    // by IFD1 & IFD2 in the IFD chain, which are located after `padding`
+    // bytes (e.g. image data).
+    fn multi_image_tiff(padding: u32) -> Vec<u8> {
+        let (ifd0, sub0, sub1) = (8, 58, 100);
+        let (ifd1, ifd2) = (118 + padding, 136 + padding);
+        [
+            b"II*\0".to_vec(),
+            8u32.to_le_bytes().to_vec(),
+            ifd(
+                ifd0,
+                &[
+                    (ExifTag::NewSubfileType, &[1]),
+                    (ExifTag::ImageWidth, &[160]),
+                    (ExifTag::SubIFDs, &[sub0, sub1]),
+                ],
+                ifd1,
+            ),
+            ifd(
+                sub0,
+                &[
+                    (ExifTag::NewSubfileType, &[0]),
+                    (ExifTag::ImageWidth, &[6000]),
+                    (ExifTag::Compression, &[7]),
+                ],
+                0,
+            ),
+            ifd(sub1, &[(ExifTag::ImageWidth, &[1024])], 0),
+            vec![0; padding as usize],
+            ifd(ifd1, &[(ExifTag::ImageWidth, &[80])], ifd2),
+            ifd(ifd2, &[(ExifTag::ImageWidth, &[40])], 0),
+        ]
+        .concat()
+    }
+
+    #[test_case(0)]
+    #[test_case(100_000)]
+    fn exif_iter_sub_ifds(padding: u32) {
+        let mut parser = MediaParser::new();
+        let ms = MediaSource::unseekable(Cursor::new(multi_image_tiff(padding))).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+
+        let widths: Vec<(String, u32)> = iter
+            .clone()
+            .filter(|x| x.tag() == Some(ExifTag::ImageWidth))
+            .map(|x| {
+                (
+                    x.ifd_id().to_string(),
+                    x.get_value().unwrap().as_u32().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            widths,
+            [
+                ("ifd0".to_string(), 160),
+                ("ifd0.sub0".to_string(), 6000),
+                ("ifd0.sub1".to_string(), 1024),
+                ("ifd1".to_string(), 80),
+                ("ifd2".to_string(), 40),
+            ]
+        );
+
+        let exif: Exif = iter.into();
+        let raw = IfdId::IFD0.with_sub_ifd(0);
+        assert_eq!(
+            exif.ifd_ids(),
+            [
+                IfdId::IFD0,
+                raw,
+                IfdId::IFD0.with_sub_ifd(1),
+                IfdId::new(1),
+                IfdId::new(2)
+            ]
+        );
+        assert_eq!(exif.get(ExifTag::ImageWidth), Some(&EntryValue::U32(160)));
+        assert_eq!(
+            exif.get_in(raw, ExifTag::ImageWidth),
+            Some(&EntryValue::U32(6000))
+        );
+        assert_eq!(
+            exif.get_in(raw, ExifTag::Compression),
+            Some(&EntryValue::U32(7))
+        );
+        assert_eq!(
+            exif.get(ExifTag::SubIFDs),
+            Some(&EntryValue::U32Array(vec![58, 100]))
+        );
+        assert_eq!(
+            exif.get_in(IfdId::new(2), ExifTag::ImageWidth),
+            Some(&EntryValue::U32(40))
+        );
+    }
+
+    #[test]
+    fn exif_iter_too_many_sub_ifds() {
+        let num = super::MAX_SUB_IFDS as u32 + 1;
+        let subs: Vec<u32> = (0..num).map(|i| 46 + i * 18).collect();
+        let mut data = [b"II*\0".to_vec(), 8u32.to_le_bytes().to_vec()].concat();
+        data.extend(ifd(8, &[(ExifTag::SubIFDs, &subs)], 0));
+        for (i, offset) in subs.iter().enumerate() {
+            data.extend(ifd(*offset, &[(ExifTag::ImageWidth, &[i as u32])], 0));
+        }
+
+        let iter: ExifIter = parse_sample(data).unwrap();
+        let exif: Exif = iter.into();
+
+        // The last sub-IFD is ignored
+        let expect: Vec<IfdId> = [IfdId::IFD0]
+            .into_iter()
+            .chain((0..super::MAX_SUB_IFDS).map(|i| IfdId::IFD0.with_sub_ifd(i)))
+            .collect();
+        assert_eq!(exif.ifd_ids(), expect);
+        assert!(exif
+            .get_in(
+                IfdId::IFD0.with_sub_ifd(super::MAX_SUB_IFDS),
+                ExifTag::ImageWidth
+            )
+            .is_none());
+    }
+
+    // Little endian BigTIFF IFD, entries are (tag, data format, components
+    // num, value data)
+    fn bigtiff_ifd(offset: u64, entries: &[(ExifTag, u16, u64, Vec<u8>)], next: u64) -> Vec<u8> {
+        let size = 8 + entries.len() * 20 + 8;
+        let mut data_offset = offset + size as u64;
+        let mut ifd = (entries.len() as u64).to_le_bytes().to_vec();
+        let mut data = Vec::new();
+        for (tag, format, num, value) in entries {
+            ifd.extend(tag.code().to_le_bytes());
+            ifd.extend(format.to_le_bytes());
+            ifd.extend(num.to_le_bytes());
+            if value.len() <= 8 {
+                let mut value = value.clone();
+                value.resize(8, 0);
+                ifd.extend(value);
+            } else {
+                ifd.extend(data_offset.to_le_bytes());
+                data.extend(value);
+                data_offset += value.len() as u64;
+            }
+        }
+        ifd.extend(next.to_le_bytes());
+        [ifd, data].concat()
+    }
+
+    #[test]
+    fn exif_iter_bigtiff() {
+        let u64s = |v: &[u64]| v.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<_>>();
+        let (ifd0, sub0, ifd1) = (16, 136, 172);
+        let data = [
+            b"II+\0\x08\0\0\0".to_vec(),
+            u64s(&[ifd0]),
+            bigtiff_ifd(
+                ifd0,
+                &[
+                    (ExifTag::ImageWidth, 4, 1, 160u32.to_le_bytes().to_vec()),
+                    (ExifTag::Make, 2, 4, b"Big\0".to_vec()),
+                    (
+                        ExifTag::StripOffsets,
+                        16,
+                        3,
+                        u64s(&[1 << 32, 2 << 32, 3 << 32]),
+                    ),
+                    (ExifTag::SubIFDs, 18, 1, u64s(&[sub0])),
+                ],
+                ifd1,
+            ),
+            bigtiff_ifd(sub0, &[(ExifTag::ImageWidth, 16, 1, u64s(&[9000]))], 0),
+            bigtiff_ifd(
+                ifd1,
+                &[(ExifTag::ImageWidth, 4, 1, 80u32.to_le_bytes().to_vec())],
+                0,
+            ),
+        ]
+        .concat();
+
+        let iter: ExifIter = parse_sample(data).unwrap();
+        let exif: Exif = iter.into();
+
+        assert_eq!(exif.get(ExifTag::ImageWidth), Some(&EntryValue::U32(160)));
+        assert_eq!(exif.get(ExifTag::Make).unwrap().as_str(), Some("Big"));
+        assert_eq!(
+            exif.get(ExifTag::StripOffsets),
+            Some(&EntryValue::U64Array(vec![1 << 32, 2 << 32, 3 << 32]))
+        );
+        assert_eq!(
+            exif.get_in(IfdId::IFD0.with_sub_ifd(0), ExifTag::ImageWidth),
+            Some(&EntryValue::U64(9000))
+        );
+        assert_eq!(
+            exif.get_in(IfdId::new(1), ExifTag::ImageWidth),
+            Some(&EntryValue::U32(80))
+        );
+    }
+
+    // Little endian TIFF data with a single IFD0, entries are (tag, data
+    // format, components num, inline value)
+    fn raw_tiff(entries: &[(u16, u16, u32, [u8; 4])]) -> Vec<u8> {
+        let mut data = b"II*\0\x08\0\0\0".to_vec();
+        data.extend((entries.len() as u16).to_le_bytes());
+        for (tag, format, num, value) in entries {
+            data.extend(tag.to_le_bytes());
+            data.extend(format.to_le_bytes());
+            data.extend(num.to_le_bytes());
+            data.extend(value);
+        }
+        data.extend(0u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn exif_iter_continue_after_error() {
+        let data = raw_tiff(&[
+            (ExifTag::Make.code(), 2, 4, *b"abc\0"),
+            // Invalid data format
+            (ExifTag::Model.code(), 99, 1, [0; 4]),
+            // Value is out of range
+            (ExifTag::Artist.code(), 2, 100, [0xff, 0, 0, 0]),
+            (ExifTag::Software.code(), 2, 4, *b"xyz\0"),
+        ]);
+        let entries: Vec<_> = input_into_iter(data, None).unwrap().collect();
+        let results: Vec<_> = entries
+            .iter()
+            .map(|x| (x.tag(), x.get_result().is_ok()))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                (Some(ExifTag::Make), true),
+                (Some(ExifTag::Model), false),
+                (Some(ExifTag::Artist), false),
+                (Some(ExifTag::Software), true),
+            ]
+        );
+        assert_eq!(entries[3].get_value().unwrap(), &"xyz".into());
+    }
+
+    #[test]
+    fn exif_iter_gps_version_id() {
+        let mut writer = ExifWriter::little_endian();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "abc".into())
+            .set(
+                IfdGroup::Gps,
+                ExifTag::GPSVersionID,
+                EntryValue::U8Array(vec![2, 3, 0, 0]),
+            )
+            .set(IfdGroup::Gps, ExifTag::GPSLatitudeRef, "N".into());
+        let iter = input_into_iter(writer.write().unwrap(), None).unwrap();
+        let gps: Vec<_> = iter
+            .clone_and_rewind()
+            .filter(|x| x.group() == IfdGroup::Gps)
+            .map(|x| x.tag())
+            .collect();
+        // GPSVersionID (tag code 0) doesn't end the GPS sub-IFD
+        assert_eq!(
+            gps,
+            [Some(ExifTag::GPSVersionID), Some(ExifTag::GPSLatitudeRef)]
+        );
+        assert_eq!(iter.parse_gps_info().unwrap().unwrap().latitude_ref, 'N');
+
+        // Tag code 0 is invalid in other IFDs, it ends the IFD
+        let data = raw_tiff(&[
+            (0, 3, 1, [1, 0, 0, 0]),
+            (ExifTag::Make.code(), 2, 4, *b"abc\0"),
+        ]);
+        assert_eq!(input_into_iter(data, None).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn exif_iter_gps_print_value() {
+        let coord = |d, m, s| {
+            EntryValue::URationalArray(vec![(d, 1).into(), (m, 1).into(), (s, 100).into()])
+        };
+        let mut writer = ExifWriter::new();
+        writer
+            .set(IfdGroup::Gps, ExifTag::GPSLatitudeRef, "S".into())
+            .set(IfdGroup::Gps, ExifTag::GPSLatitude, coord(22, 31, 5208))
+            .set(IfdGroup::Gps, ExifTag::GPSLongitude, coord(114, 1, 1733));
+        let iter = input_into_iter(writer.write().unwrap(), None).unwrap();
+        let printed: Vec<_> = iter
+            .clone_and_rewind()
+            .filter(|x| x.group() == IfdGroup::Gps)
+            .map(|x| x.print_value().unwrap().to_string())
+            .collect();
+        // GPSLongitudeRef is missing, the coordinate is printed as is
+        assert_eq!(
+            printed,
+            ["South", "22 deg 31' 52.08\" S", "114 deg 1' 17.33\""]
+        );
+
+        // The reference doesn't leak into a rewound iterator
+        let mut iter = iter.clone_and_rewind();
+        let lat = iter
+            .find(|x| x.tag() == Some(ExifTag::GPSLatitude))
+            .unwrap();
+        assert_eq!(
+            lat.print_value().unwrap().to_string(),
+            "22 deg 31' 52.08\" S"
+        );
     }
 }
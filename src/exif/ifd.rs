@@ -1,10 +1,117 @@
-use crate::EntryValue;
-use std::collections::HashMap;
+use crate::{EntryValue, ExifTag};
+use std::{collections::HashMap, fmt::Display};
+
+/// Identifies an IFD (Image File Directory) group in the Exif data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum IfdGroup {
+    /// IFD0, the main image.
+    Ifd0,
+    /// Exif sub-IFD, pointed by `ExifOffset` in IFD0.
+    Exif,
+    /// GPS sub-IFD, pointed by `GPSInfo` in IFD0.
+    Gps,
+    /// Interoperability sub-IFD, pointed by `InteropOffset` in Exif sub-IFD.
+    Interop,
+    /// IFD1, the thumbnail image.
+    Ifd1,
+    /// MakerNote IFD, which has its own (vendor specific) tag namespace.
+    ///
+    /// MakerNotes aren't parsed yet, the whole MakerNote is kept as the
+    /// `MakerNote` entry in [`IfdGroup::Exif`].
+    MakerNote,
+}
+
+/// Identifies an image directory in the Exif data, which is either an IFD in
+/// the IFD chain (IFD0, IFD1, IFD2...), or one of the sub-IFDs pointed by the
+/// `SubIFDs` tag of such an IFD.
+///
+/// Multi-image TIFF files use the IFD chain to store pages, while DNG and
+/// some TIFF based RAW formats (e.g. NEF) store the full-resolution RAW image
+/// and previews in `SubIFDs`.
+///
+/// The Exif & GPS sub-IFDs are not image directories, their entries are
+/// identified by the image directory they belong to.
+///
+/// Only the first 4 sub-IFDs of a `SubIFDs` entry are loaded, the rest are
+/// ignored (with a warning).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IfdId {
+    ifd: usize,
+    sub_ifd: Option<usize>,
+}
+
+impl IfdId {
+    /// IFD0, the main image.
+    pub const IFD0: IfdId = IfdId::new(0);
+
+    /// Identifies the IFD at `index` of the IFD chain, e.g. 1 for IFD1.
+    pub const fn new(index: usize) -> IfdId {
+        IfdId {
+            ifd: index,
+            sub_ifd: None,
+        }
+    }
+
+    /// Identifies the sub-IFD at `index` of the `SubIFDs` array of this IFD.
+    pub const fn with_sub_ifd(self, index: usize) -> IfdId {
+        IfdId {
+            ifd: self.ifd,
+            sub_ifd: Some(index),
+        }
+    }
+
+    /// Index of the IFD in the IFD chain.
+    pub fn ifd_index(&self) -> usize {
+        self.ifd
+    }
+
+    /// Index in the `SubIFDs` array, `None` if this is an IFD in the IFD
+    /// chain.
+    pub fn sub_ifd_index(&self) -> Option<usize> {
+        self.sub_ifd
+    }
+}
+
+impl Display for IfdId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.sub_ifd {
+            Some(sub) => write!(f, "ifd{}.sub{}", self.ifd, sub),
+            None => write!(f, "ifd{}", self.ifd),
+        }
+    }
+}
+
+impl IfdGroup {
+    /// All groups, in the lookup order of tag codes.
+    const ALL: [IfdGroup; 6] = [
+        IfdGroup::Ifd0,
+        IfdGroup::Exif,
+        IfdGroup::Gps,
+        IfdGroup::Interop,
+        IfdGroup::Ifd1,
+        IfdGroup::MakerNote,
+    ];
+
+    /// Returns the tag namespace of the group. IFD0, IFD1 & the Exif sub-IFD
+    /// share the same namespace, while the tag codes of GPS, Interop &
+    /// MakerNote IFDs have their own meanings, e.g. 0x0001 is
+    /// `GPSLatitudeRef` in the GPS IFD, but `InteropIndex` in the Interop
+    /// IFD.
+    pub(crate) fn namespace(self) -> IfdGroup {
+        match self {
+            IfdGroup::Ifd0 | IfdGroup::Exif | IfdGroup::Ifd1 => IfdGroup::Ifd0,
+            g => g,
+        }
+    }
+}
 
 /// https://www.media.mit.edu/pia/Research/deepview/exif.html
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Default)]
 pub(crate) struct ParsedImageFileDirectory {
-    pub entries: HashMap<u16, ParsedIdfEntry>,
+    // Keyed by group as well, since tag codes of different groups may
+    // collide.
+    pub entries: HashMap<(IfdGroup, u16), ParsedIdfEntry>,
 }
 
 impl ParsedImageFileDirectory {
@@ -21,11 +128,27 @@ pub(crate) struct ParsedIdfEntry {
 }
 
 impl ParsedImageFileDirectory {
+    /// Get the entry value by a raw tag code, the groups are looked up in
+    /// the order of [`IfdGroup::ALL`].
     pub(crate) fn get(&self, tag: u16) -> Option<&EntryValue> {
-        self.entries.get(&tag).map(|x| &x.value)
+        IfdGroup::ALL.iter().find_map(|g| self.get_in(*g, tag))
+    }
+
+    pub(crate) fn get_in(&self, group: IfdGroup, tag: u16) -> Option<&EntryValue> {
+        self.entries.get(&(group, tag)).map(|x| &x.value)
+    }
+
+    /// Get the entry value of `tag` in the groups of its namespace.
+    pub(crate) fn get_tag(&self, tag: ExifTag) -> Option<&EntryValue> {
+        let namespace = tag.group().namespace();
+        IfdGroup::ALL
+            .iter()
+            .filter(|g| g.namespace() == namespace)
+            .find_map(|g| self.get_in(*g, tag.code()))
     }
 
-    pub(crate) fn put(&mut self, code: u16, v: EntryValue) {
-        self.entries.insert(code, ParsedIdfEntry { value: v });
+    pub(crate) fn put(&mut self, group: IfdGroup, code: u16, v: EntryValue) {
+        self.entries
+            .insert((group, code), ParsedIdfEntry { value: v });
     }
 }
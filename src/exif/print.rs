@@ -0,0 +1,370 @@
+use std::fmt::Display;
+
+use crate::{
+    values::Rational, ColorSpace, Contrast, CustomRendered, EntryValue, ExifEnum, ExifTag,
+    ExposureMode, ExposureProgram, Flash, GainControl, LightSource, MeteringMode, Orientation,
+    ResolutionUnit, Saturation, SceneCaptureType, SensingMethod, Sharpness, WhiteBalance,
+};
+
+/// A human-readable rendering of an entry value, comparable to the "print
+/// conversion" of exiftool, e.g. `ExposureTime` as `1/125`, `FNumber` as
+/// `f/2.8`, `FocalLength` as `35.0 mm`, `Flash` as `Fired, Return detected`.
+///
+/// The rendering is done by the `Display` impl, the raw value is still
+/// available via [`Self::raw`]. Values which have no special conversion, or
+/// which can't be converted (e.g. a rational with a zero denominator), are
+/// printed as is.
+///
+/// Obtained via [`ParsedExifEntry::print_value`](crate::ParsedExifEntry::print_value)
+/// or [`Exif::print_value`](crate::Exif::print_value).
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+///     let iter: ExifIter = parser.parse(ms)?;
+///     let exif: Exif = iter.into();
+///
+///     let v = exif.print_value(ExifTag::FNumber).unwrap();
+///     assert_eq!(v.to_string(), "f/1.8");
+///     assert_eq!(v.raw(), &EntryValue::URational((175, 100).into()));
+///     assert_eq!(
+///         exif.print_value(ExifTag::ExifVersion).unwrap().to_string(),
+///         "0220"
+///     );
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrintValue<'a> {
+    tag: Option<ExifTag>,
+    value: &'a EntryValue,
+    // The value of the `*Ref` tag of GPS coordinates, e.g. "N"
+    gps_ref: Option<&'a str>,
+}
+
+/// Returns the `*Ref` tag holding the `N`/`S`/`E`/`W` reference of the GPS
+/// coordinate `tag`.
+pub(crate) fn gps_ref_tag(tag: ExifTag) -> Option<ExifTag> {
+    match tag {
+        ExifTag::GPSLatitude => Some(ExifTag::GPSLatitudeRef),
+        ExifTag::GPSLongitude => Some(ExifTag::GPSLongitudeRef),
+        ExifTag::GPSDestLatitude => Some(ExifTag::GPSDestLatitudeRef),
+        ExifTag::GPSDestLongitude => Some(ExifTag::GPSDestLongitudeRef),
+        _ => None,
+    }
+}
+
+impl<'a> PrintValue<'a> {
+    /// Render `value` as the value of `tag`, `None` for unrecognized tags.
+    pub fn new(tag: Option<ExifTag>, value: &'a EntryValue) -> Self {
+        Self {
+            tag,
+            value,
+            gps_ref: None,
+        }
+    }
+
+    /// Set the value of the `*Ref` tag of GPS coordinates (e.g. `"N"` for
+    /// `GPSLatitude`), which is appended to the printed coordinate.
+    pub fn gps_ref(mut self, gps_ref: Option<&'a str>) -> Self {
+        self.gps_ref = gps_ref;
+        self
+    }
+
+    pub fn tag(&self) -> Option<ExifTag> {
+        self.tag
+    }
+
+    /// Returns the raw value.
+    pub fn raw(&self) -> &'a EntryValue {
+        self.value
+    }
+
+    fn convert(&self) -> Option<String> {
+        let v = self.value;
+        let s = match self.tag? {
+            ExifTag::ExposureTime => exposure_time(v.to_f64()?),
+            ExifTag::ShutterSpeedValue => exposure_time(2f64.powf(-v.to_f64()?)),
+            ExifTag::FNumber => format!("f/{:.1}", v.to_f64()?),
+            ExifTag::ApertureValue | ExifTag::MaxApertureValue => {
+                format!("f/{:.1}", 2f64.powf(v.to_f64()? / 2.0))
+            }
+            ExifTag::FocalLength => format!("{:.1} mm", v.to_f64()?),
+            ExifTag::FocalLengthIn35mmFilm => format!("{} mm", v.to_f64()?),
+            ExifTag::ExposureBiasValue => fraction(v.to_f64()?),
+            ExifTag::SubjectDistance => format!("{} m", number(v.to_f64()?)),
+            ExifTag::GPSAltitude => format!("{:.1} m", v.to_f64()?),
+            ExifTag::GPSAltitudeRef => match v.to_f64()? as u8 {
+                0 => "Above Sea Level".to_owned(),
+                1 => "Below Sea Level".to_owned(),
+                _ => return None,
+            },
+            ExifTag::GPSLatitudeRef | ExifTag::GPSDestLatitudeRef => match v.as_str()? {
+                "N" => "North".to_owned(),
+                "S" => "South".to_owned(),
+                _ => return None,
+            },
+            ExifTag::GPSLongitudeRef | ExifTag::GPSDestLongitudeRef => match v.as_str()? {
+                "E" => "East".to_owned(),
+                "W" => "West".to_owned(),
+                _ => return None,
+            },
+            ExifTag::GPSLatitude
+            | ExifTag::GPSLongitude
+            | ExifTag::GPSDestLatitude
+            | ExifTag::GPSDestLongitude => {
+                let dms = dms(&components(v)?)?;
+                match self.gps_ref {
+                    Some(r) => format!("{dms} {r}"),
+                    None => dms,
+                }
+            }
+            ExifTag::GPSTimeStamp => time_stamp(&components(v)?)?,
+            ExifTag::ExifVersion | ExifTag::FlashPixVersion | ExifTag::InteropVersion => {
+                let EntryValue::Undefined(bytes) = v else {
+                    return None;
+                };
+                if !bytes.iter().all(|b| b.is_ascii_alphanumeric()) {
+                    return None;
+                }
+                String::from_utf8(bytes.to_owned()).ok()?
+            }
+            ExifTag::ComponentsConfiguration => {
+                let EntryValue::Undefined(bytes) = v else {
+                    return None;
+                };
+                bytes
+                    .iter()
+                    .map(|b| match b {
+                        1 => "Y",
+                        2 => "Cb",
+                        3 => "Cr",
+                        4 => "R",
+                        5 => "G",
+                        6 => "B",
+                        _ => "-",
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+            ExifTag::LensSpecification => lens_spec(&components(v)?)?,
+            ExifTag::Orientation => typed::<Orientation>(v),
+            ExifTag::ResolutionUnit | ExifTag::FocalPlaneResolutionUnit => {
+                typed::<ResolutionUnit>(v)
+            }
+            ExifTag::ExposureProgram => typed::<ExposureProgram>(v),
+            ExifTag::MeteringMode => typed::<MeteringMode>(v),
+            ExifTag::LightSource => typed::<LightSource>(v),
+            ExifTag::Flash => typed::<Flash>(v),
+            ExifTag::ColorSpace => typed::<ColorSpace>(v),
+            ExifTag::SensingMethod => typed::<SensingMethod>(v),
+            ExifTag::CustomRendered => typed::<CustomRendered>(v),
+            ExifTag::ExposureMode => typed::<ExposureMode>(v),
+            ExifTag::WhiteBalanceMode => typed::<WhiteBalance>(v),
+            ExifTag::SceneCaptureType => typed::<SceneCaptureType>(v),
+            ExifTag::GainControl => typed::<GainControl>(v),
+            ExifTag::Contrast => typed::<Contrast>(v),
+            ExifTag::Saturation => typed::<Saturation>(v),
+            ExifTag::Sharpness => typed::<Sharpness>(v),
+            _ => return None,
+        };
+        Some(s)
+    }
+}
+
+impl Display for PrintValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(s) = self.convert() {
+            return Display::fmt(&s, f);
+        }
+        match self.value {
+            EntryValue::Time(t) => Display::fmt(&t.format("%Y:%m:%d %H:%M:%S%:z"), f),
+            EntryValue::NaiveDateTime(t) => Display::fmt(&t.format("%Y:%m:%d %H:%M:%S"), f),
+            EntryValue::URational(_) | EntryValue::IRational(_) => match self.value.to_f64() {
+                Some(n) => Display::fmt(&number(n), f),
+                None => Display::fmt(self.value, f),
+            },
+            v => Display::fmt(v, f),
+        }
+    }
+}
+
+fn typed<T: ExifEnum + Display>(v: &EntryValue) -> String {
+    match T::from_entry(v) {
+        Ok(x) => x.to_string(),
+        Err(_) => format!("Unknown ({v})"),
+    }
+}
+
+fn rational<T: Copy + Into<f64>>(r: &Rational<T>) -> Option<f64> {
+    let n = r.as_float();
+    n.is_finite().then_some(n)
+}
+
+/// Numeric values of the components of a rational array, `None` for the
+/// components with a zero denominator.
+fn components(v: &EntryValue) -> Option<Vec<Option<f64>>> {
+    match v {
+        EntryValue::URationalArray(v) => Some(v.iter().map(rational).collect()),
+        EntryValue::IRationalArray(v) => Some(v.iter().map(rational).collect()),
+        _ => None,
+    }
+}
+
+/// Up to 4 decimals, without trailing zeros.
+fn number(n: f64) -> String {
+    let s = format!("{n:.4}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_owned()
+    } else {
+        s.to_owned()
+    }
+}
+
+fn exposure_time(secs: f64) -> String {
+    if secs > 0.0 && secs < 0.25001 {
+        format!("1/{}", (0.5 + 1.0 / secs) as u64)
+    } else {
+        number((secs * 10.0).round() / 10.0)
+    }
+}
+
+/// e.g. `+1/3`, `-2`, `0`
+fn fraction(n: f64) -> String {
+    let n = n * 1.00001;
+    if n.abs() < 1e-5 {
+        return "0".to_owned();
+    }
+    for d in [1.0, 2.0, 3.0] {
+        let t = (n * d).trunc();
+        if t / (n * d) > 0.999 {
+            return if d == 1.0 {
+                format!("{t:+}")
+            } else {
+                format!("{t:+}/{d}")
+            };
+        }
+    }
+    format!("{n:+.2}")
+}
+
+/// e.g. `48 deg 51' 27.72"`
+fn dms(v: &[Option<f64>]) -> Option<String> {
+    let [d, m, s] = v else {
+        return None;
+    };
+    let total = (*d)? + (*m)? / 60.0 + (*s)? / 3600.0;
+    if total < 0.0 {
+        return None;
+    }
+    // Round to 1/100 second first, so there will be no `60.00"`
+    let total = (total * 360000.0).round() as u64;
+    let (deg, rest) = (total / 360000, total % 360000);
+    let (min, sec) = (rest / 6000, rest % 6000);
+    Some(format!("{deg} deg {min}' {}.{:02}\"", sec / 100, sec % 100))
+}
+
+/// e.g. `08:09:57`
+fn time_stamp(v: &[Option<f64>]) -> Option<String> {
+    let [h, m, s] = v else {
+        return None;
+    };
+    let s = (*s)?;
+    let s = if s.fract() == 0.0 {
+        format!("{:02}", s as u32)
+    } else {
+        format!("{s:05.2}")
+    };
+    Some(format!("{:02}:{:02}:{s}", (*h)? as u32, (*m)? as u32))
+}
+
+/// e.g. `24-70mm f/2.8`, `50mm f/1.4`
+fn lens_spec(v: &[Option<f64>]) -> Option<String> {
+    let [min_fl, max_fl, min_f, max_f] = v else {
+        return None;
+    };
+    let range = |a: &Option<f64>, b: &Option<f64>| {
+        let a = a.map(number).unwrap_or_else(|| "?".to_owned());
+        let b = b.map(number).unwrap_or_else(|| "?".to_owned());
+        if a == b {
+            a
+        } else {
+            format!("{a}-{b}")
+        }
+    };
+    Some(format!(
+        "{}mm f/{}",
+        range(min_fl, max_fl),
+        range(min_f, max_f)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use test_case::test_case;
+
+    #[test_case(ExifTag::ExposureTime, (1, 125).into() => "1/125")]
+    #[test_case(ExifTag::ExposureTime, (10, 1250).into() => "1/125")]
+    #[test_case(ExifTag::ExposureTime, (1, 2).into() => "0.5")]
+    #[test_case(ExifTag::ExposureTime, (30, 1).into() => "30")]
+    #[test_case(ExifTag::ExposureTime, (1, 0).into() => "1/0 (inf)")]
+    #[test_case(ExifTag::ShutterSpeedValue, (6965784, 1000000).into() => "1/125")]
+    #[test_case(ExifTag::FNumber, (28, 10).into() => "f/2.8")]
+    #[test_case(ExifTag::ApertureValue, (297, 100).into() => "f/2.8")]
+    #[test_case(ExifTag::FocalLength, (35, 1).into() => "35.0 mm")]
+    #[test_case(ExifTag::FocalLengthIn35mmFilm, 50_u16.into() => "50 mm")]
+    #[test_case(ExifTag::ExposureBiasValue, (-2, 3).into() => "-2/3")]
+    #[test_case(ExifTag::ExposureBiasValue, (1, 2).into() => "+1/2")]
+    #[test_case(ExifTag::ExposureBiasValue, (0, 1).into() => "0")]
+    #[test_case(ExifTag::ExposureBiasValue, (-1, 1).into() => "-1")]
+    #[test_case(ExifTag::Flash, 7_u16.into() => "Fired, Return detected")]
+    #[test_case(ExifTag::Orientation, 6_u16.into() => "Rotate 90 CW")]
+    #[test_case(ExifTag::Orientation, 9_u16.into() => "Unknown (9)")]
+    #[test_case(ExifTag::FocalPlaneResolutionUnit, 3_u16.into() => "cm")]
+    #[test_case(ExifTag::ExifVersion, EntryValue::Undefined(b"0232".to_vec()) => "0232")]
+    #[test_case(ExifTag::ComponentsConfiguration, EntryValue::Undefined(vec![1, 2, 3, 0]) => "Y, Cb, Cr, -")]
+    #[test_case(ExifTag::GPSLatitudeRef, "S".into() => "South")]
+    #[test_case(ExifTag::GPSAltitude, (1234, 10).into() => "123.4 m")]
+    #[test_case(ExifTag::GPSLatitude, EntryValue::URationalArray(vec![(48, 1).into(), (51, 1).into(), (2772, 100).into()]) => "48 deg 51' 27.72\"")]
+    #[test_case(ExifTag::GPSLongitude, EntryValue::URationalArray(vec![(2, 1).into(), (1767, 100).into(), (0, 1).into()]) => "2 deg 17' 40.20\"")]
+    #[test_case(ExifTag::GPSTimeStamp, EntryValue::URationalArray(vec![(8, 1).into(), (9, 1).into(), (57, 1).into()]) => "08:09:57")]
+    #[test_case(ExifTag::LensSpecification, EntryValue::URationalArray(vec![(24, 1).into(), (70, 1).into(), (28, 10).into(), (28, 10).into()]) => "24-70mm f/2.8")]
+    #[test_case(ExifTag::LensSpecification, EntryValue::URationalArray(vec![(50, 1).into(), (50, 1).into(), (14, 10).into(), (0, 0).into()]) => "50mm f/1.4-?")]
+    #[test_case(ExifTag::XResolution, (72, 1).into() => "72")]
+    #[test_case(ExifTag::Make, "Apple".into() => "Apple")]
+    fn print_value(tag: ExifTag, v: EntryValue) -> String {
+        PrintValue::new(Some(tag), &v).to_string()
+    }
+
+    #[test]
+    fn print_value_others() {
+        let v =
+            EntryValue::Time(DateTime::parse_from_rfc3339("2023-07-09T20:36:33+08:00").unwrap());
+        assert_eq!(
+            PrintValue::new(Some(ExifTag::DateTimeOriginal), &v).to_string(),
+            "2023:07:09 20:36:33+08:00"
+        );
+
+        let v: EntryValue = 7_u16.into();
+        let p = PrintValue::new(None, &v);
+        assert_eq!(p.to_string(), "7");
+        assert_eq!(p.raw(), &v);
+
+        let v = EntryValue::URationalArray(vec![(48, 1).into(), (30, 1).into(), (0, 1).into()]);
+        let p = PrintValue::new(Some(ExifTag::GPSLatitude), &v).gps_ref(Some("N"));
+        assert_eq!(p.to_string(), "48 deg 30' 0.00\" N");
+
+        let v =
+            EntryValue::IRationalArray(vec![(22, 1).into(), (31, 1).into(), (5208, 100).into()]);
+        let p = PrintValue::new(Some(ExifTag::GPSLatitude), &v);
+        assert_eq!(p.to_string(), "22 deg 31' 52.08\"");
+    }
+}
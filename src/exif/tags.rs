@@ -6,6 +6,8 @@ use std::fmt::{Debug, Display};
 #[cfg(feature = "json_dump")]
 use serde::{Deserialize, Serialize};
 
+use crate::{values::DataFormat, IfdGroup};
+
 #[allow(unused)]
 #[cfg_attr(feature = "json_dump", derive(Serialize, Deserialize))]
 #[derive(Eq, PartialEq, Hash, Clone, Copy)]
@@ -36,6 +38,17 @@ impl ExifTagCode {
     }
 }
 
+impl ExifTagCode {
+    /// Recognize `code` in the tag namespace of `group`, see
+    /// [`ExifTag::from_code_in`].
+    pub(crate) fn from_code_in(group: IfdGroup, code: u16) -> Self {
+        match ExifTag::from_code_in(group, code) {
+            Some(tag) => ExifTagCode::Tag(tag),
+            None => ExifTagCode::Code(code),
+        }
+    }
+}
+
 impl From<u16> for ExifTagCode {
     fn from(v: u16) -> Self {
         let tag: crate::Result<ExifTag> = v.try_into();
@@ -65,156 +78,487 @@ impl Display for ExifTagCode {
     }
 }
 
-/// Defines recognized Exif tags. All tags can be parsed, no matter if it is
-/// defined here. This enum definition is just for ease of use.
+/// Definition of a tag in the catalogue: the IFD group it belongs to, and the
+/// data formats & components number defined by the specifications.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TagSpec {
+    pub group: IfdGroup,
+    /// Allowed data formats, empty means any format.
+    pub formats: &'static [DataFormat],
+    /// Expected components number, `None` means any number.
+    pub count: Option<u32>,
+}
+
+impl TagSpec {
+    /// Check if an entry conforms to the definition.
+    pub(crate) fn accepts(&self, format: DataFormat, count: u64) -> bool {
+        (self.formats.is_empty() || self.formats.contains(&format))
+            && self.count.map_or(true, |x| x as u64 == count)
+    }
+}
+
+// Data formats of the catalogue
+const ANY: &[DataFormat] = &[];
+const BYTE: &[DataFormat] = &[DataFormat::U8];
+const ASCII: &[DataFormat] = &[DataFormat::Text, DataFormat::Utf8];
+const SHORT: &[DataFormat] = &[DataFormat::U16];
+const LONG: &[DataFormat] = &[DataFormat::U32];
+const SHORT_LONG: &[DataFormat] = &[DataFormat::U16, DataFormat::U32];
+const SSHORT: &[DataFormat] = &[DataFormat::I16];
+const RATIONAL: &[DataFormat] = &[DataFormat::URational];
+const SRATIONAL: &[DataFormat] = &[DataFormat::IRational];
+const UNDEFINED: &[DataFormat] = &[DataFormat::Undefined];
+const FLOAT: &[DataFormat] = &[DataFormat::F32];
+const DOUBLE: &[DataFormat] = &[DataFormat::F64];
+const BYTES: &[DataFormat] = &[DataFormat::U8, DataFormat::Undefined];
+// 64-bit offsets are used by BigTIFF
+const IFD: &[DataFormat] = &[
+    DataFormat::U32,
+    DataFormat::Ifd,
+    DataFormat::U64,
+    DataFormat::Ifd64,
+];
+const OFFSETS: &[DataFormat] = &[DataFormat::U16, DataFormat::U32, DataFormat::U64];
+const SIZE: &[DataFormat] = &[DataFormat::U16, DataFormat::U32, DataFormat::URational];
+// Text which may be stored as ASCII or bytes (DNG)
+const TEXT_BYTES: &[DataFormat] = &[DataFormat::Text, DataFormat::Utf8, DataFormat::U8];
+const IPTC: &[DataFormat] = &[DataFormat::U32, DataFormat::U8, DataFormat::Undefined];
+const NEUTRAL: &[DataFormat] = &[DataFormat::U16, DataFormat::URational];
+
+macro_rules! tag_count {
+    (_) => {
+        None
+    };
+    ($n:literal) => {
+        Some($n)
+    };
+}
+
+/// Generates [`ExifTag`] and its name & code conversions from the catalogue
+/// of the tags.
 ///
-/// You can always get the entry value by raw tag code which is an `u16` value.
-/// See [`ParsedExifEntry::tag_code`](crate::ParsedExifEntry::tag_code) and
-/// [`Exif::get_by_tag_code`](crate::Exif::get_by_tag_code).
-#[allow(unused)]
-#[cfg_attr(feature = "json_dump", derive(Serialize, Deserialize))]
-#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
-#[non_exhaustive]
-pub enum ExifTag {
-    Make = 0x0000_010f,
-    Model = 0x0000_0110,
-    Orientation = 0x0000_0112,
-
-    ImageWidth = 0x0000_0100,
-    ImageHeight = 0x0000_0101,
-
-    ISOSpeedRatings = 0x0000_8827,
-    ShutterSpeedValue = 0x0000_9201,
-    ExposureTime = 0x0000_829a,
-    FNumber = 0x0000_829d,
-
-    ExifImageWidth = 0x0000_a002,
-    ExifImageHeight = 0x0000_a003,
-
-    DateTimeOriginal = 0x0000_9003,
-    CreateDate = 0x0000_9004,
-    ModifyDate = 0x0000_0132,
-    OffsetTime = 0x0000_9010,
-    OffsetTimeOriginal = 0x0000_9011,
-    OffsetTimeDigitized = 0x0000_9012,
-
-    GPSLatitudeRef = 0x00001,
-    GPSLatitude = 0x00002,
-    GPSLongitudeRef = 0x00003,
-    GPSLongitude = 0x00004,
-    GPSAltitudeRef = 0x00005,
-    GPSAltitude = 0x00006,
-    GPSVersionID = 0x00000,
-
-    // sub ifd
-    ExifOffset = 0x0000_8769,
-    GPSInfo = 0x0000_8825,
-
-    ImageDescription = 0x0000_010e,
-    XResolution = 0x0000_011a,
-    YResolution = 0x0000_011b,
-    ResolutionUnit = 0x0000_0128,
-    Software = 0x0000_0131,
-    HostComputer = 0x0000_013c,
-    WhitePoint = 0x0000_013e,
-    PrimaryChromaticities = 0x0000_013f,
-    YCbCrCoefficients = 0x0000_0211,
-    ReferenceBlackWhite = 0x0000_0214,
-    Copyright = 0x0000_8298,
-
-    ExposureProgram = 0x0000_8822,
-    SpectralSensitivity = 0x0000_8824,
-    OECF = 0x0000_8828,
-    SensitivityType = 0x0000_8830,
-    ExifVersion = 0x0000_9000,
-    ApertureValue = 0x0000_9202,
-    BrightnessValue = 0x0000_9203,
-    ExposureBiasValue = 0x0000_9204,
-    MaxApertureValue = 0x0000_9205,
-    SubjectDistance = 0x0000_9206,
-    MeteringMode = 0x0000_9207,
-    LightSource = 0x0000_9208,
-    Flash = 0x0000_9209,
-    FocalLength = 0x0000_920a,
-    SubjectArea = 0x0000_9214,
-    MakerNote = 0x0000_927c,
-    UserComment = 0x0000_9286,
-    FlashPixVersion = 0x0000_a000,
-    ColorSpace = 0x0000_a001,
-    RelatedSoundFile = 0x0000_a004,
-    FlashEnergy = 0x0000_a20b,
-    FocalPlaneXResolution = 0x0000_a20e,
-    FocalPlaneYResolution = 0x0000_a20f,
-    FocalPlaneResolutionUnit = 0x0000_a210,
-    SubjectLocation = 0x0000_a214,
-    ExposureIndex = 0x0000_a215,
-    SensingMethod = 0x0000_a217,
-    FileSource = 0x0000_a300,
-    SceneType = 0x0000_a301,
-    CFAPattern = 0x0000_a302,
-    CustomRendered = 0x0000_a401,
-    ExposureMode = 0x0000_a402,
-    WhiteBalanceMode = 0x0000_a403,
-    DigitalZoomRatio = 0x0000_a404,
-    FocalLengthIn35mmFilm = 0x0000_a405,
-    SceneCaptureType = 0x0000_a406,
-    GainControl = 0x0000_a407,
-    Contrast = 0x0000_a408,
-    Saturation = 0x0000_a409,
-    Sharpness = 0x0000_a40a,
-    DeviceSettingDescription = 0x0000_a40b,
-    SubjectDistanceRange = 0x0000_a40c,
-    ImageUniqueID = 0x0000_a420,
-    LensSpecification = 0x0000_a432,
-    LensMake = 0x0000_a433,
-    LensModel = 0x0000_a434,
-    Gamma = 0x0000_a500,
-
-    GPSTimeStamp = 0x00007,
-    GPSSatellites = 0x00008,
-    GPSStatus = 0x00009,
-    GPSMeasureMode = 0x0000a,
-    GPSDOP = 0x0000b,
-    GPSSpeedRef = 0x0000c,
-    GPSSpeed = 0x0000d,
-    GPSTrackRef = 0x0000e,
-    GPSTrack = 0x0000f,
-    GPSImgDirectionRef = 0x0000_0010,
-    GPSImgDirection = 0x0000_0011,
-    GPSMapDatum = 0x0000_0012,
-    GPSDestLatitudeRef = 0x0000_0013,
-    GPSDestLatitude = 0x0000_0014,
-    GPSDestLongitudeRef = 0x0000_0015,
-    GPSDestLongitude = 0x0000_0016,
-    GPSDestBearingRef = 0x0000_0017,
-    GPSDestBearing = 0x0000_0018,
-    GPSDestDistanceRef = 0x0000_0019,
-    GPSDestDistance = 0x0000_001a,
-    GPSProcessingMethod = 0x0000_001b,
-    GPSAreaInformation = 0x0000_001c,
-    GPSDateStamp = 0x0000_001d,
-    GPSDifferential = 0x0000_001e,
-
-    YCbCrPositioning = 0x0000_0213,
-    RecommendedExposureIndex = 0x0000_8832,
-    SubSecTimeDigitized = 0x0000_9292,
-    SubSecTimeOriginal = 0x0000_9291,
-    SubSecTime = 0x0000_9290,
-    InteropOffset = 0x0000_a005,
-    ComponentsConfiguration = 0x0000_9101,
-    ThumbnailOffset = 0x0000_0201,
-    ThumbnailLength = 0x0000_0202,
-    Compression = 0x0000_0103,
-    BitsPerSample = 0x0000_0102,
-    PhotometricInterpretation = 0x0000_0106,
-    SamplesPerPixel = 0x0000_0115,
-    RowsPerStrip = 0x0000_0116,
-    PlanarConfiguration = 0x0000_011c,
+/// Tag codes of different IFD groups may collide (GPS & Interop), the tag
+/// listed first wins when converting from a code.
+macro_rules! exif_tags {
+    ($($tag:ident: $code:literal, $group:ident, $formats:ident, $count:tt;)*) => {
+        /// Defines recognized Exif tags. All tags can be parsed, no matter if
+        /// it is defined here. This enum definition is just for ease of use.
+        ///
+        /// The standard tags of TIFF 6.0, Exif 3.0 (including the GPS &
+        /// Interoperability IFDs), and DNG are defined, as well as the
+        /// Windows `XP*` tags.
+        ///
+        /// You can always get the entry value by raw tag code which is an
+        /// `u16` value. See
+        /// [`ParsedExifEntry::tag_code`](crate::ParsedExifEntry::tag_code) and
+        /// [`Exif::get_by_tag_code`](crate::Exif::get_by_tag_code).
+        #[allow(unused)]
+        #[cfg_attr(feature = "json_dump", derive(Serialize, Deserialize))]
+        #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+        #[non_exhaustive]
+        pub enum ExifTag {
+            $($tag,)*
+        }
+
+        impl ExifTag {
+            pub const fn code(self) -> u16 {
+                match self {
+                    $(ExifTag::$tag => $code,)*
+                }
+            }
+
+            pub(crate) const fn spec(self) -> &'static TagSpec {
+                match self {
+                    $(ExifTag::$tag => &TagSpec {
+                        group: IfdGroup::$group,
+                        formats: $formats,
+                        count: tag_count!($count),
+                    },)*
+                }
+            }
+
+            pub(crate) const ALL: &[ExifTag] = &[$(ExifTag::$tag,)*];
+        }
+
+        impl From<ExifTag> for &str {
+            fn from(value: ExifTag) -> Self {
+                match value {
+                    $(ExifTag::$tag => stringify!($tag),)*
+                }
+            }
+        }
+
+        impl TryFrom<u16> for ExifTag {
+            type Error = crate::Error;
+
+            // Colliding codes are unreachable, see `exif_tags`
+            #[allow(unreachable_patterns)]
+            fn try_from(v: u16) -> Result<Self, Self::Error> {
+                match v {
+                    $($code => Ok(ExifTag::$tag),)*
+                    o => Err(format!("Unrecognized ExifTag 0x{o:04x}").into()),
+                }
+            }
+        }
+    };
+}
+
+exif_tags! {
+    // TIFF 6.0
+    NewSubfileType: 0x00fe, Ifd0, LONG, 1;
+    SubfileType: 0x00ff, Ifd0, SHORT, 1;
+    ImageWidth: 0x0100, Ifd0, SHORT_LONG, 1;
+    ImageHeight: 0x0101, Ifd0, SHORT_LONG, 1;
+    BitsPerSample: 0x0102, Ifd0, SHORT, _;
+    Compression: 0x0103, Ifd0, SHORT, 1;
+    PhotometricInterpretation: 0x0106, Ifd0, SHORT, 1;
+    Thresholding: 0x0107, Ifd0, SHORT, 1;
+    CellWidth: 0x0108, Ifd0, SHORT, 1;
+    CellLength: 0x0109, Ifd0, SHORT, 1;
+    FillOrder: 0x010a, Ifd0, SHORT, 1;
+    DocumentName: 0x010d, Ifd0, ASCII, _;
+    ImageDescription: 0x010e, Ifd0, ASCII, _;
+    Make: 0x010f, Ifd0, ASCII, _;
+    Model: 0x0110, Ifd0, ASCII, _;
+    StripOffsets: 0x0111, Ifd0, OFFSETS, _;
+    Orientation: 0x0112, Ifd0, SHORT, 1;
+    SamplesPerPixel: 0x0115, Ifd0, SHORT, 1;
+    RowsPerStrip: 0x0116, Ifd0, SHORT_LONG, 1;
+    StripByteCounts: 0x0117, Ifd0, OFFSETS, _;
+    MinSampleValue: 0x0118, Ifd0, SHORT, _;
+    MaxSampleValue: 0x0119, Ifd0, SHORT, _;
+    XResolution: 0x011a, Ifd0, RATIONAL, 1;
+    YResolution: 0x011b, Ifd0, RATIONAL, 1;
+    PlanarConfiguration: 0x011c, Ifd0, SHORT, 1;
+    PageName: 0x011d, Ifd0, ASCII, _;
+    XPosition: 0x011e, Ifd0, RATIONAL, 1;
+    YPosition: 0x011f, Ifd0, RATIONAL, 1;
+    FreeOffsets: 0x0120, Ifd0, LONG, _;
+    FreeByteCounts: 0x0121, Ifd0, LONG, _;
+    GrayResponseUnit: 0x0122, Ifd0, SHORT, 1;
+    GrayResponseCurve: 0x0123, Ifd0, SHORT, _;
+    T4Options: 0x0124, Ifd0, LONG, 1;
+    T6Options: 0x0125, Ifd0, LONG, 1;
+    ResolutionUnit: 0x0128, Ifd0, SHORT, 1;
+    PageNumber: 0x0129, Ifd0, SHORT, 2;
+    TransferFunction: 0x012d, Ifd0, SHORT, _;
+    Software: 0x0131, Ifd0, ASCII, _;
+    ModifyDate: 0x0132, Ifd0, ASCII, _;
+    Artist: 0x013b, Ifd0, ASCII, _;
+    HostComputer: 0x013c, Ifd0, ASCII, _;
+    Predictor: 0x013d, Ifd0, SHORT, 1;
+    WhitePoint: 0x013e, Ifd0, RATIONAL, 2;
+    PrimaryChromaticities: 0x013f, Ifd0, RATIONAL, 6;
+    ColorMap: 0x0140, Ifd0, SHORT, _;
+    HalftoneHints: 0x0141, Ifd0, SHORT, 2;
+    TileWidth: 0x0142, Ifd0, SHORT_LONG, 1;
+    TileLength: 0x0143, Ifd0, SHORT_LONG, 1;
+    TileOffsets: 0x0144, Ifd0, OFFSETS, _;
+    TileByteCounts: 0x0145, Ifd0, OFFSETS, _;
+    SubIFDs: 0x014a, Ifd0, IFD, _;
+    InkSet: 0x014c, Ifd0, SHORT, 1;
+    InkNames: 0x014d, Ifd0, ASCII, _;
+    NumberOfInks: 0x014e, Ifd0, SHORT, 1;
+    DotRange: 0x0150, Ifd0, ANY, _;
+    TargetPrinter: 0x0151, Ifd0, ASCII, _;
+    ExtraSamples: 0x0152, Ifd0, SHORT, _;
+    SampleFormat: 0x0153, Ifd0, SHORT, _;
+    SMinSampleValue: 0x0154, Ifd0, ANY, _;
+    SMaxSampleValue: 0x0155, Ifd0, ANY, _;
+    TransferRange: 0x0156, Ifd0, SHORT, 6;
+    JPEGTables: 0x015b, Ifd0, UNDEFINED, _;
+    JPEGProc: 0x0200, Ifd0, SHORT, 1;
+    ThumbnailOffset: 0x0201, Ifd1, LONG, 1;
+    ThumbnailLength: 0x0202, Ifd1, LONG, 1;
+    YCbCrCoefficients: 0x0211, Ifd0, RATIONAL, 3;
+    YCbCrSubSampling: 0x0212, Ifd0, SHORT, 2;
+    YCbCrPositioning: 0x0213, Ifd0, SHORT, 1;
+    ReferenceBlackWhite: 0x0214, Ifd0, RATIONAL, 6;
+    XMLPacket: 0x02bc, Ifd0, BYTES, _;
+    Rating: 0x4746, Ifd0, SHORT, 1;
+    RatingPercent: 0x4749, Ifd0, SHORT, 1;
+    Copyright: 0x8298, Ifd0, ASCII, _;
+    IPTCNAA: 0x83bb, Ifd0, IPTC, _;
+    ImageResources: 0x8649, Ifd0, BYTES, _;
+    ExifOffset: 0x8769, Ifd0, IFD, 1;
+    ICCProfile: 0x8773, Ifd0, BYTES, _;
+    GPSInfo: 0x8825, Ifd0, IFD, 1;
+    TimeZoneOffset: 0x882a, Ifd0, SSHORT, _;
+    XPTitle: 0x9c9b, Ifd0, BYTE, _;
+    XPComment: 0x9c9c, Ifd0, BYTE, _;
+    XPAuthor: 0x9c9d, Ifd0, BYTE, _;
+    XPKeywords: 0x9c9e, Ifd0, BYTE, _;
+    XPSubject: 0x9c9f, Ifd0, BYTE, _;
+
+    // Exif 3.0
+    ExposureTime: 0x829a, Exif, RATIONAL, 1;
+    FNumber: 0x829d, Exif, RATIONAL, 1;
+    ExposureProgram: 0x8822, Exif, SHORT, 1;
+    SpectralSensitivity: 0x8824, Exif, ASCII, _;
+    ISOSpeedRatings: 0x8827, Exif, SHORT, _;
+    OECF: 0x8828, Exif, UNDEFINED, _;
+    SensitivityType: 0x8830, Exif, SHORT, 1;
+    StandardOutputSensitivity: 0x8831, Exif, LONG, 1;
+    RecommendedExposureIndex: 0x8832, Exif, LONG, 1;
+    ISOSpeed: 0x8833, Exif, LONG, 1;
+    ISOSpeedLatitudeyyy: 0x8834, Exif, LONG, 1;
+    ISOSpeedLatitudezzz: 0x8835, Exif, LONG, 1;
+    ExifVersion: 0x9000, Exif, UNDEFINED, 4;
+    DateTimeOriginal: 0x9003, Exif, ASCII, _;
+    CreateDate: 0x9004, Exif, ASCII, _;
+    OffsetTime: 0x9010, Exif, ASCII, _;
+    OffsetTimeOriginal: 0x9011, Exif, ASCII, _;
+    OffsetTimeDigitized: 0x9012, Exif, ASCII, _;
+    ComponentsConfiguration: 0x9101, Exif, UNDEFINED, 4;
+    CompressedBitsPerPixel: 0x9102, Exif, RATIONAL, 1;
+    ShutterSpeedValue: 0x9201, Exif, SRATIONAL, 1;
+    ApertureValue: 0x9202, Exif, RATIONAL, 1;
+    BrightnessValue: 0x9203, Exif, SRATIONAL, 1;
+    ExposureBiasValue: 0x9204, Exif, SRATIONAL, 1;
+    MaxApertureValue: 0x9205, Exif, RATIONAL, 1;
+    SubjectDistance: 0x9206, Exif, RATIONAL, 1;
+    MeteringMode: 0x9207, Exif, SHORT, 1;
+    LightSource: 0x9208, Exif, SHORT, 1;
+    Flash: 0x9209, Exif, SHORT, 1;
+    FocalLength: 0x920a, Exif, RATIONAL, 1;
+    SubjectArea: 0x9214, Exif, SHORT, _;
+    MakerNote: 0x927c, Exif, UNDEFINED, _;
+    UserComment: 0x9286, Exif, UNDEFINED, _;
+    SubSecTime: 0x9290, Exif, ASCII, _;
+    SubSecTimeOriginal: 0x9291, Exif, ASCII, _;
+    SubSecTimeDigitized: 0x9292, Exif, ASCII, _;
+    Temperature: 0x9400, Exif, SRATIONAL, 1;
+    Humidity: 0x9401, Exif, RATIONAL, 1;
+    Pressure: 0x9402, Exif, RATIONAL, 1;
+    WaterDepth: 0x9403, Exif, SRATIONAL, 1;
+    Acceleration: 0x9404, Exif, RATIONAL, 1;
+    CameraElevationAngle: 0x9405, Exif, SRATIONAL, 1;
+    FlashPixVersion: 0xa000, Exif, UNDEFINED, 4;
+    ColorSpace: 0xa001, Exif, SHORT, 1;
+    ExifImageWidth: 0xa002, Exif, SHORT_LONG, 1;
+    ExifImageHeight: 0xa003, Exif, SHORT_LONG, 1;
+    RelatedSoundFile: 0xa004, Exif, ASCII, _;
+    InteropOffset: 0xa005, Exif, IFD, 1;
+    FlashEnergy: 0xa20b, Exif, RATIONAL, 1;
+    SpatialFrequencyResponse: 0xa20c, Exif, UNDEFINED, _;
+    FocalPlaneXResolution: 0xa20e, Exif, RATIONAL, 1;
+    FocalPlaneYResolution: 0xa20f, Exif, RATIONAL, 1;
+    FocalPlaneResolutionUnit: 0xa210, Exif, SHORT, 1;
+    SubjectLocation: 0xa214, Exif, SHORT, 2;
+    ExposureIndex: 0xa215, Exif, RATIONAL, 1;
+    SensingMethod: 0xa217, Exif, SHORT, 1;
+    FileSource: 0xa300, Exif, UNDEFINED, 1;
+    SceneType: 0xa301, Exif, UNDEFINED, 1;
+    CFAPattern: 0xa302, Exif, UNDEFINED, _;
+    CustomRendered: 0xa401, Exif, SHORT, 1;
+    ExposureMode: 0xa402, Exif, SHORT, 1;
+    WhiteBalanceMode: 0xa403, Exif, SHORT, 1;
+    DigitalZoomRatio: 0xa404, Exif, RATIONAL, 1;
+    FocalLengthIn35mmFilm: 0xa405, Exif, SHORT, 1;
+    SceneCaptureType: 0xa406, Exif, SHORT, 1;
+    GainControl: 0xa407, Exif, SHORT, 1;
+    Contrast: 0xa408, Exif, SHORT, 1;
+    Saturation: 0xa409, Exif, SHORT, 1;
+    Sharpness: 0xa40a, Exif, SHORT, 1;
+    DeviceSettingDescription: 0xa40b, Exif, UNDEFINED, _;
+    SubjectDistanceRange: 0xa40c, Exif, SHORT, 1;
+    ImageUniqueID: 0xa420, Exif, ASCII, _;
+    CameraOwnerName: 0xa430, Exif, ASCII, _;
+    BodySerialNumber: 0xa431, Exif, ASCII, _;
+    LensSpecification: 0xa432, Exif, RATIONAL, 4;
+    LensMake: 0xa433, Exif, ASCII, _;
+    LensModel: 0xa434, Exif, ASCII, _;
+    LensSerialNumber: 0xa435, Exif, ASCII, _;
+    ImageTitle: 0xa436, Exif, ASCII, _;
+    Photographer: 0xa437, Exif, ASCII, _;
+    ImageEditor: 0xa438, Exif, ASCII, _;
+    CameraFirmware: 0xa439, Exif, ASCII, _;
+    RAWDevelopingSoftware: 0xa43a, Exif, ASCII, _;
+    ImageEditingSoftware: 0xa43b, Exif, ASCII, _;
+    MetadataEditingSoftware: 0xa43c, Exif, ASCII, _;
+    CompositeImage: 0xa460, Exif, SHORT, 1;
+    SourceImageNumberOfCompositeImage: 0xa461, Exif, SHORT, 2;
+    SourceExposureTimesOfCompositeImage: 0xa462, Exif, UNDEFINED, _;
+    Gamma: 0xa500, Exif, RATIONAL, 1;
+
+    // GPS
+    GPSVersionID: 0x0000, Gps, BYTE, 4;
+    GPSLatitudeRef: 0x0001, Gps, ASCII, 2;
+    GPSLatitude: 0x0002, Gps, RATIONAL, 3;
+    GPSLongitudeRef: 0x0003, Gps, ASCII, 2;
+    GPSLongitude: 0x0004, Gps, RATIONAL, 3;
+    GPSAltitudeRef: 0x0005, Gps, BYTE, 1;
+    GPSAltitude: 0x0006, Gps, RATIONAL, 1;
+    GPSTimeStamp: 0x0007, Gps, RATIONAL, 3;
+    GPSSatellites: 0x0008, Gps, ASCII, _;
+    GPSStatus: 0x0009, Gps, ASCII, 2;
+    GPSMeasureMode: 0x000a, Gps, ASCII, 2;
+    GPSDOP: 0x000b, Gps, RATIONAL, 1;
+    GPSSpeedRef: 0x000c, Gps, ASCII, 2;
+    GPSSpeed: 0x000d, Gps, RATIONAL, 1;
+    GPSTrackRef: 0x000e, Gps, ASCII, 2;
+    GPSTrack: 0x000f, Gps, RATIONAL, 1;
+    GPSImgDirectionRef: 0x0010, Gps, ASCII, 2;
+    GPSImgDirection: 0x0011, Gps, RATIONAL, 1;
+    GPSMapDatum: 0x0012, Gps, ASCII, _;
+    GPSDestLatitudeRef: 0x0013, Gps, ASCII, 2;
+    GPSDestLatitude: 0x0014, Gps, RATIONAL, 3;
+    GPSDestLongitudeRef: 0x0015, Gps, ASCII, 2;
+    GPSDestLongitude: 0x0016, Gps, RATIONAL, 3;
+    GPSDestBearingRef: 0x0017, Gps, ASCII, 2;
+    GPSDestBearing: 0x0018, Gps, RATIONAL, 1;
+    GPSDestDistanceRef: 0x0019, Gps, ASCII, 2;
+    GPSDestDistance: 0x001a, Gps, RATIONAL, 1;
+    GPSProcessingMethod: 0x001b, Gps, UNDEFINED, _;
+    GPSAreaInformation: 0x001c, Gps, UNDEFINED, _;
+    GPSDateStamp: 0x001d, Gps, ASCII, 11;
+    GPSDifferential: 0x001e, Gps, SHORT, 1;
+    GPSHPositioningError: 0x001f, Gps, RATIONAL, 1;
+
+    // Interoperability
+    InteropIndex: 0x0001, Interop, ASCII, _;
+    InteropVersion: 0x0002, Interop, UNDEFINED, 4;
+    RelatedImageFileFormat: 0x1000, Interop, ASCII, _;
+    RelatedImageWidth: 0x1001, Interop, SHORT_LONG, 1;
+    RelatedImageHeight: 0x1002, Interop, SHORT_LONG, 1;
+
+    // DNG
+    DNGVersion: 0xc612, Ifd0, BYTE, 4;
+    DNGBackwardVersion: 0xc613, Ifd0, BYTE, 4;
+    UniqueCameraModel: 0xc614, Ifd0, ASCII, _;
+    LocalizedCameraModel: 0xc615, Ifd0, TEXT_BYTES, _;
+    CFAPlaneColor: 0xc616, Ifd0, BYTE, _;
+    CFALayout: 0xc617, Ifd0, SHORT, 1;
+    LinearizationTable: 0xc618, Ifd0, SHORT, _;
+    BlackLevelRepeatDim: 0xc619, Ifd0, SHORT, 2;
+    BlackLevel: 0xc61a, Ifd0, SIZE, _;
+    BlackLevelDeltaH: 0xc61b, Ifd0, SRATIONAL, _;
+    BlackLevelDeltaV: 0xc61c, Ifd0, SRATIONAL, _;
+    WhiteLevel: 0xc61d, Ifd0, SHORT_LONG, _;
+    DefaultScale: 0xc61e, Ifd0, RATIONAL, 2;
+    DefaultCropOrigin: 0xc61f, Ifd0, SIZE, 2;
+    DefaultCropSize: 0xc620, Ifd0, SIZE, 2;
+    ColorMatrix1: 0xc621, Ifd0, SRATIONAL, _;
+    ColorMatrix2: 0xc622, Ifd0, SRATIONAL, _;
+    CameraCalibration1: 0xc623, Ifd0, SRATIONAL, _;
+    CameraCalibration2: 0xc624, Ifd0, SRATIONAL, _;
+    ReductionMatrix1: 0xc625, Ifd0, SRATIONAL, _;
+    ReductionMatrix2: 0xc626, Ifd0, SRATIONAL, _;
+    AnalogBalance: 0xc627, Ifd0, RATIONAL, _;
+    AsShotNeutral: 0xc628, Ifd0, NEUTRAL, _;
+    AsShotWhiteXY: 0xc629, Ifd0, RATIONAL, 2;
+    BaselineExposure: 0xc62a, Ifd0, SRATIONAL, 1;
+    BaselineNoise: 0xc62b, Ifd0, RATIONAL, 1;
+    BaselineSharpness: 0xc62c, Ifd0, RATIONAL, 1;
+    BayerGreenSplit: 0xc62d, Ifd0, LONG, 1;
+    LinearResponseLimit: 0xc62e, Ifd0, RATIONAL, 1;
+    CameraSerialNumber: 0xc62f, Ifd0, ASCII, _;
+    LensInfo: 0xc630, Ifd0, RATIONAL, 4;
+    ChromaBlurRadius: 0xc631, Ifd0, RATIONAL, 1;
+    AntiAliasStrength: 0xc632, Ifd0, RATIONAL, 1;
+    ShadowScale: 0xc633, Ifd0, RATIONAL, 1;
+    DNGPrivateData: 0xc634, Ifd0, BYTE, _;
+    MakerNoteSafety: 0xc635, Ifd0, SHORT, 1;
+    CalibrationIlluminant1: 0xc65a, Ifd0, SHORT, 1;
+    CalibrationIlluminant2: 0xc65b, Ifd0, SHORT, 1;
+    BestQualityScale: 0xc65c, Ifd0, RATIONAL, 1;
+    RawDataUniqueID: 0xc65d, Ifd0, BYTE, 16;
+    OriginalRawFileName: 0xc68b, Ifd0, TEXT_BYTES, _;
+    OriginalRawFileData: 0xc68c, Ifd0, UNDEFINED, _;
+    ActiveArea: 0xc68d, Ifd0, SHORT_LONG, 4;
+    MaskedAreas: 0xc68e, Ifd0, SHORT_LONG, _;
+    AsShotICCProfile: 0xc68f, Ifd0, UNDEFINED, _;
+    AsShotPreProfileMatrix: 0xc690, Ifd0, SRATIONAL, _;
+    CurrentICCProfile: 0xc691, Ifd0, UNDEFINED, _;
+    CurrentPreProfileMatrix: 0xc692, Ifd0, SRATIONAL, _;
+    ColorimetricReference: 0xc6bf, Ifd0, SHORT, 1;
+    CameraCalibrationSignature: 0xc6f3, Ifd0, TEXT_BYTES, _;
+    ProfileCalibrationSignature: 0xc6f4, Ifd0, TEXT_BYTES, _;
+    ExtraCameraProfiles: 0xc6f5, Ifd0, IFD, _;
+    AsShotProfileName: 0xc6f6, Ifd0, TEXT_BYTES, _;
+    NoiseReductionApplied: 0xc6f7, Ifd0, RATIONAL, 1;
+    ProfileName: 0xc6f8, Ifd0, TEXT_BYTES, _;
+    ProfileHueSatMapDims: 0xc6f9, Ifd0, LONG, 3;
+    ProfileHueSatMapData1: 0xc6fa, Ifd0, FLOAT, _;
+    ProfileHueSatMapData2: 0xc6fb, Ifd0, FLOAT, _;
+    ProfileToneCurve: 0xc6fc, Ifd0, FLOAT, _;
+    ProfileEmbedPolicy: 0xc6fd, Ifd0, LONG, 1;
+    ProfileCopyright: 0xc6fe, Ifd0, TEXT_BYTES, _;
+    ForwardMatrix1: 0xc714, Ifd0, SRATIONAL, _;
+    ForwardMatrix2: 0xc715, Ifd0, SRATIONAL, _;
+    PreviewApplicationName: 0xc716, Ifd0, TEXT_BYTES, _;
+    PreviewApplicationVersion: 0xc717, Ifd0, TEXT_BYTES, _;
+    PreviewSettingsName: 0xc718, Ifd0, TEXT_BYTES, _;
+    PreviewSettingsDigest: 0xc719, Ifd0, BYTE, 16;
+    PreviewColorSpace: 0xc71a, Ifd0, LONG, 1;
+    PreviewDateTime: 0xc71b, Ifd0, ASCII, _;
+    RawImageDigest: 0xc71c, Ifd0, BYTE, 16;
+    OriginalRawFileDigest: 0xc71d, Ifd0, BYTE, 16;
+    SubTileBlockSize: 0xc71e, Ifd0, SHORT_LONG, 2;
+    RowInterleaveFactor: 0xc71f, Ifd0, SHORT_LONG, 1;
+    ProfileLookTableDims: 0xc725, Ifd0, LONG, 3;
+    ProfileLookTableData: 0xc726, Ifd0, FLOAT, _;
+    OpcodeList1: 0xc740, Ifd0, UNDEFINED, _;
+    OpcodeList2: 0xc741, Ifd0, UNDEFINED, _;
+    OpcodeList3: 0xc74e, Ifd0, UNDEFINED, _;
+    NoiseProfile: 0xc761, Ifd0, DOUBLE, _;
+    OriginalDefaultFinalSize: 0xc791, Ifd0, SHORT_LONG, 2;
+    OriginalBestQualityFinalSize: 0xc792, Ifd0, SHORT_LONG, 2;
+    OriginalDefaultCropSize: 0xc793, Ifd0, SIZE, 2;
+    ProfileHueSatMapEncoding: 0xc7a3, Ifd0, LONG, 1;
+    ProfileLookTableEncoding: 0xc7a4, Ifd0, LONG, 1;
+    BaselineExposureOffset: 0xc7a5, Ifd0, SRATIONAL, 1;
+    DefaultBlackRender: 0xc7a6, Ifd0, LONG, 1;
+    NewRawImageDigest: 0xc7a7, Ifd0, BYTE, 16;
+    RawToPreviewGain: 0xc7a8, Ifd0, DOUBLE, 1;
+    DefaultUserCrop: 0xc7b5, Ifd0, RATIONAL, 4;
+    DepthFormat: 0xc7e9, Ifd0, SHORT, 1;
+    DepthNear: 0xc7ea, Ifd0, RATIONAL, 1;
+    DepthFar: 0xc7eb, Ifd0, RATIONAL, 1;
+    DepthUnits: 0xc7ec, Ifd0, SHORT, 1;
+    DepthMeasureType: 0xc7ed, Ifd0, SHORT, 1;
+    EnhanceParams: 0xc7ee, Ifd0, ASCII, _;
+    ProfileGainTableMap: 0xcd2d, Ifd0, UNDEFINED, _;
+    SemanticName: 0xcd2e, Ifd0, ASCII, _;
+    SemanticInstanceID: 0xcd30, Ifd0, ASCII, _;
+    CalibrationIlluminant3: 0xcd31, Ifd0, SHORT, 1;
+    CameraCalibration3: 0xcd32, Ifd0, SRATIONAL, _;
+    ColorMatrix3: 0xcd33, Ifd0, SRATIONAL, _;
+    ForwardMatrix3: 0xcd34, Ifd0, SRATIONAL, _;
+    IlluminantData1: 0xcd35, Ifd0, UNDEFINED, _;
+    IlluminantData2: 0xcd36, Ifd0, UNDEFINED, _;
+    IlluminantData3: 0xcd37, Ifd0, UNDEFINED, _;
+    MaskSubArea: 0xcd38, Ifd0, LONG, 4;
+    ProfileHueSatMapData3: 0xcd39, Ifd0, FLOAT, _;
+    ReductionMatrix3: 0xcd3a, Ifd0, SRATIONAL, _;
+    RGBTables: 0xcd3f, Ifd0, UNDEFINED, _;
 }
 
 impl ExifTag {
-    pub const fn code(self) -> u16 {
-        self as u16
+    /// The IFD group the tag is defined in, e.g. [`IfdGroup::Gps`] for
+    /// `GPSLatitude`.
+    pub fn group(self) -> IfdGroup {
+        self.spec().group
+    }
+
+    /// Get the tag of `code` in an IFD of `group`.
+    ///
+    /// Unlike `TryFrom<u16>`, the tag codes are recognized in the namespace
+    /// of the group, e.g. 0x0001 is `InteropIndex` in [`IfdGroup::Interop`],
+    /// but `GPSLatitudeRef` in [`IfdGroup::Gps`], and is unrecognized in
+    /// [`IfdGroup::Ifd0`].
+    pub fn from_code_in(group: IfdGroup, code: u16) -> Option<ExifTag> {
+        let namespace = group.namespace();
+        let in_namespace = |tag: &ExifTag| tag.group().namespace() == namespace;
+        match ExifTag::try_from(code) {
+            Ok(tag) if in_namespace(&tag) => Some(tag),
+            // Maybe shadowed by a colliding tag of another namespace
+            Ok(_) => ExifTag::ALL
+                .iter()
+                .find(|x| x.code() == code && in_namespace(x))
+                .copied(),
+            Err(_) => None,
+        }
     }
 }
 
@@ -225,281 +569,66 @@ impl Display for ExifTag {
     }
 }
 
-impl From<ExifTag> for &str {
-    fn from(value: ExifTag) -> Self {
-        match value {
-            ExifTag::Make => "Make",
-            ExifTag::Model => "Model",
-            ExifTag::Orientation => "Orientation",
-            ExifTag::ImageWidth => "ImageWidth",
-            ExifTag::ImageHeight => "ImageHeight",
-            ExifTag::ISOSpeedRatings => "ISOSpeedRatings",
-            ExifTag::ShutterSpeedValue => "ShutterSpeedValue",
-            ExifTag::ExposureTime => "ExposureTime",
-            ExifTag::FNumber => "FNumber",
-            ExifTag::ExifImageWidth => "ExifImageWidth",
-            ExifTag::ExifImageHeight => "ExifImageHeight",
-            ExifTag::DateTimeOriginal => "DateTimeOriginal",
-            ExifTag::CreateDate => "CreateDate",
-            ExifTag::ModifyDate => "ModifyDate",
-            ExifTag::OffsetTime => "OffsetTime",
-            ExifTag::OffsetTimeOriginal => "OffsetTimeOriginal",
-            ExifTag::OffsetTimeDigitized => "OffsetTimeDigitized",
-            ExifTag::GPSLatitudeRef => "GPSLatitudeRef",
-            ExifTag::GPSLatitude => "GPSLatitude",
-            ExifTag::GPSLongitudeRef => "GPSLongitudeRef",
-            ExifTag::GPSLongitude => "GPSLongitude",
-            ExifTag::GPSAltitudeRef => "GPSAltitudeRef",
-            ExifTag::GPSAltitude => "GPSAltitude",
-            ExifTag::GPSVersionID => "GPSVersionID",
-            ExifTag::ExifOffset => "ExifOffset",
-            ExifTag::GPSInfo => "GPSInfo",
-            ExifTag::ImageDescription => "ImageDescription",
-            ExifTag::XResolution => "XResolution",
-            ExifTag::YResolution => "YResolution",
-            ExifTag::ResolutionUnit => "ResolutionUnit",
-            ExifTag::Software => "Software",
-            ExifTag::HostComputer => "HostComputer",
-            ExifTag::WhitePoint => "WhitePoint",
-            ExifTag::PrimaryChromaticities => "PrimaryChromaticities",
-            ExifTag::YCbCrCoefficients => "YCbCrCoefficients",
-            ExifTag::ReferenceBlackWhite => "ReferenceBlackWhite",
-            ExifTag::Copyright => "Copyright",
-            ExifTag::ExposureProgram => "ExposureProgram",
-            ExifTag::SpectralSensitivity => "SpectralSensitivity",
-            ExifTag::OECF => "OECF",
-            ExifTag::SensitivityType => "SensitivityType",
-            ExifTag::ExifVersion => "ExifVersion",
-            ExifTag::ApertureValue => "ApertureValue",
-            ExifTag::BrightnessValue => "BrightnessValue",
-            ExifTag::ExposureBiasValue => "ExposureBiasValue",
-            ExifTag::MaxApertureValue => "MaxApertureValue",
-            ExifTag::SubjectDistance => "SubjectDistance",
-            ExifTag::MeteringMode => "MeteringMode",
-            ExifTag::LightSource => "LightSource",
-            ExifTag::Flash => "Flash",
-            ExifTag::FocalLength => "FocalLength",
-            ExifTag::SubjectArea => "SubjectArea",
-            ExifTag::MakerNote => "MakerNote",
-            ExifTag::UserComment => "UserComment",
-            ExifTag::FlashPixVersion => "FlashPixVersion",
-            ExifTag::ColorSpace => "ColorSpace",
-            ExifTag::RelatedSoundFile => "RelatedSoundFile",
-            ExifTag::FlashEnergy => "FlashEnergy",
-            ExifTag::FocalPlaneXResolution => "FocalPlaneXResolution",
-            ExifTag::FocalPlaneYResolution => "FocalPlaneYResolution",
-            ExifTag::FocalPlaneResolutionUnit => "FocalPlaneResolutionUnit",
-            ExifTag::SubjectLocation => "SubjectLocation",
-            ExifTag::ExposureIndex => "ExposureIndex",
-            ExifTag::SensingMethod => "SensingMethod",
-            ExifTag::FileSource => "FileSource",
-            ExifTag::SceneType => "SceneType",
-            ExifTag::CFAPattern => "CFAPattern",
-            ExifTag::CustomRendered => "CustomRendered",
-            ExifTag::ExposureMode => "ExposureMode",
-            ExifTag::WhiteBalanceMode => "WhiteBalanceMode",
-            ExifTag::DigitalZoomRatio => "DigitalZoomRatio",
-            ExifTag::FocalLengthIn35mmFilm => "FocalLengthIn35mmFilm",
-            ExifTag::SceneCaptureType => "SceneCaptureType",
-            ExifTag::GainControl => "GainControl",
-            ExifTag::Contrast => "Contrast",
-            ExifTag::Saturation => "Saturation",
-            ExifTag::Sharpness => "Sharpness",
-            ExifTag::DeviceSettingDescription => "DeviceSettingDescription",
-            ExifTag::SubjectDistanceRange => "SubjectDistanceRange",
-            ExifTag::ImageUniqueID => "ImageUniqueID",
-            ExifTag::LensSpecification => "LensSpecification",
-            ExifTag::LensMake => "LensMake",
-            ExifTag::LensModel => "LensModel",
-            ExifTag::Gamma => "Gamma",
-            ExifTag::GPSTimeStamp => "GPSTimeStamp",
-            ExifTag::GPSSatellites => "GPSSatellites",
-            ExifTag::GPSStatus => "GPSStatus",
-            ExifTag::GPSMeasureMode => "GPSMeasureMode",
-            ExifTag::GPSDOP => "GPSDOP",
-            ExifTag::GPSSpeedRef => "GPSSpeedRef",
-            ExifTag::GPSSpeed => "GPSSpeed",
-            ExifTag::GPSTrackRef => "GPSTrackRef",
-            ExifTag::GPSTrack => "GPSTrack",
-            ExifTag::GPSImgDirectionRef => "GPSImgDirectionRef",
-            ExifTag::GPSImgDirection => "GPSImgDirection",
-            ExifTag::GPSMapDatum => "GPSMapDatum",
-            ExifTag::GPSDestLatitudeRef => "GPSDestLatitudeRef",
-            ExifTag::GPSDestLatitude => "GPSDestLatitude",
-            ExifTag::GPSDestLongitudeRef => "GPSDestLongitudeRef",
-            ExifTag::GPSDestLongitude => "GPSDestLongitude",
-            ExifTag::GPSDestBearingRef => "GPSDestBearingRef",
-            ExifTag::GPSDestBearing => "GPSDestBearing",
-            ExifTag::GPSDestDistanceRef => "GPSDestDistanceRef",
-            ExifTag::GPSDestDistance => "GPSDestDistance",
-            ExifTag::GPSProcessingMethod => "GPSProcessingMethod",
-            ExifTag::GPSAreaInformation => "GPSAreaInformation",
-            ExifTag::GPSDateStamp => "GPSDateStamp",
-            ExifTag::GPSDifferential => "GPSDifferential",
-            ExifTag::YCbCrPositioning => "YCbCrPositioning",
-            ExifTag::RecommendedExposureIndex => "RecommendedExposureIndex",
-            ExifTag::SubSecTimeDigitized => "SubSecTimeDigitized",
-            ExifTag::SubSecTimeOriginal => "SubSecTimeOriginal",
-            ExifTag::SubSecTime => "SubSecTime",
-            ExifTag::InteropOffset => "InteropOffset",
-            ExifTag::ComponentsConfiguration => "ComponentsConfiguration",
-            ExifTag::ThumbnailOffset => "ThumbnailOffset",
-            ExifTag::ThumbnailLength => "ThumbnailLength",
-            ExifTag::Compression => "Compression",
-            ExifTag::BitsPerSample => "BitsPerSample",
-            ExifTag::PhotometricInterpretation => "PhotometricInterpretation",
-            ExifTag::SamplesPerPixel => "SamplesPerPixel",
-            ExifTag::RowsPerStrip => "RowsPerStrip",
-            ExifTag::PlanarConfiguration => "PlanarConfiguration",
-        }
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0x013b, ExifTag::Artist, IfdGroup::Ifd0)]
+    #[test_case(0x010d, ExifTag::DocumentName, IfdGroup::Ifd0)]
+    #[test_case(0xa431, ExifTag::BodySerialNumber, IfdGroup::Exif)]
+    #[test_case(0xa460, ExifTag::CompositeImage, IfdGroup::Exif)]
+    #[test_case(0xc621, ExifTag::ColorMatrix1, IfdGroup::Ifd0)]
+    #[test_case(0x9c9b, ExifTag::XPTitle, IfdGroup::Ifd0)]
+    #[test_case(0x001f, ExifTag::GPSHPositioningError, IfdGroup::Gps)]
+    #[test_case(0x1001, ExifTag::RelatedImageWidth, IfdGroup::Interop)]
+    // Collides with InteropIndex
+    #[test_case(0x0001, ExifTag::GPSLatitudeRef, IfdGroup::Gps)]
+    fn tag_catalogue(code: u16, tag: ExifTag, group: IfdGroup) {
+        assert_eq!(ExifTag::try_from(code).unwrap(), tag);
+        assert_eq!(tag.code(), code);
+        assert_eq!(tag.group(), group);
     }
-}
 
-impl TryFrom<u16> for ExifTag {
-    type Error = crate::Error;
-    fn try_from(v: u16) -> Result<Self, Self::Error> {
-        use ExifTag::*;
-
-        let tag = match v {
-            x if x == Make.code() => Self::Make,
-            x if x == Model.code() => Self::Model,
-            x if x == Orientation.code() => Self::Orientation,
-            x if x == ImageWidth.code() => Self::ImageWidth,
-            x if x == ImageHeight.code() => Self::ImageHeight,
-            x if x == ISOSpeedRatings.code() => Self::ISOSpeedRatings,
-            x if x == ShutterSpeedValue.code() => Self::ShutterSpeedValue,
-            x if x == ExposureTime.code() => Self::ExposureTime,
-            x if x == FNumber.code() => Self::FNumber,
-            x if x == ExifImageWidth.code() => Self::ExifImageWidth,
-            x if x == ExifImageHeight.code() => Self::ExifImageHeight,
-            x if x == DateTimeOriginal.code() => Self::DateTimeOriginal,
-            x if x == CreateDate.code() => Self::CreateDate,
-            x if x == ModifyDate.code() => Self::ModifyDate,
-            x if x == OffsetTime.code() => Self::OffsetTime,
-            x if x == OffsetTimeOriginal.code() => Self::OffsetTimeOriginal,
-            x if x == OffsetTimeDigitized.code() => Self::OffsetTimeDigitized,
-            x if x == GPSLatitudeRef.code() => Self::GPSLatitudeRef,
-            x if x == GPSLatitude.code() => Self::GPSLatitude,
-            x if x == GPSLongitudeRef.code() => Self::GPSLongitudeRef,
-            x if x == GPSLongitude.code() => Self::GPSLongitude,
-            x if x == GPSAltitudeRef.code() => Self::GPSAltitudeRef,
-            x if x == GPSAltitude.code() => Self::GPSAltitude,
-            x if x == GPSVersionID.code() => Self::GPSVersionID,
-            x if x == ExifOffset.code() => Self::ExifOffset,
-            x if x == GPSInfo.code() => Self::GPSInfo,
-            x if x == ImageDescription.code() => Self::ImageDescription,
-            x if x == XResolution.code() => Self::XResolution,
-            x if x == YResolution.code() => Self::YResolution,
-            x if x == ResolutionUnit.code() => Self::ResolutionUnit,
-            x if x == Software.code() => Self::Software,
-            x if x == HostComputer.code() => Self::HostComputer,
-            x if x == WhitePoint.code() => Self::WhitePoint,
-            x if x == PrimaryChromaticities.code() => Self::PrimaryChromaticities,
-            x if x == YCbCrCoefficients.code() => Self::YCbCrCoefficients,
-            x if x == ReferenceBlackWhite.code() => Self::ReferenceBlackWhite,
-            x if x == Copyright.code() => Self::Copyright,
-            x if x == ExposureProgram.code() => Self::ExposureProgram,
-            x if x == SpectralSensitivity.code() => Self::SpectralSensitivity,
-            x if x == OECF.code() => Self::OECF,
-            x if x == SensitivityType.code() => Self::SensitivityType,
-            x if x == ExifVersion.code() => Self::ExifVersion,
-            x if x == ApertureValue.code() => Self::ApertureValue,
-            x if x == BrightnessValue.code() => Self::BrightnessValue,
-            x if x == ExposureBiasValue.code() => Self::ExposureBiasValue,
-            x if x == MaxApertureValue.code() => Self::MaxApertureValue,
-            x if x == SubjectDistance.code() => Self::SubjectDistance,
-            x if x == MeteringMode.code() => Self::MeteringMode,
-            x if x == LightSource.code() => Self::LightSource,
-            x if x == Flash.code() => Self::Flash,
-            x if x == FocalLength.code() => Self::FocalLength,
-            x if x == SubjectArea.code() => Self::SubjectArea,
-            x if x == MakerNote.code() => Self::MakerNote,
-            x if x == UserComment.code() => Self::UserComment,
-            x if x == FlashPixVersion.code() => Self::FlashPixVersion,
-            x if x == ColorSpace.code() => Self::ColorSpace,
-            x if x == RelatedSoundFile.code() => Self::RelatedSoundFile,
-            x if x == FlashEnergy.code() => Self::FlashEnergy,
-            x if x == FocalPlaneXResolution.code() => Self::FocalPlaneXResolution,
-            x if x == FocalPlaneYResolution.code() => Self::FocalPlaneYResolution,
-            x if x == FocalPlaneResolutionUnit.code() => Self::FocalPlaneResolutionUnit,
-            x if x == SubjectLocation.code() => Self::SubjectLocation,
-            x if x == ExposureIndex.code() => Self::ExposureIndex,
-            x if x == SensingMethod.code() => Self::SensingMethod,
-            x if x == FileSource.code() => Self::FileSource,
-            x if x == SceneType.code() => Self::SceneType,
-            x if x == CFAPattern.code() => Self::CFAPattern,
-            x if x == CustomRendered.code() => Self::CustomRendered,
-            x if x == ExposureMode.code() => Self::ExposureMode,
-            x if x == WhiteBalanceMode.code() => Self::WhiteBalanceMode,
-            x if x == DigitalZoomRatio.code() => Self::DigitalZoomRatio,
-            x if x == FocalLengthIn35mmFilm.code() => Self::FocalLengthIn35mmFilm,
-            x if x == SceneCaptureType.code() => Self::SceneCaptureType,
-            x if x == GainControl.code() => Self::GainControl,
-            x if x == Contrast.code() => Self::Contrast,
-            x if x == Saturation.code() => Self::Saturation,
-            x if x == Sharpness.code() => Self::Sharpness,
-            x if x == DeviceSettingDescription.code() => Self::DeviceSettingDescription,
-            x if x == SubjectDistanceRange.code() => Self::SubjectDistanceRange,
-            x if x == ImageUniqueID.code() => Self::ImageUniqueID,
-            x if x == LensSpecification.code() => Self::LensSpecification,
-            x if x == LensMake.code() => Self::LensMake,
-            x if x == LensModel.code() => Self::LensModel,
-            x if x == Gamma.code() => Self::Gamma,
-            x if x == GPSTimeStamp.code() => Self::GPSTimeStamp,
-            x if x == GPSSatellites.code() => Self::GPSSatellites,
-            x if x == GPSStatus.code() => Self::GPSStatus,
-            x if x == GPSMeasureMode.code() => Self::GPSMeasureMode,
-            x if x == GPSDOP.code() => Self::GPSDOP,
-            x if x == GPSSpeedRef.code() => Self::GPSSpeedRef,
-            x if x == GPSSpeed.code() => Self::GPSSpeed,
-            x if x == GPSTrackRef.code() => Self::GPSTrackRef,
-            x if x == GPSTrack.code() => Self::GPSTrack,
-            x if x == GPSImgDirectionRef.code() => Self::GPSImgDirectionRef,
-            x if x == GPSImgDirection.code() => Self::GPSImgDirection,
-            x if x == GPSMapDatum.code() => Self::GPSMapDatum,
-            x if x == GPSDestLatitudeRef.code() => Self::GPSDestLatitudeRef,
-            x if x == GPSDestLatitude.code() => Self::GPSDestLatitude,
-            x if x == GPSDestLongitudeRef.code() => Self::GPSDestLongitudeRef,
-            x if x == GPSDestLongitude.code() => Self::GPSDestLongitude,
-            x if x == GPSDestBearingRef.code() => Self::GPSDestBearingRef,
-            x if x == GPSDestBearing.code() => Self::GPSDestBearing,
-            x if x == GPSDestDistanceRef.code() => Self::GPSDestDistanceRef,
-            x if x == GPSDestDistance.code() => Self::GPSDestDistance,
-            x if x == GPSProcessingMethod.code() => Self::GPSProcessingMethod,
-            x if x == GPSAreaInformation.code() => Self::GPSAreaInformation,
-            x if x == GPSDateStamp.code() => Self::GPSDateStamp,
-            x if x == GPSDifferential.code() => Self::GPSDifferential,
-            x if x == YCbCrPositioning.code() => Self::YCbCrPositioning,
-            x if x == RecommendedExposureIndex.code() => Self::RecommendedExposureIndex,
-            x if x == SubSecTimeDigitized.code() => Self::SubSecTimeDigitized,
-            x if x == SubSecTimeOriginal.code() => Self::SubSecTimeOriginal,
-            x if x == SubSecTime.code() => Self::SubSecTime,
-            x if x == InteropOffset.code() => Self::InteropOffset,
-            x if x == ComponentsConfiguration.code() => Self::ComponentsConfiguration,
-            x if x == ThumbnailOffset.code() => Self::ThumbnailOffset,
-            x if x == ThumbnailLength.code() => Self::ThumbnailLength,
-            x if x == Compression.code() => Self::Compression,
-            x if x == BitsPerSample.code() => Self::BitsPerSample,
-            x if x == PhotometricInterpretation.code() => Self::PhotometricInterpretation,
-            x if x == SamplesPerPixel.code() => Self::SamplesPerPixel,
-            x if x == RowsPerStrip.code() => Self::RowsPerStrip,
-            x if x == PlanarConfiguration.code() => Self::PlanarConfiguration,
-
-            o => return Err(format!("Unrecognized ExifTag 0x{o:04x}").into()),
-        };
-
-        Ok(tag)
+    #[test_case(IfdGroup::Gps, 0x0001 => Some(ExifTag::GPSLatitudeRef))]
+    #[test_case(IfdGroup::Interop, 0x0001 => Some(ExifTag::InteropIndex))]
+    #[test_case(IfdGroup::Interop, 0x0002 => Some(ExifTag::InteropVersion))]
+    #[test_case(IfdGroup::Exif, 0x829a => Some(ExifTag::ExposureTime))]
+    #[test_case(IfdGroup::Ifd1, 0x0201 => Some(ExifTag::ThumbnailOffset))]
+    #[test_case(IfdGroup::Ifd0, 0x0001 => None)]
+    #[test_case(IfdGroup::Gps, 0x829a => None)]
+    fn tag_from_code_in(group: IfdGroup, code: u16) -> Option<ExifTag> {
+        ExifTag::from_code_in(group, code)
     }
-}
 
-#[allow(unused)]
-pub enum Orientation {
-    Horizontal,
-    MirrorHorizontal,
-    Rotate,
-    MirrorVertical,
-    MirrorHorizontalRotate270,
-    Rotate90,
-    MirrorHorizontalRotate90,
-    Rotate270,
+    #[test]
+    fn tag_names() {
+        let names: HashSet<&str> = ExifTag::ALL.iter().map(|x| (*x).into()).collect();
+        assert_eq!(names.len(), ExifTag::ALL.len());
+        assert_eq!(ExifTag::InteropIndex.to_string(), "InteropIndex");
+        assert_eq!(ExifTag::InteropIndex.code(), 0x0001);
+
+        for tag in ExifTag::ALL {
+            let parsed = ExifTag::try_from(tag.code()).unwrap();
+            // Only GPS tags are allowed to shadow tags of other groups
+            assert!(parsed == *tag || parsed.group() == IfdGroup::Gps, "{tag}");
+        }
+        ExifTag::try_from(0xfe00).unwrap_err();
+    }
+
+    #[test]
+    fn tag_spec() {
+        let spec = ExifTag::Orientation.spec();
+        assert!(spec.accepts(DataFormat::U16, 1));
+        assert!(!spec.accepts(DataFormat::U16, 2));
+        assert!(!spec.accepts(DataFormat::U32, 1));
+        assert!(ExifTag::Make.spec().accepts(DataFormat::Utf8, 10));
+        assert!(ExifTag::DotRange.spec().accepts(DataFormat::U8, 2));
+        // BigTIFF pointers
+        assert!(ExifTag::ExifOffset.spec().accepts(DataFormat::Ifd64, 1));
+        assert!(ExifTag::GPSInfo.spec().accepts(DataFormat::U64, 1));
+        assert!(!ExifTag::GPSInfo.spec().accepts(DataFormat::U16, 1));
+    }
 }
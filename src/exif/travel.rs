@@ -1,24 +1,25 @@
-use nom::{
-    number::{streaming, Endianness},
-    sequence::tuple,
-    IResult, Needed,
-};
+use nom::{number::Endianness, IResult, Needed};
 
 use crate::{error::ParsingError, exif::TiffHeader, values::DataFormat, ExifTag};
 
-use super::{exif_exif::IFD_ENTRY_SIZE, exif_iter::SUBIFD_TAGS};
+use super::{
+    exif_exif::{ifd_entry_size, offset_size},
+    exif_iter::{MAX_SUB_IFDS, SUBIFD_TAGS},
+};
 
 /// Only iterates headers, don't parse entries.
 ///
-/// Currently only used to extract Exif data for *.tiff files
+/// Used to extract Exif data for *.tiff files, and to locate entry data when
+/// patching Exif data in place.
 pub(crate) struct IfdHeaderTravel<'a> {
-    // starts from "ifd/sub-ifd entries" (two bytes of ifd/sub-ifd entry num)
+    // starts from "ifd/sub-ifd entries" (ifd/sub-ifd entry num)
     ifd_data: &'a [u8],
 
     // IFD data offset relative to the TIFF header.
-    offset: u32,
+    offset: u64,
 
     endian: Endianness,
+    bigtiff: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -26,19 +27,21 @@ pub(crate) struct EntryInfo<'a> {
     pub tag: u16,
     #[allow(unused)]
     pub data: &'a [u8],
-    #[allow(unused)]
     pub data_format: DataFormat,
-    #[allow(unused)]
-    pub data_offset: Option<u32>,
-    pub sub_ifd_offset: Option<u32>,
+    pub components_num: u64,
+    // Entry data offset relative to the TIFF header, either points to the
+    // value field of the entry itself, or to the data area.
+    pub data_offset: u64,
+    pub sub_ifd_offset: Option<u64>,
 }
 
 impl<'a> IfdHeaderTravel<'a> {
-    pub fn new(input: &'a [u8], offset: u32, endian: Endianness) -> Self {
+    pub fn new(input: &'a [u8], offset: u64, endian: Endianness, bigtiff: bool) -> Self {
         Self {
             ifd_data: input,
             endian,
             offset,
+            bigtiff,
         }
     }
 
@@ -46,14 +49,10 @@ impl<'a> IfdHeaderTravel<'a> {
     fn parse_tag_entry_header(
         &'a self,
         entry_data: &'a [u8],
+        pos: u32,
     ) -> IResult<&'a [u8], Option<EntryInfo<'a>>> {
-        let endian = self.endian;
-        let (remain, (tag, data_format, components_num, value_or_offset)) = tuple((
-            streaming::u16::<_, nom::error::Error<_>>(endian),
-            streaming::u16(endian),
-            streaming::u32(endian),
-            streaming::u32(endian),
-        ))(entry_data)?;
+        let (remain, (tag, data_format, components_num, value_or_offset)) =
+            TiffHeader::parse_ifd_entry(entry_data, self.endian, self.bigtiff)?;
 
         if tag == 0 {
             return Ok((remain, None));
@@ -72,10 +71,18 @@ impl<'a> IfdHeaderTravel<'a> {
         let component_size = data_format.component_size();
 
         // get entry data
-        let size = components_num as usize * component_size;
-        let (data, data_offset) = if size > 4 {
+        let size = usize::try_from(components_num)
+            .ok()
+            .and_then(|x| x.checked_mul(component_size))
+            .ok_or_else(|| {
+                nom::Err::Failure(nom::error::Error::new(
+                    entry_data,
+                    nom::error::ErrorKind::TooLarge,
+                ))
+            })?;
+        let (data, data_offset) = if size > offset_size(self.bigtiff) {
             let start = self.get_data_pos(value_or_offset) as usize;
-            let end = start + size;
+            let end = start.saturating_add(size);
             tracing::debug!(
                 components_num,
                 size,
@@ -90,9 +97,14 @@ impl<'a> IfdHeaderTravel<'a> {
             if end > self.ifd_data.len() {
                 return Err(nom::Err::Incomplete(Needed::new(end - self.ifd_data.len())));
             }
-            (&self.ifd_data[start..end], Some(start as u32))
+            (&self.ifd_data[start..end], value_or_offset)
         } else {
-            (entry_data, None)
+            let value_pos = ifd_entry_size(self.bigtiff) - offset_size(self.bigtiff);
+            (
+                &entry_data[value_pos..],
+                self.offset
+                    .saturating_add((pos as usize + value_pos) as u64),
+            )
         };
 
         let sub_ifd_offset = if SUBIFD_TAGS.contains(&tag) {
@@ -106,41 +118,96 @@ impl<'a> IfdHeaderTravel<'a> {
             tag,
             data,
             data_format,
+            components_num,
             data_offset,
             sub_ifd_offset,
         };
         Ok((&[][..], Some(entry)))
     }
 
-    fn get_data_pos(&'a self, value_or_offset: u32) -> u32 {
+    fn get_data_pos(&'a self, value_or_offset: u64) -> u64 {
         value_or_offset.saturating_sub(self.offset)
     }
 
-    fn parse_ifd_entry_header(&self, pos: u32) -> IResult<&[u8], Option<IfdHeaderTravel<'a>>> {
-        let (_, entry_data) =
-            nom::bytes::streaming::take(IFD_ENTRY_SIZE)(&self.ifd_data[pos as usize..])?;
+    fn parse_ifd_entry_header<F>(
+        &self,
+        pos: u32,
+        follow_sub_ifds: bool,
+        cb: &mut F,
+    ) -> IResult<&[u8], Vec<IfdHeaderTravel<'a>>>
+    where
+        F: FnMut(&EntryInfo),
+    {
+        let (_, entry_data) = nom::bytes::streaming::take(ifd_entry_size(self.bigtiff))(
+            &self.ifd_data[pos as usize..],
+        )?;
 
-        let (remain, entry) = self.parse_tag_entry_header(entry_data)?;
+        let (remain, entry) = self.parse_tag_entry_header(entry_data, pos)?;
 
-        if let Some(entry) = entry {
-            // if !cb(&entry) {
-            //     return Ok((&[][..], ()));
-            // }
+        let Some(entry) = entry else {
+            return Ok((remain, Vec::new()));
+        };
+        cb(&entry);
 
-            if let Some(offset) = entry.sub_ifd_offset {
-                let tag: ExifTag = entry.tag.try_into().unwrap();
-                println!("sub-ifd: {:?}", tag);
-                let sub_ifd =
-                    IfdHeaderTravel::new(&self.ifd_data[offset as usize..], offset, self.endian);
-                return Ok((remain, Some(sub_ifd)));
+        let offsets = match entry.sub_ifd_offset {
+            Some(offset) => vec![offset],
+            None if follow_sub_ifds && entry.tag == ExifTag::SubIFDs.code() => {
+                self.sub_ifds_offsets(&entry)
             }
+            None => Vec::new(),
+        };
+
+        let mut sub_ifds = Vec::with_capacity(offsets.len());
+        for offset in offsets {
+            tracing::debug!(tag = ?ExifTag::try_from(entry.tag), "sub-ifd");
+            if offset as usize > self.ifd_data.len() {
+                return Err(nom::Err::Incomplete(Needed::new(
+                    offset as usize - self.ifd_data.len(),
+                )));
+            }
+            // `offset` is relative to current IFD, convert it to be
+            // relative to the TIFF header.
+            sub_ifds.push(IfdHeaderTravel::new(
+                &self.ifd_data[offset as usize..],
+                self.offset.saturating_add(offset),
+                self.endian,
+                self.bigtiff,
+            ));
         }
 
-        Ok((remain, None))
+        Ok((remain, sub_ifds))
+    }
+
+    /// Returns the sub-IFD offsets (relative to current IFD) in a `SubIFDs`
+    /// entry.
+    fn sub_ifds_offsets(&self, entry: &EntryInfo) -> Vec<u64> {
+        let bigtiff = match entry.data_format {
+            DataFormat::U32 | DataFormat::Ifd => false,
+            DataFormat::U64 | DataFormat::Ifd64 => true,
+            _ => {
+                tracing::warn!(?entry.data_format, "Ignored: invalid SubIFDs data format");
+                return Vec::new();
+            }
+        };
+        entry
+            .data
+            .chunks_exact(offset_size(bigtiff))
+            .take((entry.components_num as usize).min(MAX_SUB_IFDS))
+            .filter_map(|x| TiffHeader::parse_offset(x, self.endian, bigtiff).ok())
+            .map(|(_, x)| self.get_data_pos(x))
+            .collect()
     }
 
-    #[tracing::instrument(skip(self))]
     pub fn travel_ifd(&mut self, depth: usize) -> Result<(), ParsingError> {
+        self.travel_ifd_with(depth, &mut |_| {})
+    }
+
+    /// Same as [`Self::travel_ifd`], but calls `cb` for every entry visited.
+    #[tracing::instrument(skip(self, cb))]
+    pub fn travel_ifd_with<F>(&mut self, depth: usize, cb: &mut F) -> Result<(), ParsingError>
+    where
+        F: FnMut(&EntryInfo),
+    {
         if depth >= 3 {
             let msg = "depth shouldn't be greater than 3";
             tracing::error!(msg);
@@ -148,17 +215,18 @@ impl<'a> IfdHeaderTravel<'a> {
         }
 
         tracing::debug!(ifd_data_len = self.ifd_data.len(), offset = self.offset);
-        let (remain, entry_num) = TiffHeader::parse_ifd_entry_num(self.ifd_data, self.endian)?;
+        let (remain, entry_num) =
+            TiffHeader::parse_ifd_entry_num(self.ifd_data, self.endian, self.bigtiff)?;
         let mut pos = self.ifd_data.len() - remain.len();
 
         let mut sub_ifds = Vec::new();
 
-        // parse entries
+        // parse entries, `SubIFDs` of sub-IFDs are not followed
         for _ in 0..entry_num {
-            let (_, sub_ifd) = self.parse_ifd_entry_header(pos as u32)?;
-            pos += IFD_ENTRY_SIZE;
+            let (_, sub_ifd) = self.parse_ifd_entry_header(pos as u32, depth == 0, cb)?;
+            pos += ifd_entry_size(self.bigtiff);
 
-            if let Some(ifd) = sub_ifd {
+            for ifd in sub_ifd {
                 if ifd.offset <= self.offset {
                     tracing::error!(
                         current_ifd_offset = self.offset,
@@ -172,12 +240,48 @@ impl<'a> IfdHeaderTravel<'a> {
         }
 
         for mut ifd in sub_ifds {
-            ifd.travel_ifd(depth + 1)?;
+            ifd.travel_ifd_with(depth + 1, cb)?;
         }
 
-        // Currently, we ignore ifd1 data in *.tif files
         Ok(())
     }
+
+    /// Same as [`Self::travel_ifd`], but also travels the following IFDs in
+    /// the IFD chain (IFD1, IFD2...), e.g. pages of a multi-page TIFF file.
+    pub fn travel_ifd_chain(&mut self) -> Result<(), ParsingError> {
+        self.travel_ifd(0)?;
+
+        let mut ifd_data = self.ifd_data;
+        let mut offset = self.offset;
+        loop {
+            let (remain, entry_num) =
+                TiffHeader::parse_ifd_entry_num(ifd_data, self.endian, self.bigtiff)?;
+            // `entry_num` has been checked by `parse_ifd_entry_num`
+            let pos =
+                ifd_data.len() - remain.len() + entry_num as usize * ifd_entry_size(self.bigtiff);
+            let (_, next) = TiffHeader::parse_offset(&ifd_data[pos..], self.endian, self.bigtiff)?;
+            if next == 0 {
+                return Ok(());
+            }
+            if next <= offset {
+                tracing::error!(
+                    current_ifd_offset = offset,
+                    next_ifd_offset = next,
+                    "bad next IFD in TIFF: offset is smaller than current IFD"
+                );
+                return Ok(());
+            }
+
+            // `ifd_data` of IFD0 contains the following IFDs
+            let start = (next - self.offset) as usize;
+            if start > self.ifd_data.len() {
+                return Err(ParsingError::Need(start - self.ifd_data.len()));
+            }
+            ifd_data = &self.ifd_data[start..];
+            offset = next;
+            IfdHeaderTravel::new(ifd_data, offset, self.endian, self.bigtiff).travel_ifd(0)?;
+        }
+    }
 }
 
 // fn keep_incomplete_err_only<T: Debug>(e: nom::Err<T>) -> nom::Err<String> {
@@ -0,0 +1,733 @@
+use std::collections::BTreeMap;
+
+use nom::number::Endianness;
+
+use crate::{values::DataFormat, EntryValue, Exif, ExifTag};
+
+use super::{exif_exif::IFD_ENTRY_SIZE, ifd::IfdGroup};
+
+/// Serializes Exif entries into a TIFF/Exif byte block, which can then be
+/// embedded into a file, e.g. as the payload of a JPEG APP1 segment.
+///
+/// Entries are organized by [`IfdGroup`]. The pointer entries (`ExifOffset`,
+/// `GPSInfo`, `InteropOffset`, `ThumbnailOffset` & `ThumbnailLength`) and the
+/// IFD chain are generated automatically, so you don't need to (and can't)
+/// set them yourself.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut writer = ExifWriter::new();
+///     writer
+///         .set(IfdGroup::Ifd0, ExifTag::Make, "nom-exif".into())
+///         .set(IfdGroup::Exif, ExifTag::ExposureTime, (1, 125).into())
+///         .set(IfdGroup::Gps, ExifTag::GPSLatitudeRef, "N".into());
+///
+///     let data = writer.write()?;
+///     assert_eq!(&data[..4], b"MM\0*");
+///     Ok(())
+/// }
+/// ```
+///
+/// `EntryValue::Text` is written as `ASCII`, or as the Exif 3.0 `UTF-8` type
+/// if it contains non-ASCII characters.
+///
+/// *Note*: The `MakerNote` entry is written as-is. If a MakerNote contains
+/// offsets relative to the TIFF header, they may become invalid after
+/// rewriting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExifWriter {
+    endian: Endianness,
+    groups: BTreeMap<IfdGroup, BTreeMap<u16, EntryValue>>,
+    thumbnail: Option<Vec<u8>>,
+}
+
+impl Default for ExifWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExifWriter {
+    /// Create an `ExifWriter` which outputs big-endian ("MM") data.
+    pub fn new() -> Self {
+        Self::with_endian(Endianness::Big)
+    }
+
+    /// Create an `ExifWriter` which outputs little-endian ("II") data.
+    pub fn little_endian() -> Self {
+        Self::with_endian(Endianness::Little)
+    }
+
+    fn with_endian(endian: Endianness) -> Self {
+        Self {
+            endian,
+            groups: BTreeMap::new(),
+            thumbnail: None,
+        }
+    }
+
+    /// Set the entry value for `tag` in the specified IFD `group`, an existing
+    /// value will be replaced.
+    pub fn set(&mut self, group: IfdGroup, tag: ExifTag, value: EntryValue) -> &mut Self {
+        self.set_by_tag_code(group, tag.code(), value)
+    }
+
+    /// Set the entry value by a raw tag code, see [`Self::set`].
+    ///
+    /// Pointer tags (e.g. `ExifOffset`) are ignored, since they are generated
+    /// automatically.
+    pub fn set_by_tag_code(&mut self, group: IfdGroup, tag: u16, value: EntryValue) -> &mut Self {
+        if is_pointer_tag(group, tag) {
+            tracing::warn!(
+                ?group,
+                tag,
+                "pointer tag is generated automatically, ignored"
+            );
+            return self;
+        }
+        self.groups.entry(group).or_default().insert(tag, value);
+        self
+    }
+
+    /// Remove the entry for `tag` in the specified IFD `group`, returns the
+    /// removed value.
+    pub fn remove(&mut self, group: IfdGroup, tag: ExifTag) -> Option<EntryValue> {
+        self.remove_by_tag_code(group, tag.code())
+    }
+
+    /// Remove the entry by a raw tag code, see [`Self::remove`].
+    pub fn remove_by_tag_code(&mut self, group: IfdGroup, tag: u16) -> Option<EntryValue> {
+        self.groups.get_mut(&group).and_then(|x| x.remove(&tag))
+    }
+
+    /// Remove all entries of the specified IFD `group`.
+    pub fn remove_group(&mut self, group: IfdGroup) {
+        self.groups.remove(&group);
+    }
+
+    /// Get the entry value for `tag` in the specified IFD `group`.
+    pub fn get(&self, group: IfdGroup, tag: ExifTag) -> Option<&EntryValue> {
+        self.get_by_tag_code(group, tag.code())
+    }
+
+    /// Get the entry value by a raw tag code, see [`Self::get`].
+    pub fn get_by_tag_code(&self, group: IfdGroup, tag: u16) -> Option<&EntryValue> {
+        self.groups.get(&group).and_then(|x| x.get(&tag))
+    }
+
+    /// Set the (JPEG) thumbnail image data, it will be referenced by
+    /// `ThumbnailOffset` & `ThumbnailLength` in IFD1.
+    pub fn set_thumbnail(&mut self, data: Vec<u8>) -> &mut Self {
+        self.thumbnail = Some(data);
+        self
+    }
+
+    /// Remove the thumbnail image data.
+    pub fn remove_thumbnail(&mut self) -> Option<Vec<u8>> {
+        self.thumbnail.take()
+    }
+
+    /// Serialize all entries into a TIFF/Exif byte block, which starts with
+    /// the TIFF header ("MM\0*" or "II*\0").
+    ///
+    /// The returned data doesn't include the "Exif\0\0" identifier.
+    pub fn write(&self) -> crate::Result<Vec<u8>> {
+        if self.has_group(IfdGroup::MakerNote) {
+            return Err(
+                "MakerNote IFD can't be written, please set the MakerNote entry instead".into(),
+            );
+        }
+
+        let mut ifds = Vec::new();
+        for group in [
+            IfdGroup::Ifd0,
+            IfdGroup::Exif,
+            IfdGroup::Interop,
+            IfdGroup::Gps,
+            IfdGroup::Ifd1,
+        ] {
+            if group == IfdGroup::Ifd0 || self.has_group(group) {
+                ifds.push(self.encode_ifd(group)?);
+            }
+        }
+
+        // Layout: header, then each IFD followed by its own data area, and
+        // finally the thumbnail. Sub-IFDs & their data are always placed
+        // after their parents.
+        let mut pos = TIFF_HEADER_SIZE;
+        let mut offsets = BTreeMap::new();
+        for ifd in ifds.iter() {
+            offsets.insert(ifd.group, pos);
+            pos += ifd.size();
+        }
+        let thumbnail_offset = pos;
+        if let Some(ref data) = self.thumbnail {
+            pos += data.len();
+        }
+        if pos > u32::MAX as usize {
+            return Err("Exif data is too big".into());
+        }
+
+        let mut out = Vec::with_capacity(pos);
+        match self.endian {
+            Endianness::Little => out.extend_from_slice(b"II"),
+            _ => out.extend_from_slice(b"MM"),
+        }
+        self.put_u16(&mut out, 0x2a);
+        self.put_u32(&mut out, TIFF_HEADER_SIZE as u32);
+
+        for ifd in ifds.iter() {
+            let ifd_offset = offsets[&ifd.group];
+            let mut data_offset = ifd_offset + ifd.table_size();
+            let mut data_area = Vec::new();
+
+            self.put_u16(&mut out, ifd.entries.len() as u16);
+            for entry in ifd.entries.iter() {
+                let (count, bytes) = match entry.value {
+                    EncodedValue::Data(ref bytes) => (entry.count, bytes.to_owned()),
+                    EncodedValue::Pointer(group) => (1, self.u32_bytes(offsets[&group] as u32)),
+                    EncodedValue::ThumbnailOffset => (1, self.u32_bytes(thumbnail_offset as u32)),
+                };
+
+                self.put_u16(&mut out, entry.tag);
+                self.put_u16(&mut out, entry.format as u16);
+                self.put_u32(&mut out, count);
+                if bytes.len() <= 4 {
+                    out.extend_from_slice(&bytes);
+                    out.resize(out.len() + 4 - bytes.len(), 0);
+                } else {
+                    self.put_u32(&mut out, data_offset as u32);
+                    data_offset += padded_len(bytes.len());
+                    data_area.extend_from_slice(&bytes);
+                    data_area.resize(padded_len(data_area.len()), 0);
+                }
+            }
+
+            let next = if ifd.group == IfdGroup::Ifd0 {
+                offsets.get(&IfdGroup::Ifd1).cloned().unwrap_or(0)
+            } else {
+                0
+            };
+            self.put_u32(&mut out, next as u32);
+            out.extend_from_slice(&data_area);
+        }
+
+        if let Some(ref data) = self.thumbnail {
+            out.extend_from_slice(data);
+        }
+
+        debug_assert_eq!(out.len(), pos);
+        Ok(out)
+    }
+
+    fn has_group(&self, group: IfdGroup) -> bool {
+        let non_empty = |g| self.groups.get(&g).map(|x| !x.is_empty()).unwrap_or(false);
+        match group {
+            IfdGroup::Exif => non_empty(IfdGroup::Exif) || non_empty(IfdGroup::Interop),
+            IfdGroup::Ifd1 => non_empty(IfdGroup::Ifd1) || self.thumbnail.is_some(),
+            g => non_empty(g),
+        }
+    }
+
+    fn encode_ifd(&self, group: IfdGroup) -> crate::Result<EncodedIfd> {
+        let mut entries = Vec::new();
+        if let Some(values) = self.groups.get(&group) {
+            for (tag, value) in values.iter() {
+                let (format, count, bytes) = encode_value(value, self.endian).map_err(|e| {
+                    crate::Error::ParseFailed(format!("encode tag 0x{tag:04x} failed: {e}").into())
+                })?;
+                entries.push(EncodedEntry {
+                    tag: *tag,
+                    format,
+                    count,
+                    value: EncodedValue::Data(bytes),
+                });
+            }
+        }
+
+        let pointer = |tag: ExifTag, value| EncodedEntry {
+            tag: tag.code(),
+            format: DataFormat::U32,
+            count: 1,
+            value,
+        };
+        match group {
+            IfdGroup::Ifd0 => {
+                if self.has_group(IfdGroup::Exif) {
+                    entries.push(pointer(
+                        ExifTag::ExifOffset,
+                        EncodedValue::Pointer(IfdGroup::Exif),
+                    ));
+                }
+                if self.has_group(IfdGroup::Gps) {
+                    entries.push(pointer(
+                        ExifTag::GPSInfo,
+                        EncodedValue::Pointer(IfdGroup::Gps),
+                    ));
+                }
+            }
+            IfdGroup::Exif if self.has_group(IfdGroup::Interop) => {
+                entries.push(pointer(
+                    ExifTag::InteropOffset,
+                    EncodedValue::Pointer(IfdGroup::Interop),
+                ));
+            }
+            IfdGroup::Ifd1 => {
+                if let Some(ref data) = self.thumbnail {
+                    entries.push(pointer(
+                        ExifTag::ThumbnailOffset,
+                        EncodedValue::ThumbnailOffset,
+                    ));
+                    entries.push(pointer(
+                        ExifTag::ThumbnailLength,
+                        EncodedValue::Data(self.u32_bytes(data.len() as u32)),
+                    ));
+                }
+            }
+            _ => (),
+        }
+
+        if entries.len() > u16::MAX as usize {
+            return Err(format!("too many entries in {group:?} IFD: {}", entries.len()).into());
+        }
+
+        // Entries in an IFD must be sorted in ascending order by tag code.
+        entries.sort_by_key(|x| x.tag);
+        Ok(EncodedIfd { group, entries })
+    }
+
+    fn u32_bytes(&self, v: u32) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4);
+        self.put_u32(&mut out, v);
+        out
+    }
+
+    fn put_u16(&self, out: &mut Vec<u8>, v: u16) {
+        match self.endian {
+            Endianness::Little => out.extend_from_slice(&v.to_le_bytes()),
+            _ => out.extend_from_slice(&v.to_be_bytes()),
+        }
+    }
+
+    fn put_u32(&self, out: &mut Vec<u8>, v: u32) {
+        match self.endian {
+            Endianness::Little => out.extend_from_slice(&v.to_le_bytes()),
+            _ => out.extend_from_slice(&v.to_be_bytes()),
+        }
+    }
+}
+
+impl From<&Exif> for ExifWriter {
+    /// Convert an [`Exif`] into an `ExifWriter`, so you can modify the
+    /// entries and then serialize them back.
+    ///
+    /// Entries of ifd0 are kept in the IFD groups where they were parsed
+    /// from. Entries of ifd1 are kept in [`IfdGroup::Ifd1`], along with the
+    /// thumbnail. The byte order of the source data is kept as well. Pointer
+    /// entries are dropped, since they are generated automatically.
+    ///
+    /// The other image directories (IFD2 and later, and the sub-IFDs pointed
+    /// by `SubIFDs`) are discarded, so are the entries pointing to data
+    /// outside of the Exif data (`SubIFDs`, `StripOffsets`, `TileOffsets`,
+    /// their byte counts, etc.), e.g. the image data of a TIFF or RAW file,
+    /// which would be invalid in the new Exif data.
+    fn from(exif: &Exif) -> Self {
+        let mut writer = ExifWriter::with_endian(exif.endian());
+        writer.thumbnail = exif.thumbnail().map(|x| x.to_vec());
+        for (idx, ifd) in exif.ifds().iter().enumerate() {
+            for ((group, code), entry) in ifd.entries.iter() {
+                let group = if idx == 0 && *group != IfdGroup::MakerNote {
+                    *group
+                } else if idx == 1 {
+                    IfdGroup::Ifd1
+                } else {
+                    continue;
+                };
+                if is_pointer_tag(IfdGroup::Ifd0, *code)
+                    || is_pointer_tag(group, *code)
+                    || is_data_offset_tag(group, *code)
+                {
+                    continue;
+                }
+                writer.set_by_tag_code(group, *code, entry.value.clone());
+            }
+        }
+        writer
+    }
+}
+
+const TIFF_HEADER_SIZE: usize = 8;
+
+fn is_pointer_tag(group: IfdGroup, tag: u16) -> bool {
+    match group {
+        IfdGroup::Ifd0 => tag == ExifTag::ExifOffset.code() || tag == ExifTag::GPSInfo.code(),
+        IfdGroup::Exif => tag == ExifTag::InteropOffset.code(),
+        IfdGroup::Ifd1 => {
+            tag == ExifTag::ThumbnailOffset.code() || tag == ExifTag::ThumbnailLength.code()
+        }
+        _ => false,
+    }
+}
+
+/// Tags of image directories, which point to data outside of the Exif data
+/// (or are the sizes of such data).
+const DATA_OFFSET_TAGS: [ExifTag; 8] = [
+    ExifTag::StripOffsets,
+    ExifTag::StripByteCounts,
+    ExifTag::FreeOffsets,
+    ExifTag::FreeByteCounts,
+    ExifTag::TileOffsets,
+    ExifTag::TileByteCounts,
+    ExifTag::SubIFDs,
+    ExifTag::ExtraCameraProfiles,
+];
+
+fn is_data_offset_tag(group: IfdGroup, tag: u16) -> bool {
+    matches!(group, IfdGroup::Ifd0 | IfdGroup::Ifd1)
+        && DATA_OFFSET_TAGS.iter().any(|x| x.code() == tag)
+}
+
+fn padded_len(len: usize) -> usize {
+    // Values are word aligned
+    len + len % 2
+}
+
+#[derive(Debug)]
+struct EncodedIfd {
+    group: IfdGroup,
+    entries: Vec<EncodedEntry>,
+}
+
+impl EncodedIfd {
+    /// Size of entry num + entries + next IFD offset.
+    fn table_size(&self) -> usize {
+        2 + self.entries.len() * IFD_ENTRY_SIZE + 4
+    }
+
+    fn size(&self) -> usize {
+        self.table_size()
+            + self
+                .entries
+                .iter()
+                .map(|x| match x.value {
+                    EncodedValue::Data(ref bytes) if bytes.len() > 4 => padded_len(bytes.len()),
+                    _ => 0,
+                })
+                .sum::<usize>()
+    }
+}
+
+#[derive(Debug)]
+struct EncodedEntry {
+    tag: u16,
+    format: DataFormat,
+    count: u32,
+    value: EncodedValue,
+}
+
+#[derive(Debug)]
+enum EncodedValue {
+    Data(Vec<u8>),
+    Pointer(IfdGroup),
+    ThumbnailOffset,
+}
+
+fn encode_value(
+    value: &EntryValue,
+    endian: Endianness,
+) -> Result<(DataFormat, u32, Vec<u8>), String> {
+    macro_rules! encode_nums {
+        ($values:expr) => {{
+            let mut out = Vec::new();
+            for v in $values {
+                match endian {
+                    Endianness::Little => out.extend_from_slice(&v.to_le_bytes()),
+                    _ => out.extend_from_slice(&v.to_be_bytes()),
+                }
+            }
+            out
+        }};
+    }
+
+    let text = |s: &str| {
+        let mut bytes = s.as_bytes().to_vec();
+        bytes.push(0);
+        let format = if s.is_ascii() {
+            DataFormat::Text
+        } else {
+            DataFormat::Utf8
+        };
+        (format, bytes.len() as u32, bytes)
+    };
+
+    let res = match value {
+        EntryValue::Text(s) => text(s),
+        EntryValue::Time(t) => text(&t.format("%Y:%m:%d %H:%M:%S").to_string()),
+        EntryValue::NaiveDateTime(t) => text(&t.format("%Y:%m:%d %H:%M:%S").to_string()),
+        EntryValue::URational(v) => (DataFormat::URational, 1, encode_nums!([v.0, v.1])),
+        EntryValue::IRational(v) => (DataFormat::IRational, 1, encode_nums!([v.0, v.1])),
+        EntryValue::U8(v) => (DataFormat::U8, 1, vec![*v]),
+        EntryValue::I8(v) => (DataFormat::I8, 1, vec![*v as u8]),
+        EntryValue::U16(v) => (DataFormat::U16, 1, encode_nums!([*v])),
+        EntryValue::I16(v) => (DataFormat::I16, 1, encode_nums!([*v])),
+        EntryValue::U32(v) => (DataFormat::U32, 1, encode_nums!([*v])),
+        EntryValue::I32(v) => (DataFormat::I32, 1, encode_nums!([*v])),
+        EntryValue::F32(v) => (DataFormat::F32, 1, encode_nums!([*v])),
+        EntryValue::F64(v) => (DataFormat::F64, 1, encode_nums!([*v])),
+        EntryValue::Undefined(v) => (DataFormat::Undefined, v.len() as u32, v.to_owned()),
+        EntryValue::URationalArray(v) => (
+            DataFormat::URational,
+            v.len() as u32,
+            encode_nums!(v.iter().flat_map(|x| [x.0, x.1])),
+        ),
+        EntryValue::IRationalArray(v) => (
+            DataFormat::IRational,
+            v.len() as u32,
+            encode_nums!(v.iter().flat_map(|x| [x.0, x.1])),
+        ),
+        EntryValue::U8Array(v) => (DataFormat::U8, v.len() as u32, v.to_owned()),
+        EntryValue::U16Array(v) => (DataFormat::U16, v.len() as u32, encode_nums!(v.iter())),
+        EntryValue::U32Array(v) => (DataFormat::U32, v.len() as u32, encode_nums!(v.iter())),
+        EntryValue::I8Array(v) => (DataFormat::I8, v.len() as u32, encode_nums!(v.iter())),
+        EntryValue::I16Array(v) => (DataFormat::I16, v.len() as u32, encode_nums!(v.iter())),
+        EntryValue::I32Array(v) => (DataFormat::I32, v.len() as u32, encode_nums!(v.iter())),
+        EntryValue::F32Array(v) => (DataFormat::F32, v.len() as u32, encode_nums!(v.iter())),
+        EntryValue::F64Array(v) => (DataFormat::F64, v.len() as u32, encode_nums!(v.iter())),
+        EntryValue::U64(_)
+        | EntryValue::I64(_)
+        | EntryValue::U64Array(_)
+        | EntryValue::I64Array(_) => {
+            return Err(format!("value type is unsupported: {value:?}"));
+        }
+    };
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use test_case::test_case;
+
+    use crate::exif::input_into_iter;
+    use crate::jpeg::extract_exif_data;
+    use crate::testkit::read_sample;
+
+    use super::*;
+
+    fn write_and_parse(writer: &ExifWriter) -> Exif {
+        let data = writer.write().unwrap();
+        input_into_iter(data, None).unwrap().into()
+    }
+
+    #[test_case(ExifWriter::new(), b"MM\0*")]
+    #[test_case(ExifWriter::little_endian(), b"II*\0")]
+    fn write_entries(mut writer: ExifWriter, header: &[u8]) {
+        let t =
+            DateTime::parse_from_str("2024:05:06 07:08:09 +08:00", "%Y:%m:%d %H:%M:%S %z").unwrap();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "nom-exif".into())
+            .set(IfdGroup::Ifd0, ExifTag::Orientation, 6_u16.into())
+            .set(IfdGroup::Ifd0, ExifTag::XResolution, (72, 1).into())
+            .set(IfdGroup::Exif, ExifTag::DateTimeOriginal, t.into())
+            .set(IfdGroup::Exif, ExifTag::OffsetTimeOriginal, "+08:00".into())
+            .set(IfdGroup::Exif, ExifTag::ExposureBiasValue, (-1, 3).into())
+            .set(
+                IfdGroup::Exif,
+                ExifTag::ExifVersion,
+                EntryValue::Undefined(b"0232".to_vec()),
+            )
+            .set(IfdGroup::Exif, ExifTag::ExifImageWidth, 4000_u32.into())
+            .set(IfdGroup::Gps, ExifTag::GPSLatitudeRef, "N".into())
+            .set(
+                IfdGroup::Gps,
+                ExifTag::GPSLatitude,
+                EntryValue::URationalArray(vec![
+                    (22, 1).into(),
+                    (31, 1).into(),
+                    (5208, 100).into(),
+                ]),
+            )
+            .set(IfdGroup::Ifd1, ExifTag::Compression, 6_u16.into());
+
+        let data = writer.write().unwrap();
+        assert_eq!(&data[..4], header);
+
+        let exif = write_and_parse(&writer);
+        assert_eq!(exif.get(ExifTag::Make).unwrap(), &"nom-exif".into());
+        assert_eq!(exif.get(ExifTag::Orientation).unwrap(), &6_u16.into());
+        assert_eq!(exif.get(ExifTag::XResolution).unwrap(), &(72, 1).into());
+        assert_eq!(exif.get(ExifTag::DateTimeOriginal).unwrap(), &t.into());
+        assert_eq!(
+            exif.get(ExifTag::ExposureBiasValue).unwrap(),
+            &(-1, 3).into()
+        );
+        assert_eq!(
+            exif.get(ExifTag::ExifVersion).unwrap(),
+            &EntryValue::Undefined(b"0232".to_vec())
+        );
+        assert_eq!(exif.get(ExifTag::ExifImageWidth).unwrap(), &4000_u32.into());
+        assert_eq!(exif.get(ExifTag::GPSLatitudeRef).unwrap(), &"N".into());
+        assert_eq!(
+            exif.get_gps_info().unwrap().unwrap().latitude.0,
+            (22, 1).into()
+        );
+        assert_eq!(exif.get_by_ifd_tag_code(1, 0x0103).unwrap(), &6_u16.into());
+    }
+
+    #[test]
+    fn write_thumbnail() {
+        let mut writer = ExifWriter::new();
+        writer.set_thumbnail(vec![0xff, 0xd8, 0xff, 0xd9]);
+
+        let data = writer.write().unwrap();
+        let exif: Exif = input_into_iter(data.clone(), None).unwrap().into();
+        let offset = exif
+            .get_by_ifd_tag_code(1, ExifTag::ThumbnailOffset.code())
+            .and_then(|x| x.as_u32())
+            .unwrap() as usize;
+        let len = exif
+            .get_by_ifd_tag_code(1, ExifTag::ThumbnailLength.code())
+            .and_then(|x| x.as_u32())
+            .unwrap() as usize;
+        assert_eq!(&data[offset..offset + len], &[0xff, 0xd8, 0xff, 0xd9]);
+    }
+
+    #[test_case(EntryValue::U8Array(vec![1, 2, 3]))]
+    #[test_case(EntryValue::I8Array(vec![-1, 2, 3]))]
+    #[test_case(EntryValue::I16Array(vec![-1, 2]))]
+    #[test_case(EntryValue::I32Array(vec![-1, 2]))]
+    #[test_case(EntryValue::F32Array(vec![1.5, -2.0]))]
+    #[test_case(EntryValue::F64Array(vec![1.5, -2.0]))]
+    fn write_arrays(value: EntryValue) {
+        for mut writer in [ExifWriter::new(), ExifWriter::little_endian()] {
+            writer.set_by_tag_code(IfdGroup::Ifd0, 0xfe00, value.clone());
+            let exif = write_and_parse(&writer);
+            assert_eq!(exif.get_by_ifd_tag_code(0, 0xfe00).unwrap(), &value);
+        }
+    }
+
+    #[test]
+    fn write_unsupported_value() {
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, ExifTag::Make, 1_u64.into());
+        writer.write().unwrap_err();
+    }
+
+    #[test]
+    fn write_too_many_entries() {
+        let mut writer = ExifWriter::new();
+        for tag in 0..=u16::MAX {
+            writer.set_by_tag_code(IfdGroup::Ifd0, tag, 1_u8.into());
+        }
+        writer.write().unwrap();
+
+        // The generated pointer entries make IFD0 overflow
+        writer
+            .set(IfdGroup::Exif, ExifTag::FNumber, (28, 10).into())
+            .set(IfdGroup::Gps, ExifTag::GPSLatitudeRef, "N".into());
+        writer.write().unwrap_err();
+    }
+
+    #[test_case(ExifWriter::new(), b"MM\0*")]
+    #[test_case(ExifWriter::little_endian(), b"II*\0")]
+    fn rewrite_endian_and_thumbnail(mut writer: ExifWriter, header: &[u8]) {
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "nom-exif".into())
+            .set(IfdGroup::Ifd1, ExifTag::Compression, 6_u16.into())
+            .set_thumbnail(vec![0xff, 0xd8, 0xff, 0xd9]);
+        let exif = write_and_parse(&writer);
+        assert_eq!(exif.thumbnail(), Some(&[0xff, 0xd8, 0xff, 0xd9][..]));
+
+        let writer = ExifWriter::from(&exif);
+        let data = writer.write().unwrap();
+        assert_eq!(&data[..4], header);
+        let exif2: Exif = input_into_iter(data, None).unwrap().into();
+        assert_eq!(exif2.get(ExifTag::Make), exif.get(ExifTag::Make));
+        assert_eq!(exif2.thumbnail(), exif.thumbnail());
+    }
+
+    #[test]
+    fn write_utf8_text() {
+        let (format, _, _) = encode_value(&"ascii".into(), Endianness::Big).unwrap();
+        assert_eq!(format, DataFormat::Text);
+        let (format, count, bytes) = encode_value(&"中文".into(), Endianness::Big).unwrap();
+        assert_eq!(format, DataFormat::Utf8);
+        assert_eq!(count, 7);
+        assert_eq!(bytes, "中文\0".as_bytes());
+
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, ExifTag::ImageDescription, "中文".into());
+        let exif = write_and_parse(&writer);
+        assert_eq!(
+            exif.get(ExifTag::ImageDescription).unwrap().as_str(),
+            Some("中文")
+        );
+    }
+
+    #[test]
+    fn rewrite_drop_data_offsets() {
+        let mut writer = ExifWriter::little_endian();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "nom-exif".into())
+            .set(IfdGroup::Ifd0, ExifTag::StripOffsets, 0x10000_u32.into())
+            .set(IfdGroup::Ifd0, ExifTag::StripByteCounts, 100_u32.into())
+            .set(IfdGroup::Ifd0, ExifTag::TileOffsets, 0x20000_u32.into())
+            .set(IfdGroup::Ifd0, ExifTag::SubIFDs, 0x30000_u32.into())
+            .set(IfdGroup::Ifd1, ExifTag::StripOffsets, 0x40000_u32.into())
+            .set(IfdGroup::Ifd1, ExifTag::Compression, 1_u16.into());
+        let exif = write_and_parse(&writer);
+        assert!(exif.get(ExifTag::StripOffsets).is_some());
+        assert!(exif.get(ExifTag::SubIFDs).is_some());
+
+        let writer = ExifWriter::from(&exif);
+        assert!(writer.get(IfdGroup::Ifd0, ExifTag::Make).is_some());
+        assert!(writer.get(IfdGroup::Ifd1, ExifTag::Compression).is_some());
+        for tag in [
+            ExifTag::StripOffsets,
+            ExifTag::StripByteCounts,
+            ExifTag::TileOffsets,
+            ExifTag::SubIFDs,
+        ] {
+            assert!(writer.get(IfdGroup::Ifd0, tag).is_none(), "{tag}");
+        }
+        assert!(writer.get(IfdGroup::Ifd1, ExifTag::StripOffsets).is_none());
+    }
+
+    #[test]
+    fn write_makernote_group() {
+        let mut writer = ExifWriter::new();
+        writer.set_by_tag_code(IfdGroup::MakerNote, 0x0001, 1_u16.into());
+        writer.write().unwrap_err();
+    }
+
+    #[test_case("exif.jpg")]
+    fn rewrite_exif(path: &str) {
+        let buf = read_sample(path).unwrap();
+        let (_, data) = extract_exif_data(&buf).unwrap();
+        let data = data.unwrap().to_vec();
+        let exif: Exif = input_into_iter(data, None).unwrap().into();
+
+        let writer = ExifWriter::from(&exif);
+        assert!(writer.get(IfdGroup::Exif, ExifTag::ExifOffset).is_none());
+        assert!(writer.get(IfdGroup::Exif, ExifTag::FNumber).is_some());
+        assert!(exif.thumbnail().is_some());
+
+        let exif2 = write_and_parse(&writer);
+        assert_eq!(exif2.endian(), exif.endian());
+        assert_eq!(exif2.thumbnail(), exif.thumbnail());
+        for tag in [
+            ExifTag::Make,
+            ExifTag::Model,
+            ExifTag::DateTimeOriginal,
+            ExifTag::FNumber,
+            ExifTag::GPSLatitude,
+        ] {
+            assert_eq!(exif2.get(tag), exif.get(tag), "{tag}");
+        }
+        assert_eq!(exif2.get_gps_info().unwrap(), exif.get_gps_info().unwrap());
+    }
+}
@@ -8,11 +8,15 @@ use crate::{
     bbox::{travel_header, BoxHolder},
     ebml::element::parse_ebml_doc_type,
     error::{ParsedError, ParsingError},
-    exif::TiffHeader,
+    exif::{ifd_entry_size, offset_size, TiffHeader, MAX_SUB_IFDS},
     jpeg::check_jpeg,
+    jxl::check_jxl,
     loader::Load,
+    png::check_png,
     raf::RafInfo,
     slice::SubsliceRange,
+    webp::check_webp,
+    ExifTag,
 };
 
 const HEIF_HEIC_BRAND_NAMES: &[&[u8]] = &[
@@ -28,6 +32,9 @@ const HEIF_HEIC_BRAND_NAMES: &[&[u8]] = &[
 
 const HEIC_BRAND_NAMES: &[&[u8]] = &[b"heic", b"heix", b"heim", b"heis"];
 
+// AVIF image & AVIF image sequence
+const AVIF_BRAND_NAMES: &[&[u8]] = &[b"avif", b"avis"];
+
 // TODO: Refer to the information on the website https://www.ftyps.com to add
 // other less common MP4 brands.
 const MP4_BRAND_NAMES: &[&str] = &[
@@ -37,6 +44,9 @@ const MP4_BRAND_NAMES: &[&str] = &[
 
 const QT_BRAND_NAMES: &[&str] = &["qt  ", "mqt "];
 
+// Audio-only MP4: m4a, m4b (audiobook), m4p (protected), f4a & f4b (Flash)
+const AUDIO_MP4_BRAND_NAMES: &[&[u8]] = &[b"M4A ", b"M4B ", b"M4P ", b"F4A ", b"F4B "];
+
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub(crate) enum Mime {
     Image(MimeImage),
@@ -56,6 +66,39 @@ impl Mime {
             Mime::Video(val) => val,
         }
     }
+
+    pub fn kind(self) -> MediaKind {
+        match self {
+            Mime::Image(x) => match x {
+                MimeImage::Jpeg => MediaKind::Jpeg,
+                MimeImage::Heic => MediaKind::Heic,
+                MimeImage::Heif => MediaKind::Heif,
+                MimeImage::Tiff => MediaKind::Tiff,
+                MimeImage::Raf => MediaKind::Raf,
+                MimeImage::Png => MediaKind::Png,
+                MimeImage::Webp => MediaKind::Webp,
+                MimeImage::Avif => MediaKind::Avif,
+                MimeImage::Jxl => MediaKind::Jxl,
+                MimeImage::Cr3 => MediaKind::Cr3,
+                MimeImage::Dng => MediaKind::Dng,
+                MimeImage::Nef => MediaKind::Nef,
+                MimeImage::Arw => MediaKind::Arw,
+                MimeImage::Cr2 => MediaKind::Cr2,
+                MimeImage::Orf => MediaKind::Orf,
+                MimeImage::Rw2 => MediaKind::Rw2,
+                MimeImage::Pef => MediaKind::Pef,
+                MimeImage::Srw => MediaKind::Srw,
+            },
+            Mime::Video(x) => match x {
+                MimeVideo::QuickTime => MediaKind::QuickTime,
+                MimeVideo::Mp4 => MediaKind::Mp4,
+                MimeVideo::Webm => MediaKind::Webm,
+                MimeVideo::Matroska => MediaKind::Matroska,
+                MimeVideo::_3gpp => MediaKind::ThreeGpp,
+                MimeVideo::M4a => MediaKind::M4a,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -65,6 +108,40 @@ pub(crate) enum MimeImage {
     Heif,
     Tiff,
     Raf, // Fujifilm RAW, image/x-fuji-raf
+    Png,
+    Webp,
+    Avif,
+    Jxl,
+    Cr3, // Canon RAW 3, image/x-canon-cr3
+
+    // TIFF based RAW formats
+    Dng, // Adobe Digital Negative
+    Nef, // Nikon
+    Arw, // Sony
+    Cr2, // Canon RAW 2
+    Orf, // Olympus
+    Rw2, // Panasonic
+    Pef, // Pentax
+    Srw, // Samsung
+}
+
+impl MimeImage {
+    /// Returns true if the file is a TIFF file, or a TIFF based RAW file, so
+    /// that it can be parsed as a TIFF file.
+    pub(crate) fn is_tiff(&self) -> bool {
+        matches!(
+            self,
+            MimeImage::Tiff
+                | MimeImage::Dng
+                | MimeImage::Nef
+                | MimeImage::Arw
+                | MimeImage::Cr2
+                | MimeImage::Orf
+                | MimeImage::Rw2
+                | MimeImage::Pef
+                | MimeImage::Srw
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -74,6 +151,178 @@ pub(crate) enum MimeVideo {
     Webm,
     Matroska,
     _3gpp,
+    M4a, // audio-only MP4, parsed like MP4
+}
+
+/// The file type of a media file, detected from its content, see
+/// [`detect`] & [`crate::MediaSource::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MediaKind {
+    Jpeg,
+    Heic,
+    Heif,
+    Tiff,
+    /// Fujifilm RAW
+    Raf,
+    Png,
+    Webp,
+    Avif,
+    /// JPEG XL
+    Jxl,
+    /// Canon RAW 3
+    Cr3,
+
+    /// Adobe Digital Negative
+    Dng,
+    /// Nikon RAW
+    Nef,
+    /// Sony RAW
+    Arw,
+    /// Canon RAW 2
+    Cr2,
+    /// Olympus RAW
+    Orf,
+    /// Panasonic RAW
+    Rw2,
+    /// Pentax RAW
+    Pef,
+    /// Samsung RAW
+    Srw,
+
+    QuickTime,
+    Mp4,
+    Webm,
+    /// mkv, mka, mk3d
+    Matroska,
+    ThreeGpp,
+
+    /// Audio-only MP4, e.g. m4a, m4b
+    M4a,
+}
+
+/// Classification of a [`MediaKind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MediaClass {
+    Image,
+    Video,
+    /// Audio-only files, see [`MediaKind::class`].
+    Audio,
+}
+
+impl MediaKind {
+    /// Returns the canonical MIME type, e.g. "image/jpeg".
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            MediaKind::Jpeg => "image/jpeg",
+            MediaKind::Heic => "image/heic",
+            MediaKind::Heif => "image/heif",
+            MediaKind::Tiff => "image/tiff",
+            MediaKind::Raf => "image/x-fuji-raf",
+            MediaKind::Png => "image/png",
+            MediaKind::Webp => "image/webp",
+            MediaKind::Avif => "image/avif",
+            MediaKind::Jxl => "image/jxl",
+            MediaKind::Cr3 => "image/x-canon-cr3",
+            MediaKind::Dng => "image/x-adobe-dng",
+            MediaKind::Nef => "image/x-nikon-nef",
+            MediaKind::Arw => "image/x-sony-arw",
+            MediaKind::Cr2 => "image/x-canon-cr2",
+            MediaKind::Orf => "image/x-olympus-orf",
+            MediaKind::Rw2 => "image/x-panasonic-rw2",
+            MediaKind::Pef => "image/x-pentax-pef",
+            MediaKind::Srw => "image/x-samsung-srw",
+            MediaKind::QuickTime => "video/quicktime",
+            MediaKind::Mp4 => "video/mp4",
+            MediaKind::Webm => "video/webm",
+            MediaKind::Matroska => "video/x-matroska",
+            MediaKind::ThreeGpp => "video/3gpp",
+            MediaKind::M4a => "audio/mp4",
+        }
+    }
+
+    /// Returns the preferred file extension (without the leading dot), e.g.
+    /// "jpg".
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MediaKind::Jpeg => "jpg",
+            MediaKind::Heic => "heic",
+            MediaKind::Heif => "heif",
+            MediaKind::Tiff => "tif",
+            MediaKind::Raf => "raf",
+            MediaKind::Png => "png",
+            MediaKind::Webp => "webp",
+            MediaKind::Avif => "avif",
+            MediaKind::Jxl => "jxl",
+            MediaKind::Cr3 => "cr3",
+            MediaKind::Dng => "dng",
+            MediaKind::Nef => "nef",
+            MediaKind::Arw => "arw",
+            MediaKind::Cr2 => "cr2",
+            MediaKind::Orf => "orf",
+            MediaKind::Rw2 => "rw2",
+            MediaKind::Pef => "pef",
+            MediaKind::Srw => "srw",
+            MediaKind::QuickTime => "mov",
+            MediaKind::Mp4 => "mp4",
+            MediaKind::Webm => "webm",
+            MediaKind::Matroska => "mkv",
+            MediaKind::ThreeGpp => "3gp",
+            MediaKind::M4a => "m4a",
+        }
+    }
+
+    /// Returns whether it's an image, a video or an audio file.
+    ///
+    /// Audio-only MP4 files are identified by the major brand of `ftyp`
+    /// (e.g. `M4A `). Note that other audio-only files in a video container
+    /// (e.g. `.mka`, or MP4 files with a generic brand) can't be told apart
+    /// by the file header, and are classified as videos.
+    pub fn class(&self) -> MediaClass {
+        match self {
+            MediaKind::QuickTime
+            | MediaKind::Mp4
+            | MediaKind::Webm
+            | MediaKind::Matroska
+            | MediaKind::ThreeGpp => MediaClass::Video,
+            MediaKind::M4a => MediaClass::Audio,
+            _ => MediaClass::Image,
+        }
+    }
+}
+
+impl Display for MediaKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.mime_type().fmt(f)
+    }
+}
+
+/// Detects the file type from the beginning of a media file.
+///
+/// `input` should contain at least the first 4096 bytes of the file (or the
+/// whole file if it's smaller), so that TIFF based RAW formats can be
+/// identified by their IFD0 entries; otherwise they may be detected as
+/// [`MediaKind::Tiff`].
+///
+/// Returns [`crate::Error::UnrecognizedFileFormat`] if the file type isn't
+/// supported.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// let data = std::fs::read("./testdata/exif.jpg").unwrap();
+/// let kind = detect(&data).unwrap();
+/// assert_eq!(kind, MediaKind::Jpeg);
+/// assert_eq!(kind.mime_type(), "image/jpeg");
+/// assert_eq!(kind.extension(), "jpg");
+/// assert_eq!(kind.class(), MediaClass::Image);
+/// ```
+pub fn detect(input: &[u8]) -> crate::Result<MediaKind> {
+    let mime: Mime = input.try_into()?;
+    Ok(mime.kind())
 }
 
 impl TryFrom<&[u8]> for Mime {
@@ -88,11 +337,17 @@ impl TryFrom<&[u8]> for Mime {
                 Mime::Video(MimeVideo::Matroska)
             }
         } else if TiffHeader::parse(input).is_ok() {
-            Mime::Image(MimeImage::Tiff)
+            Mime::Image(parse_tiff_mime(input))
         } else if check_jpeg(input).is_ok() {
             Mime::Image(MimeImage::Jpeg)
         } else if RafInfo::check(input).is_ok() {
             Mime::Image(MimeImage::Raf)
+        } else if check_png(input).is_ok() {
+            Mime::Image(MimeImage::Png)
+        } else if check_webp(input).is_ok() {
+            Mime::Image(MimeImage::Webp)
+        } else if check_jxl(input).is_ok() {
+            Mime::Image(MimeImage::Jxl)
         } else {
             return Err(crate::Error::UnrecognizedFileFormat);
         };
@@ -101,7 +356,8 @@ impl TryFrom<&[u8]> for Mime {
     }
 }
 
-/// *Deprecated*: Please use [`MediaSource`] instead.
+/// *Deprecated*: Please use [`crate::MediaSource`] instead, or [`detect`] if only
+/// the file type ([`MediaKind`]) is needed.
 #[deprecated(since = "2.0.0")]
 #[allow(unused)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -177,6 +433,181 @@ impl Display for FileFormat {
     }
 }
 
+/// Tag code of `DNGVersion`.
+const DNG_VERSION_TAG: u16 = 0xC612;
+/// `PhotometricInterpretation` values of raw sensor data: CFA & LinearRaw.
+const RAW_PHOTOMETRIC: [u16; 2] = [32803, 34892];
+
+/// Identify the TIFF based RAW formats, by the non-standard TIFF magic
+/// (ORF/RW2), the CR2 marker, or `DNGVersion` in IFD0.
+///
+/// Other RAW formats are identified by the `Make` in IFD0, only if there is
+/// a RAW specific tag as well, i.e. raw sensor data (CFA or LinearRaw) in
+/// IFD0 or one of its `SubIFDs`, or `DNGPrivateData` (e.g. `SR2Private` of
+/// Sony), so that plain TIFF images from cameras (or their software) aren't
+/// taken as RAW files.
+///
+/// If the tags aren't located in `input`, a generic TIFF is returned.
+pub(crate) fn parse_tiff_mime(input: &[u8]) -> MimeImage {
+    let Ok((_, header)) = TiffHeader::parse(input) else {
+        return MimeImage::Tiff;
+    };
+
+    match &input[2..4] {
+        // "IIRO", "IIRS", "MMOR"
+        b"RO" | b"RS" | b"OR" => return MimeImage::Orf,
+        // "IIU\0"
+        b"U\0" => return MimeImage::Rw2,
+        _ => (),
+    }
+    if input.get(8..11) == Some(b"CR\x02") {
+        return MimeImage::Cr2;
+    }
+
+    let ifd0 = scan_ifd0(input, &header);
+    if ifd0.dng {
+        return MimeImage::Dng;
+    }
+    if !(ifd0.raw_data || ifd0.private_data) {
+        return MimeImage::Tiff;
+    }
+    let Some(make) = ifd0.make else {
+        return MimeImage::Tiff;
+    };
+    let make = make.to_ascii_uppercase();
+    [
+        (&b"NIKON"[..], MimeImage::Nef),
+        (b"SONY", MimeImage::Arw),
+        (b"PENTAX", MimeImage::Pef),
+        (b"SAMSUNG", MimeImage::Srw),
+    ]
+    .into_iter()
+    .find(|(prefix, _)| make.starts_with(prefix))
+    .map(|(_, mime)| mime)
+    .unwrap_or(MimeImage::Tiff)
+}
+
+/// What [`scan_ifd0`] found in IFD0.
+#[derive(Debug, Default)]
+struct Ifd0Scan<'a> {
+    /// Has `DNGVersion`.
+    dng: bool,
+    /// `Make` data.
+    make: Option<&'a [u8]>,
+    /// Raw sensor data in IFD0 or one of its `SubIFDs`.
+    raw_data: bool,
+    /// Has `DNGPrivateData`.
+    private_data: bool,
+}
+
+/// Scan IFD0 entries for `DNGVersion`, `Make`, `DNGPrivateData` & raw sensor
+/// data.
+fn scan_ifd0<'a>(input: &'a [u8], header: &TiffHeader) -> Ifd0Scan<'a> {
+    let value_size = offset_size(header.bigtiff);
+
+    let mut scan = Ifd0Scan::default();
+    for (entry, (tag, format, count, value_or_offset)) in
+        ifd_entries(input, header, header.ifd0_offset as usize)
+    {
+        if tag == DNG_VERSION_TAG {
+            scan.dng = true;
+        } else if tag == ExifTag::DNGPrivateData.code() {
+            scan.private_data = true;
+        } else if tag == ExifTag::PhotometricInterpretation.code() {
+            scan.raw_data |= is_raw_photometric(entry, header);
+        } else if tag == ExifTag::SubIFDs.code() {
+            scan.raw_data |= sub_ifd_offsets(input, header, entry, format, count, value_or_offset)
+                .into_iter()
+                .any(|offset| {
+                    ifd_entries(input, header, offset).any(|(entry, (tag, ..))| {
+                        tag == ExifTag::PhotometricInterpretation.code()
+                            && is_raw_photometric(entry, header)
+                    })
+                });
+        } else if tag == ExifTag::Make.code() {
+            let count = count as usize;
+            scan.make = if count <= value_size {
+                entry.get(entry.len() - value_size..entry.len() - value_size + count)
+            } else {
+                let offset = value_or_offset as usize;
+                offset
+                    .checked_add(count)
+                    .and_then(|end| input.get(offset..end))
+            };
+        }
+    }
+    scan
+}
+
+/// Iterate the entries of the IFD at `offset`, as (entry data, parsed
+/// entry), stops at the first entry which isn't located in `input`.
+fn ifd_entries<'a>(
+    input: &'a [u8],
+    header: &TiffHeader,
+    offset: usize,
+) -> impl Iterator<Item = (&'a [u8], (u16, u16, u64, u64))> {
+    let (endian, bigtiff) = (header.endian, header.bigtiff);
+    let entry_size = ifd_entry_size(bigtiff);
+
+    let (start, num) = input
+        .get(offset..)
+        .and_then(|x| TiffHeader::parse_ifd_entry_num(x, endian, bigtiff).ok())
+        .map(|(remain, num)| (input.len() - remain.len(), num as usize))
+        .unwrap_or_default();
+    (0..num).map_while(move |i| {
+        let pos = start + i * entry_size;
+        let entry = input.get(pos..pos + entry_size)?;
+        let (_, parsed) = TiffHeader::parse_ifd_entry(entry, endian, bigtiff).ok()?;
+        Some((entry, parsed))
+    })
+}
+
+fn is_raw_photometric(entry: &[u8], header: &TiffHeader) -> bool {
+    let value = &entry[entry.len() - offset_size(header.bigtiff)..];
+    nom::number::complete::u16::<_, nom::error::Error<_>>(header.endian)(value)
+        .is_ok_and(|(_, x)| RAW_PHOTOMETRIC.contains(&x))
+}
+
+/// Offsets of the IFDs of a `SubIFDs` entry, at most [`MAX_SUB_IFDS`].
+fn sub_ifd_offsets(
+    input: &[u8],
+    header: &TiffHeader,
+    entry: &[u8],
+    format: u16,
+    count: u64,
+    value_or_offset: u64,
+) -> Vec<usize> {
+    let value_size = offset_size(header.bigtiff);
+    // LONG8 & IFD8 are 8 bytes, LONG & IFD are 4 bytes
+    let size = if matches!(format, 16 | 18) { 8 } else { 4 };
+    let count = (count as usize).min(MAX_SUB_IFDS);
+    let data = if count * size <= value_size {
+        entry.get(entry.len() - value_size..)
+    } else {
+        let offset = value_or_offset as usize;
+        offset
+            .checked_add(count * size)
+            .and_then(|end| input.get(offset..end))
+    };
+    let Some(data) = data else {
+        return Vec::new();
+    };
+
+    data.chunks_exact(size)
+        .take(count)
+        .filter_map(|x| {
+            let (_, offset) = if size == 8 {
+                nom::number::complete::u64::<_, nom::error::Error<_>>(header.endian)(x).ok()?
+            } else {
+                nom::number::complete::u32::<_, nom::error::Error<_>>(header.endian)(x)
+                    .map(|(remain, x)| (remain, x.into()))
+                    .ok()?
+            };
+            usize::try_from(offset).ok()
+        })
+        .collect()
+}
+
 fn get_ebml_doc_type(input: &[u8]) -> crate::Result<String> {
     let mut cursor = Cursor::new(input);
     let doc = parse_ebml_doc_type(&mut cursor)?;
@@ -195,11 +626,39 @@ fn parse_bmff_mime(input: &[u8]) -> crate::Result<Mime> {
         return Err(crate::Error::UnrecognizedFileFormat);
     };
 
+    // Canon CR3 RAW
+    if major_brand == b"crx " {
+        return Ok(Mime::Image(MimeImage::Cr3));
+    }
+
     // Check if it is a QuickTime file
     if QT_BRAND_NAMES.iter().any(|v| v.as_bytes() == major_brand) {
         return Ok(Mime::Video(MimeVideo::QuickTime));
     }
 
+    // Only the major brand is checked, since e.g. M4V videos list `M4A ` in
+    // their compatible brands
+    if AUDIO_MP4_BRAND_NAMES.contains(&major_brand) {
+        return Ok(Mime::Video(MimeVideo::M4a));
+    }
+
+    let compatible_brands = ftyp.body_data();
+    let is_compatible_with = |brands: &[&[u8]]| {
+        brands
+            .iter()
+            .any(|x| compatible_brands.find_substring(*x).is_some())
+    };
+
+    // Check if it is an AVIF file, the major brand may be a general HEIF
+    // brand, e.g. `mif1`
+    if AVIF_BRAND_NAMES.contains(&major_brand)
+        || (HEIF_HEIC_BRAND_NAMES.contains(&major_brand)
+            && !HEIC_BRAND_NAMES.contains(&major_brand)
+            && is_compatible_with(AVIF_BRAND_NAMES))
+    {
+        return Ok(Mime::Image(MimeImage::Avif));
+    }
+
     // Check if it is a HEIF file
     if HEIF_HEIC_BRAND_NAMES.contains(&major_brand) {
         if HEIC_BRAND_NAMES.contains(&major_brand) {
@@ -217,8 +676,6 @@ fn parse_bmff_mime(input: &[u8]) -> crate::Result<Mime> {
     }
 
     // Check compatible brands
-    let compatible_brands = ftyp.body_data();
-
     if QT_BRAND_NAMES
         .iter()
         .any(|v| compatible_brands.find_substring(v.as_bytes()).is_some())
@@ -226,10 +683,11 @@ fn parse_bmff_mime(input: &[u8]) -> crate::Result<Mime> {
         return Ok(Mime::Video(MimeVideo::QuickTime));
     }
 
-    if HEIF_HEIC_BRAND_NAMES
-        .iter()
-        .any(|x| compatible_brands.find_substring(*x).is_some())
-    {
+    if is_compatible_with(AVIF_BRAND_NAMES) {
+        return Ok(Mime::Image(MimeImage::Avif));
+    }
+
+    if is_compatible_with(HEIF_HEIC_BRAND_NAMES) {
         if HEIC_BRAND_NAMES.contains(&major_brand) {
             return Ok(Mime::Image(MimeImage::Heic));
         }
@@ -376,12 +834,108 @@ mod tests {
     #[test_case("mkv_640x360.mkv", Video(Matroska))]
     #[test_case("mka.mka", Video(Matroska))]
     #[test_case("3gp_640x360.3gp", Video(_3gpp))]
+    #[test_case("exif.png", Image(Png))]
+    #[test_case("exif.webp", Image(Webp))]
+    #[test_case("exif.avif", Image(Avif))]
+    #[test_case("exif.jxl", Image(Jxl))]
+    #[test_case("exif.cr3", Image(Cr3))]
     fn mime(path: &str, mime: Mime) {
         let data = read_sample(path).unwrap();
         let m: Mime = data.deref().try_into().unwrap();
         assert_eq!(m, mime);
     }
 
+    #[test_case("exif.jpg", MediaKind::Jpeg, "image/jpeg", "jpg", MediaClass::Image)]
+    #[test_case("tif.tif", MediaKind::Tiff, "image/tiff", "tif", MediaClass::Image)]
+    #[test_case(
+        "fujifilm_x_t1_01.raf.meta",
+        MediaKind::Raf,
+        "image/x-fuji-raf",
+        "raf",
+        MediaClass::Image
+    )]
+    #[test_case(
+        "exif.cr3",
+        MediaKind::Cr3,
+        "image/x-canon-cr3",
+        "cr3",
+        MediaClass::Image
+    )]
+    #[test_case(
+        "meta.mov",
+        MediaKind::QuickTime,
+        "video/quicktime",
+        "mov",
+        MediaClass::Video
+    )]
+    #[test_case(
+        "webm_480.webm",
+        MediaKind::Webm,
+        "video/webm",
+        "webm",
+        MediaClass::Video
+    )]
+    #[test_case(
+        "mkv_640x360.mkv",
+        MediaKind::Matroska,
+        "video/x-matroska",
+        "mkv",
+        MediaClass::Video
+    )]
+    #[test_case(
+        "3gp_640x360.3gp",
+        MediaKind::ThreeGpp,
+        "video/3gpp",
+        "3gp",
+        MediaClass::Video
+    )]
+    fn detect_kind(path: &str, kind: MediaKind, mime: &str, ext: &str, class: MediaClass) {
+        let data = read_sample(path).unwrap();
+        let k = detect(&data).unwrap();
+        assert_eq!(k, kind);
+        assert_eq!(k.mime_type(), mime);
+        assert_eq!(k.to_string(), mime);
+        assert_eq!(k.extension(), ext);
+        assert_eq!(k.class(), class);
+
+        let ms = crate::MediaSource::file(open_sample(path).unwrap()).unwrap();
+        assert_eq!(ms.kind(), kind);
+    }
+
+    #[test_case(b"M4A ", MediaKind::M4a, MediaClass::Audio)]
+    #[test_case(b"M4B ", MediaKind::M4a, MediaClass::Audio)]
+    #[test_case(b"F4A ", MediaKind::M4a, MediaClass::Audio)]
+    // Not an audio brand, detected by the compatible brands
+    #[test_case(b"M4V ", MediaKind::Mp4, MediaClass::Video)]
+    fn detect_mp4_brand(brand: &[u8], kind: MediaKind, class: MediaClass) {
+        let mut data = read_sample("meta.mp4").unwrap();
+        data[8..12].copy_from_slice(brand);
+        let k = detect(&data).unwrap();
+        assert_eq!(k, kind);
+        assert_eq!(k.class(), class);
+
+        let ms = crate::MediaSource::seekable(Cursor::new(data)).unwrap();
+        assert_eq!(ms.kind(), kind);
+        assert!(ms.has_track());
+        let info: crate::TrackInfo = crate::MediaParser::new().parse(ms).unwrap();
+        assert_eq!(
+            info.get(crate::TrackInfoTag::DurationMs),
+            Some(&1063_u64.into())
+        );
+    }
+
+    #[test]
+    fn detect_raw_and_unknown() {
+        assert_eq!(
+            detect(&raw_tiff("SONY", RawSignal::PrivateData)).unwrap(),
+            MediaKind::Arw
+        );
+        assert!(matches!(
+            detect(b"not a media file"),
+            Err(crate::Error::UnrecognizedFileFormat)
+        ));
+    }
+
     #[test_case("exif.heic", FileFormat::Heif)]
     #[test_case("exif.jpg", FileFormat::Jpeg)]
     #[test_case("meta.mov", FileFormat::QuickTime)]
@@ -399,4 +953,110 @@ mod tests {
         let f = open_sample(path).unwrap();
         FileFormat::try_from_read(f).unwrap_err();
     }
+
+    enum RawSignal {
+        None,
+        Dng,
+        PrivateData,
+        /// `PhotometricInterpretation` in IFD0
+        Photometric(u16),
+        /// `PhotometricInterpretation` in a SubIFD
+        SubIfdPhotometric(u16),
+    }
+
+    fn raw_tiff(make: &str, signal: RawSignal) -> Vec<u8> {
+        let mut writer = crate::ExifWriter::little_endian();
+        writer.set(crate::IfdGroup::Ifd0, ExifTag::Make, make.into());
+        match signal {
+            RawSignal::None => (),
+            RawSignal::Dng => {
+                writer.set_by_tag_code(
+                    crate::IfdGroup::Ifd0,
+                    DNG_VERSION_TAG,
+                    crate::EntryValue::Undefined(vec![1, 4, 0, 0]),
+                );
+            }
+            RawSignal::PrivateData => {
+                writer.set(
+                    crate::IfdGroup::Ifd0,
+                    ExifTag::DNGPrivateData,
+                    crate::EntryValue::Undefined(vec![0; 4]),
+                );
+            }
+            RawSignal::Photometric(x) => {
+                writer.set(
+                    crate::IfdGroup::Ifd0,
+                    ExifTag::PhotometricInterpretation,
+                    x.into(),
+                );
+            }
+            RawSignal::SubIfdPhotometric(x) => {
+                // The SubIFD is appended after the data of IFD0, whose size
+                // doesn't depend on the offset
+                writer.set(crate::IfdGroup::Ifd0, ExifTag::SubIFDs, 0_u32.into());
+                let offset = writer.write().unwrap().len() as u32;
+                writer.set(crate::IfdGroup::Ifd0, ExifTag::SubIFDs, offset.into());
+                let mut data = writer.write().unwrap();
+                data.extend_from_slice(&1_u16.to_le_bytes());
+                data.extend_from_slice(&[0x06, 0x01, 3, 0, 1, 0, 0, 0]);
+                data.extend_from_slice(&(x as u32).to_le_bytes());
+                data.extend_from_slice(&0_u32.to_le_bytes());
+                return data;
+            }
+        }
+        writer.write().unwrap()
+    }
+
+    #[test_case(
+        raw_tiff("NIKON CORPORATION", RawSignal::SubIfdPhotometric(32803)),
+        Nef
+    )]
+    #[test_case(raw_tiff("NIKON CORPORATION", RawSignal::Photometric(32803)), Nef)]
+    #[test_case(raw_tiff("SONY", RawSignal::PrivateData), Arw)]
+    #[test_case(raw_tiff("PENTAX", RawSignal::Photometric(32803)), Pef)]
+    #[test_case(raw_tiff("SAMSUNG", RawSignal::SubIfdPhotometric(34892)), Srw)]
+    #[test_case(raw_tiff("NIKON CORPORATION", RawSignal::Dng), Dng)]
+    #[test_case(raw_tiff("Apple", RawSignal::Photometric(32803)), Tiff)]
+    // Plain TIFF images with the Make of a camera vendor
+    #[test_case(raw_tiff("NIKON CORPORATION", RawSignal::None), Tiff)]
+    #[test_case(raw_tiff("SONY", RawSignal::Photometric(2)), Tiff)]
+    #[test_case(raw_tiff("PENTAX", RawSignal::SubIfdPhotometric(2)), Tiff)]
+    #[test_case([&b"IIRO"[..], &raw_tiff("OLYMPUS", RawSignal::None)[4..]].concat(), Orf)]
+    #[test_case([&b"IIU\0"[..], &raw_tiff("Panasonic", RawSignal::None)[4..]].concat(), Rw2)]
+    #[test_case(b"II*\0\x10\0\0\0CR\x02\0\0\0\0\0\0\0\0\0\0\0".to_vec(), Cr2)]
+    fn tiff_raw_mime(data: Vec<u8>, expect: MimeImage) {
+        let m: Mime = data.deref().try_into().unwrap();
+        assert_eq!(m, Image(expect));
+        assert!(expect.is_tiff());
+    }
+
+    // IFD0 entries of a RAW file are located beyond the header buffer of
+    // `MediaSource`
+    #[test]
+    fn tiff_raw_mime_large_ifd0() {
+        let mut writer = crate::ExifWriter::new();
+        for (i, tag) in [0x100, 0x101, 0x102, 0x103, 0x10e].iter().enumerate() {
+            writer.set_by_tag_code(crate::IfdGroup::Ifd0, *tag, (i as u32).into());
+        }
+        // CFA
+        writer.set(
+            crate::IfdGroup::Ifd0,
+            ExifTag::PhotometricInterpretation,
+            32803_u16.into(),
+        );
+        writer.set(crate::IfdGroup::Ifd0, ExifTag::Model, "NIKON Z 8".into());
+        writer.set(
+            crate::IfdGroup::Ifd0,
+            ExifTag::Make,
+            "NIKON CORPORATION".into(),
+        );
+        let data = writer.write().unwrap();
+        assert!(data.len() > 128);
+
+        let ms = crate::MediaSource::seekable(Cursor::new(data)).unwrap();
+        assert_eq!(ms.mime, Image(Nef));
+        let iter: crate::ExifIter = crate::MediaParser::new().parse(ms).unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Model).unwrap().to_string(), "NIKON Z 8");
+    }
 }
@@ -3,11 +3,11 @@ use std::io::{Read, Seek};
 use nom::combinator::fail;
 use nom::{number::complete::be_u32, IResult};
 
-use crate::bbox::find_box;
+use crate::bbox::{find_box, travel_while};
 use crate::exif::Exif;
 use crate::{
     bbox::{BoxHolder, MetaBox, ParseBox},
-    exif::check_exif_header,
+    exif::{TiffHeader, EXIF_IDENT},
 };
 use crate::{ExifIter, MediaParser, MediaSource};
 
@@ -48,14 +48,60 @@ pub fn parse_heif_exif<R: Read + Seek>(reader: R) -> crate::Result<Option<Exif>>
 pub(crate) fn extract_exif_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
     let (remain, meta) = parse_meta_box(input)?;
 
-    if let Some(meta) = meta {
-        extract_exif_with_meta(input, &meta)
+    if let Some((offset, meta)) = meta {
+        extract_exif_with_meta(input, offset, &meta)
     } else {
         Ok((remain, None))
     }
 }
 
-pub(crate) fn parse_meta_box(input: &[u8]) -> IResult<&[u8], Option<MetaBox>> {
+/// Extract the XMP packet from the bytes of a HEIF/HEIC file.
+pub(crate) fn extract_xmp_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
+    let (remain, meta) = parse_meta_box(input)?;
+
+    if let Some((offset, meta)) = meta {
+        meta.xmp_data(input, offset)
+    } else {
+        Ok((remain, None))
+    }
+}
+
+/// Extract the ICC profile from the `colr` item property of a HEIF/HEIC
+/// file. Only colour type `prof` & `rICC` carry an ICC profile, `nclx` is
+/// ignored.
+pub(crate) fn extract_icc_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
+    let (remain, bbox) = BoxHolder::parse(input)?;
+    if bbox.box_type() != "ftyp" {
+        return fail(input);
+    }
+
+    let (remain, Some(meta)) = find_box(remain, "meta")? else {
+        return Ok((remain, None));
+    };
+    // skip version & flags of the full box header
+    let Some(body) = meta.body_data().get(4..) else {
+        return fail(input);
+    };
+    let (_, Some(ipco)) = find_box(body, "iprp/ipco")? else {
+        return Ok((remain, None));
+    };
+
+    let mut profile = None;
+    travel_while(ipco.body_data(), |b| {
+        if b.box_type() == "colr" {
+            let data = b.body_data();
+            if data.len() > 4 && (&data[..4] == b"prof" || &data[..4] == b"rICC") {
+                profile = Some(&data[4..]);
+            }
+        }
+        profile.is_none()
+    })?;
+
+    Ok((remain, profile))
+}
+
+/// Parse the top level `meta` box, returns (offset of the meta box, meta box).
+pub(crate) fn parse_meta_box(input: &[u8]) -> IResult<&[u8], Option<(usize, MetaBox)>> {
     let remain = input;
     let (remain, bbox) = BoxHolder::parse(remain)?;
     if bbox.box_type() != "ftyp" {
@@ -66,36 +112,37 @@ pub(crate) fn parse_meta_box(input: &[u8]) -> IResult<&[u8], Option<MetaBox>> {
         tracing::debug!(?bbox, "meta box not found");
         return Ok((remain, None));
     };
-    tracing::debug!(
-        ?bbox,
-        pos = input.len() - remain.len() - bbox.header.box_size as usize,
-        "Got meta box"
-    );
+    let offset = input.len() - remain.len() - bbox.data.len();
+    tracing::debug!(?bbox, pos = offset, "Got meta box");
     let (_, bbox) = MetaBox::parse_box(bbox.data)?;
     tracing::debug!(?bbox, "meta box parsed");
-    Ok((remain, Some(bbox)))
+    Ok((remain, Some((offset, bbox))))
 }
 
 pub(crate) fn extract_exif_with_meta<'a>(
     input: &'a [u8],
+    meta_offset: usize,
     bbox: &MetaBox,
 ) -> IResult<&'a [u8], Option<&'a [u8]>> {
-    let (out_remain, data) = bbox.exif_data(input)?;
+    let (out_remain, data) = bbox.exif_data(input, meta_offset)?;
     tracing::debug!(
         data_len = data.as_ref().map(|x| x.len()),
         "exif data extracted"
     );
 
-    if let Some(data) = data {
-        let (remain, _) = be_u32(data)?;
-        if check_exif_header(remain)? {
-            Ok((out_remain, Some(&remain[6..]))) // Safe-slice
-        } else {
-            Ok((out_remain, None))
-        }
-    } else {
-        Ok((out_remain, None))
-    }
+    Ok((out_remain, data.and_then(exif_item_tiff_data)))
+}
+
+/// The HEIF Exif item: a 4-bytes offset of TIFF header, then the Exif data.
+/// The TIFF header is usually preceded by `Exif\0\0` (e.g. HEIC files from
+/// iPhone), but it may be not (e.g. AVIF files from libavif).
+///
+/// Returns the TIFF data, or `None` if the TIFF header can't be found.
+pub(crate) fn exif_item_tiff_data(item: &[u8]) -> Option<&[u8]> {
+    let (data, offset) = be_u32::<_, nom::error::Error<_>>(item).ok()?;
+    let data = data.get(offset as usize..)?;
+    let data = data.strip_prefix(EXIF_IDENT.as_bytes()).unwrap_or(data);
+    TiffHeader::parse(data).is_ok().then_some(data)
 }
 
 #[allow(deprecated)]
@@ -142,4 +189,31 @@ mod tests {
             assert_eq!(exif.unwrap().len(), exif_size);
         }
     }
+
+    // (major brand, Exif identifier before the TIFF header, stored in idat)
+    #[test_case(b"avif", false, true)]
+    #[test_case(b"avif", false, false)]
+    #[test_case(b"avif", true, true)]
+    #[test_case(b"avis", false, true)]
+    #[test_case(b"mif1", true, false)]
+    fn avif_exif(major_brand: &[u8; 4], exif_ident: bool, in_idat: bool) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut writer = crate::ExifWriter::new();
+        writer.set(crate::IfdGroup::Ifd0, crate::ExifTag::Make, "abc".into());
+        let tiff = writer.write().unwrap();
+        let item = if exif_ident {
+            [&6u32.to_be_bytes()[..], EXIF_IDENT.as_bytes(), &tiff].concat()
+        } else {
+            [&0u32.to_be_bytes()[..], &tiff].concat()
+        };
+
+        let buf = avif_sample(major_brand, &item, in_idat);
+        let (_, exif) = extract_exif_data(&buf).unwrap();
+        assert_eq!(exif.unwrap(), tiff);
+
+        let iter: ExifIter = parse_sample(buf).unwrap();
+        let exif: Exif = iter.into();
+        assert_eq!(exif.get(crate::ExifTag::Make).unwrap().to_string(), "abc");
+    }
 }
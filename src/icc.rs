@@ -0,0 +1,444 @@
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::{
+    error::ParsingError,
+    exif::extract_tiff_tag_data,
+    file::{Mime, MimeImage},
+    heif, jpeg,
+};
+
+const HEADER_SIZE: usize = 128;
+const TIFF_ICC_TAG: u16 = 0x8773;
+
+/// Represents an embedded ICC profile.
+///
+/// The raw profile data can be obtained by [`IccProfile::data`], e.g. to be
+/// passed to a color management library. Besides, the profile header and the
+/// profile description (`desc` tag) are decoded, which is usually enough to
+/// tell the commonly used color spaces apart, e.g.:
+///
+/// - `"sRGB IEC61966-2.1"`
+/// - `"Display P3"`
+/// - `"Adobe RGB (1998)"`
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// let mut parser = MediaParser::new();
+/// let ms = MediaSource::file_path("./testdata/no-exif.jpg").unwrap();
+/// let icc: IccProfile = parser.parse(ms).unwrap();
+///
+/// assert_eq!(icc.description(), Some("sRGB IEC61966-2.1"));
+/// assert_eq!(icc.header().color_space, "RGB");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IccProfile {
+    data: Vec<u8>,
+    header: IccHeader,
+    description: Option<String>,
+}
+
+/// The decoded ICC profile header.
+///
+/// Signature fields (e.g. `device_class`, `color_space`) are represented as
+/// strings with the trailing spaces trimmed, e.g. `"mntr"`, `"RGB"`. A zero
+/// signature is represented as an empty string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IccHeader {
+    /// Profile size declared in the header.
+    pub size: u32,
+    pub cmm_type: String,
+    /// (major, minor, bug fix) version, e.g. `(4, 3, 0)`.
+    pub version: (u8, u8, u8),
+    /// Profile/device class, e.g. `"mntr"`, `"prtr"`, `"scnr"`.
+    pub device_class: String,
+    /// Data color space, e.g. `"RGB"`, `"GRAY"`, `"CMYK"`.
+    pub color_space: String,
+    /// Profile connection space, `"XYZ"` or `"Lab"`.
+    pub pcs: String,
+    pub created: Option<NaiveDateTime>,
+    /// Primary platform, e.g. `"APPL"`, `"MSFT"`.
+    pub platform: String,
+    pub manufacturer: String,
+    pub model: String,
+    pub rendering_intent: u32,
+    pub creator: String,
+}
+
+impl IccProfile {
+    /// Returns the raw profile data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn header(&self) -> &IccHeader {
+        &self.header
+    }
+
+    /// Returns the profile description stored in the `desc` tag, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the data of the tag with signature `sig` (e.g. `b"wtpt"`),
+    /// including the tag type signature.
+    pub fn tag_data(&self, sig: &[u8; 4]) -> Option<&[u8]> {
+        tag_data(&self.data, sig)
+    }
+}
+
+impl TryFrom<Vec<u8>> for IccProfile {
+    type Error = crate::Error;
+
+    fn try_from(mut data: Vec<u8>) -> Result<Self, Self::Error> {
+        let header = parse_header(&data).ok_or("invalid ICC profile")?;
+        // some writers pad the embedded profile, drop the padding bytes
+        if (HEADER_SIZE..data.len()).contains(&(header.size as usize)) {
+            data.truncate(header.size as usize);
+        }
+        let description = tag_data(&data, b"desc").and_then(parse_text);
+        Ok(Self {
+            data,
+            header,
+            description,
+        })
+    }
+}
+
+fn be_u16(data: &[u8], pos: usize) -> u16 {
+    u16::from_be_bytes(data[pos..pos + 2].try_into().unwrap())
+}
+
+fn be_u32(data: &[u8], pos: usize) -> u32 {
+    u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap())
+}
+
+fn signature(data: &[u8]) -> String {
+    String::from_utf8_lossy(data)
+        .trim_end_matches([' ', '\0'])
+        .to_owned()
+}
+
+fn parse_header(data: &[u8]) -> Option<IccHeader> {
+    if data.len() < HEADER_SIZE || &data[36..40] != b"acsp" {
+        return None;
+    }
+
+    let created = NaiveDate::from_ymd_opt(
+        be_u16(data, 24).into(),
+        be_u16(data, 26).into(),
+        be_u16(data, 28).into(),
+    )
+    .and_then(|d| {
+        d.and_hms_opt(
+            be_u16(data, 30).into(),
+            be_u16(data, 32).into(),
+            be_u16(data, 34).into(),
+        )
+    });
+
+    Some(IccHeader {
+        size: be_u32(data, 0),
+        cmm_type: signature(&data[4..8]),
+        version: (data[8], data[9] >> 4, data[9] & 0x0F),
+        device_class: signature(&data[12..16]),
+        color_space: signature(&data[16..20]),
+        pcs: signature(&data[20..24]),
+        created,
+        platform: signature(&data[40..44]),
+        manufacturer: signature(&data[48..52]),
+        model: signature(&data[52..56]),
+        rendering_intent: be_u32(data, 64),
+        creator: signature(&data[80..84]),
+    })
+}
+
+fn tag_data<'a>(data: &'a [u8], sig: &[u8; 4]) -> Option<&'a [u8]> {
+    if data.len() < HEADER_SIZE + 4 {
+        return None;
+    }
+    let count = be_u32(data, HEADER_SIZE);
+    let table = &data[HEADER_SIZE + 4..];
+    table
+        .chunks_exact(12)
+        .take(count as usize)
+        .find(|x| &x[..4] == sig)
+        .and_then(|x| {
+            let offset = be_u32(x, 4) as usize;
+            let size = be_u32(x, 8) as usize;
+            data.get(offset..offset.checked_add(size)?)
+        })
+}
+
+/// Decode a text tag of type `desc` (ICC v2), `mluc` (ICC v4) or `text`.
+fn parse_text(data: &[u8]) -> Option<String> {
+    if data.len() < 12 {
+        return None;
+    }
+
+    match &data[..4] {
+        b"desc" => {
+            let count = be_u32(data, 8) as usize;
+            let s = data.get(12..12usize.checked_add(count)?)?;
+            Some(ascii(s))
+        }
+        b"text" => Some(ascii(&data[8..])),
+        b"mluc" => {
+            let num = be_u32(data, 8) as usize;
+            let rec_size = be_u32(data, 12) as usize;
+            if rec_size < 12 {
+                return None;
+            }
+            // (language & country code, text)
+            let records = data
+                .get(16..)?
+                .chunks_exact(rec_size)
+                .take(num)
+                .filter_map(|r| {
+                    let len = be_u32(r, 4) as usize;
+                    let offset = be_u32(r, 8) as usize;
+                    let s = data.get(offset..offset.checked_add(len)?)?;
+                    Some((&r[..4], s))
+                })
+                .collect::<Vec<_>>();
+
+            let (_, s) = records
+                .iter()
+                .find(|(code, _)| code == b"enUS")
+                .or_else(|| records.iter().find(|(code, _)| code.starts_with(b"en")))
+                .or(records.first())?;
+            let s = s.chunks_exact(2).map(|x| u16::from_be_bytes([x[0], x[1]]));
+            Some(
+                char::decode_utf16(s)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect::<String>()
+                    .trim_end_matches('\0')
+                    .to_owned(),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn ascii(data: &[u8]) -> String {
+    let end = data.iter().position(|x| *x == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
+
+pub(crate) fn extract_icc(mime: Mime, input: &[u8]) -> Result<Option<Vec<u8>>, ParsingError> {
+    match mime {
+        Mime::Image(MimeImage::Jpeg) => {
+            let (_, data) = jpeg::extract_icc_data(input)?;
+            Ok(data)
+        }
+        Mime::Image(img) if img.is_tiff() => {
+            let data = extract_tiff_tag_data(input, TIFF_ICC_TAG)?;
+            Ok(data.map(|x| x.to_vec()))
+        }
+        Mime::Image(MimeImage::Heic | MimeImage::Heif | MimeImage::Avif) => {
+            let (_, data) = heif::extract_icc_data(input)?;
+            Ok(data.map(|x| x.to_vec()))
+        }
+        _ => Err(ParsingError::Failed(format!(
+            "ICC profile is not supported for {mime:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::{bmff_box, parse_sample, read_sample};
+    use crate::{EntryValue, ExifTag, ExifWriter, IfdGroup};
+    use test_case::test_case;
+
+    fn desc_v2(s: &str) -> Vec<u8> {
+        [
+            &b"desc\0\0\0\0"[..],
+            &(s.len() as u32 + 1).to_be_bytes(),
+            s.as_bytes(),
+            &[0],
+            &[0; 79],
+        ]
+        .concat()
+    }
+
+    fn mluc(records: &[(&[u8; 4], &str)]) -> Vec<u8> {
+        let mut head = [
+            &b"mluc\0\0\0\0"[..],
+            &(records.len() as u32).to_be_bytes(),
+            &12u32.to_be_bytes(),
+        ]
+        .concat();
+        let mut texts = Vec::new();
+        let base = 16 + records.len() * 12;
+        for (code, s) in records {
+            let s = s
+                .encode_utf16()
+                .flat_map(|x| x.to_be_bytes())
+                .collect::<Vec<_>>();
+            head.extend_from_slice(&code[..]);
+            head.extend_from_slice(&(s.len() as u32).to_be_bytes());
+            head.extend_from_slice(&((base + texts.len()) as u32).to_be_bytes());
+            texts.extend(s);
+        }
+        [head, texts].concat()
+    }
+
+    fn profile(version: u8, desc: &[u8]) -> Vec<u8> {
+        let mut header = vec![0u8; HEADER_SIZE];
+        header[4..8].copy_from_slice(b"appl");
+        header[8] = version;
+        header[9] = 0x30;
+        header[12..16].copy_from_slice(b"mntr");
+        header[16..20].copy_from_slice(b"RGB ");
+        header[20..24].copy_from_slice(b"XYZ ");
+        for (i, v) in [2022u16, 1, 1, 0, 0, 0].iter().enumerate() {
+            header[24 + i * 2..26 + i * 2].copy_from_slice(&v.to_be_bytes());
+        }
+        header[36..40].copy_from_slice(b"acsp");
+        header[40..44].copy_from_slice(b"APPL");
+        header[64..68].copy_from_slice(&1u32.to_be_bytes());
+
+        let offset = HEADER_SIZE + 4 + 12;
+        let mut data = [
+            header,
+            1u32.to_be_bytes().to_vec(),
+            b"desc".to_vec(),
+            (offset as u32).to_be_bytes().to_vec(),
+            (desc.len() as u32).to_be_bytes().to_vec(),
+            desc.to_vec(),
+        ]
+        .concat();
+        let size = data.len() as u32;
+        data[..4].copy_from_slice(&size.to_be_bytes());
+        data
+    }
+
+    fn app2(seq: u8, count: u8, data: &[u8]) -> Vec<u8> {
+        let payload = [jpeg::ICC_IDENT, &[seq, count], data].concat();
+        [
+            &[0xFF, 0xE2][..],
+            &((payload.len() + 2) as u16).to_be_bytes(),
+            &payload,
+        ]
+        .concat()
+    }
+
+    #[test_case("no-exif.jpg", "sRGB IEC61966-2.1", (2, 1, 0), "MSFT")]
+    #[test_case("exif.jpg", "Display P3", (4, 0, 0), "APPL")]
+    fn jpeg_icc(path: &str, desc: &str, version: (u8, u8, u8), platform: &str) {
+        let icc: IccProfile = parse_sample(read_sample(path).unwrap()).unwrap();
+        assert_eq!(icc.description(), Some(desc));
+
+        let header = icc.header();
+        assert_eq!(header.size as usize, icc.data().len());
+        assert_eq!(header.version, version);
+        assert_eq!(header.device_class, "mntr");
+        assert_eq!(header.color_space, "RGB");
+        assert_eq!(header.pcs, "XYZ");
+        assert_eq!(header.platform, platform);
+        assert!(icc.tag_data(b"wtpt").is_some());
+    }
+
+    #[test]
+    fn no_exif_jpg_header() {
+        let icc: IccProfile = parse_sample(read_sample("no-exif.jpg").unwrap()).unwrap();
+        let header = icc.header();
+        assert_eq!(header.cmm_type, "Lino");
+        assert_eq!(header.manufacturer, "IEC");
+        assert_eq!(header.model, "sRGB");
+        assert_eq!(header.creator, "HP");
+        assert_eq!(header.created.unwrap().to_string(), "1998-02-09 06:49:00");
+    }
+
+    #[test]
+    fn jpeg_icc_chunks() {
+        let data = profile(2, &desc_v2("Adobe RGB (1998)"));
+        let (a, b) = data.split_at(100);
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &app2(2, 2, b),
+            &app2(1, 2, a),
+            &[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0],
+            &[0xFF, 0xD9],
+        ]
+        .concat();
+        let icc: IccProfile = parse_sample(jpeg).unwrap();
+        assert_eq!(icc.data(), data);
+        assert_eq!(icc.description(), Some("Adobe RGB (1998)"));
+        assert_eq!(icc.header().version, (2, 3, 0));
+        assert_eq!(icc.header().rendering_intent, 1);
+
+        // missing chunk
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &app2(2, 2, b),
+            &[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0],
+            &[0xFF, 0xD9],
+        ]
+        .concat();
+        parse_sample::<IccProfile>(jpeg).unwrap_err();
+    }
+
+    #[test_case(&[(b"deDE", "Anzeige P3"), (b"enUS", "Display P3")], "Display P3")]
+    #[test_case(&[(b"deDE", "Anzeige P3"), (b"enGB", "Display P3")], "Display P3")]
+    #[test_case(&[(b"zhCN", "\u{663e}\u{793a}\u{5668}")], "\u{663e}\u{793a}\u{5668}")]
+    fn mluc_description(records: &[(&[u8; 4], &str)], desc: &str) {
+        let icc = IccProfile::try_from(profile(4, &mluc(records))).unwrap();
+        assert_eq!(icc.description(), Some(desc));
+    }
+
+    #[test]
+    fn invalid_profile() {
+        let mut data = profile(4, &desc_v2("x"));
+        data[36] = b'x';
+        IccProfile::try_from(data).unwrap_err();
+        IccProfile::try_from(vec![0; 20]).unwrap_err();
+    }
+
+    #[test]
+    fn tiff_icc() {
+        let data = profile(2, &desc_v2("Adobe RGB (1998)"));
+        let mut writer = ExifWriter::new();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "abc".into())
+            .set_by_tag_code(IfdGroup::Ifd0, TIFF_ICC_TAG, EntryValue::Undefined(data));
+        let icc: IccProfile = parse_sample(writer.write().unwrap()).unwrap();
+        assert_eq!(icc.description(), Some("Adobe RGB (1998)"));
+
+        parse_sample::<IccProfile>(read_sample("tif.tif").unwrap()).unwrap_err();
+    }
+
+    #[test_case(b"prof", true)]
+    #[test_case(b"rICC", true)]
+    #[test_case(b"nclx", false)]
+    fn heif_icc(colour_type: &[u8; 4], found: bool) {
+        let data = profile(4, &mluc(&[(b"enUS", "Display P3")]));
+        let colr = bmff_box(b"colr", &[&colour_type[..], &data].concat());
+        let ipco = bmff_box(b"ipco", &[bmff_box(b"ispe", &[0; 12]), colr].concat());
+        let meta = bmff_box(
+            b"meta",
+            &[
+                &[0, 0, 0, 0][..],
+                &bmff_box(b"hdlr", &[0; 25]),
+                &bmff_box(b"iprp", &ipco),
+            ]
+            .concat(),
+        );
+        let heic = [
+            bmff_box(b"ftyp", b"heic\0\0\0\0mif1heic"),
+            meta,
+            bmff_box(b"mdat", &[0; 16]),
+        ]
+        .concat();
+
+        let res = parse_sample::<IccProfile>(heic);
+        if found {
+            assert_eq!(res.unwrap().description(), Some("Display P3"));
+        } else {
+            res.unwrap_err();
+        }
+    }
+}
@@ -0,0 +1,441 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+
+use crate::{
+    error::ParsingError,
+    exif::extract_tiff_tag_data,
+    file::{Mime, MimeImage},
+    jpeg,
+};
+
+/// Represents the IPTC-IIM datasets of the application record (record 2).
+///
+/// Please note that most datasets are repeatable according to the IIM
+/// specification, e.g. [`IptcTag::Keywords`], use [`Iptc::get_all`] to get
+/// all of the values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum IptcTag {
+    ObjectName = 5,
+    Urgency = 10,
+    Category = 15,
+    SupplementalCategories = 20,
+    Keywords = 25,
+    SpecialInstructions = 40,
+    /// Format: `CCYYMMDD`
+    DateCreated = 55,
+    /// Format: `HHMMSS±HHMM`
+    TimeCreated = 60,
+    DigitalCreationDate = 62,
+    DigitalCreationTime = 63,
+    Byline = 80,
+    BylineTitle = 85,
+    City = 90,
+    SubLocation = 92,
+    ProvinceState = 95,
+    CountryCode = 100,
+    CountryName = 101,
+    OriginalTransmissionReference = 103,
+    Headline = 105,
+    Credit = 110,
+    Source = 115,
+    CopyrightNotice = 116,
+    Contact = 118,
+    Caption = 120,
+    CaptionWriter = 122,
+}
+
+impl IptcTag {
+    pub const fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for IptcTag {
+    type Error = crate::Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        use IptcTag::*;
+        const TAGS: &[IptcTag] = &[
+            ObjectName,
+            Urgency,
+            Category,
+            SupplementalCategories,
+            Keywords,
+            SpecialInstructions,
+            DateCreated,
+            TimeCreated,
+            DigitalCreationDate,
+            DigitalCreationTime,
+            Byline,
+            BylineTitle,
+            City,
+            SubLocation,
+            ProvinceState,
+            CountryCode,
+            CountryName,
+            OriginalTransmissionReference,
+            Headline,
+            Credit,
+            Source,
+            CopyrightNotice,
+            Contact,
+            Caption,
+            CaptionWriter,
+        ];
+        TAGS.iter()
+            .find(|t| t.code() == v)
+            .copied()
+            .ok_or_else(|| format!("unknown IPTC dataset: 2:{v}").into())
+    }
+}
+
+impl Display for IptcTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Represents the IPTC-IIM data of a media file.
+///
+/// Text values of the application record (record 2) are decoded according to
+/// the `CodedCharacterSet` dataset (1:90): UTF-8 if it's declared, otherwise
+/// values are decoded as UTF-8 if they are valid, or as Latin-1 if they
+/// aren't. Binary datasets (e.g. `RecordVersion`) are omitted.
+///
+/// The IPTC data is searched for in:
+///
+/// - JPEG: the IPTC resource (0x0404) of the Photoshop image resources in
+///   `APP13` segments.
+/// - TIFF: tag `0x83BB` (IPTC-NAA), or the IPTC resource of tag `0x8649`
+///   (Photoshop image resources).
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+///     // There is no IPTC data in this file
+///     assert!(parser.parse::<_, _, Iptc>(ms).is_err());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Iptc {
+    // (dataset number, value) of record 2, in the original order
+    entries: Vec<(u8, String)>,
+}
+
+impl Iptc {
+    /// Get the first value of `tag`.
+    pub fn get(&self, tag: IptcTag) -> Option<&str> {
+        self.get_all(tag).next()
+    }
+
+    /// Get all values of `tag`, in the original order.
+    pub fn get_all(&self, tag: IptcTag) -> impl Iterator<Item = &str> {
+        self.get_all_by_code(tag.code())
+    }
+
+    /// Get all values by dataset number, in the original order.
+    pub fn get_all_by_code(&self, dataset: u8) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(move |x| x.0 == dataset)
+            .map(|x| x.1.as_str())
+    }
+
+    /// Iterate over all (dataset number, value) pairs of record 2.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &str)> {
+        self.entries.iter().map(|x| (x.0, x.1.as_str()))
+    }
+
+    pub fn keywords(&self) -> Vec<&str> {
+        self.get_all(IptcTag::Keywords).collect()
+    }
+
+    pub fn caption(&self) -> Option<&str> {
+        self.get(IptcTag::Caption)
+    }
+
+    pub fn headline(&self) -> Option<&str> {
+        self.get(IptcTag::Headline)
+    }
+
+    pub fn byline(&self) -> Option<&str> {
+        self.get(IptcTag::Byline)
+    }
+
+    pub fn copyright_notice(&self) -> Option<&str> {
+        self.get(IptcTag::CopyrightNotice)
+    }
+
+    /// Combine `DateCreated` & `TimeCreated`. Time is assumed to be 00:00:00
+    /// if `TimeCreated` is missing, and time zone is assumed to be UTC if it's
+    /// not specified.
+    pub fn date_created(&self) -> Option<DateTime<FixedOffset>> {
+        let date = NaiveDate::parse_from_str(self.get(IptcTag::DateCreated)?, "%Y%m%d").ok()?;
+        let (time, offset) = match self.get(IptcTag::TimeCreated) {
+            Some(s) => {
+                let time = NaiveTime::parse_from_str(s.get(..6)?, "%H%M%S").ok()?;
+                (time, parse_tz(&s[6..])?)
+            }
+            None => (NaiveTime::MIN, FixedOffset::east_opt(0)?),
+        };
+        offset.from_local_datetime(&date.and_time(time)).single()
+    }
+
+    /// Parse IPTC-IIM datasets.
+    pub(crate) fn parse(data: &[u8]) -> crate::Result<Self> {
+        let mut utf8 = false;
+        let mut raw = Vec::new();
+        for (record, dataset, value) in parse_datasets(data)? {
+            match (record, dataset) {
+                // CodedCharacterSet
+                (1, 90) => utf8 = value == b"\x1b%G",
+                (2, 0) => (), // RecordVersion (binary)
+                (2, _) => raw.push((dataset, value)),
+                _ => (),
+            }
+        }
+
+        let entries = raw
+            .into_iter()
+            .map(|(dataset, value)| (dataset, decode_text(value, utf8)))
+            .collect();
+        Ok(Iptc { entries })
+    }
+}
+
+/// Parse time zone in format `±HHMM`.
+fn parse_tz(s: &str) -> Option<FixedOffset> {
+    if s.is_empty() {
+        return FixedOffset::east_opt(0);
+    }
+    let sign = match s.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let hours: i32 = s.get(1..3)?.parse().ok()?;
+    let minutes: i32 = s.get(3..5)?.parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+fn decode_text(value: &[u8], utf8: bool) -> String {
+    let value = value.strip_suffix(b"\0").unwrap_or(value);
+    if utf8 {
+        return String::from_utf8_lossy(value).into_owned();
+    }
+    match std::str::from_utf8(value) {
+        Ok(s) => s.to_owned(),
+        // Latin-1
+        Err(_) => value.iter().map(|b| *b as char).collect(),
+    }
+}
+
+/// Returns (record number, dataset number, data) of each dataset.
+fn parse_datasets(mut data: &[u8]) -> crate::Result<Vec<(u8, u8, &[u8])>> {
+    let mut datasets = Vec::new();
+    // Each dataset: tag marker (0x1C), record, dataset, size, data
+    while data.len() >= 5 && data[0] == 0x1C {
+        let (record, dataset) = (data[1], data[2]);
+        let mut size = u16::from_be_bytes([data[3], data[4]]) as usize;
+        data = &data[5..];
+
+        // extended dataset: the lower 15 bits are the length of size field
+        if size & 0x8000 != 0 {
+            let n = size & 0x7FFF;
+            if n > 4 || data.len() < n {
+                return Err(format!("invalid IPTC dataset size, length: {n}").into());
+            }
+            size = data[..n].iter().fold(0, |acc, b| (acc << 8) | *b as usize);
+            data = &data[n..];
+        }
+
+        if data.len() < size {
+            return Err(format!("IPTC dataset {record}:{dataset} is truncated").into());
+        }
+        datasets.push((record, dataset, &data[..size]));
+        data = &data[size..];
+    }
+    Ok(datasets)
+}
+
+const IRB_IPTC: u16 = 0x0404;
+
+/// Find the IPTC resource (0x0404) in Photoshop image resource blocks.
+pub(crate) fn find_irb_iptc(mut data: &[u8]) -> Option<&[u8]> {
+    // Each resource block: signature (4), id (2), name (Pascal string, padded
+    // to even), size (4), data (padded to even)
+    while data.len() >= 12 && data[..4] == *b"8BIM" {
+        let id = u16::from_be_bytes([data[4], data[5]]);
+        let name_len = data[6] as usize;
+        let pos = 6 + ((name_len + 2) & !1);
+        let size = data
+            .get(pos..pos + 4)
+            .map(|x| u32::from_be_bytes(x.try_into().unwrap()) as usize)?;
+        let start = pos + 4;
+        let body = data.get(start..start.checked_add(size)?)?;
+        if id == IRB_IPTC {
+            return Some(body);
+        }
+        data = data.get(start + ((size + 1) & !1)..).unwrap_or_default();
+    }
+    None
+}
+
+const TIFF_IPTC_TAG: u16 = 0x83BB;
+const TIFF_PHOTOSHOP_TAG: u16 = 0x8649;
+
+/// Extract IPTC-IIM data from `input`.
+pub(crate) fn extract_iptc(mime: Mime, input: &[u8]) -> Result<Option<Vec<u8>>, ParsingError> {
+    match mime {
+        Mime::Image(MimeImage::Jpeg) => {
+            let (_, irb) = jpeg::extract_photoshop_irb(input)?;
+            Ok(irb.and_then(|x| find_irb_iptc(&x).map(|x| x.to_vec())))
+        }
+        Mime::Image(img) if img.is_tiff() => {
+            if let Some(data) = extract_tiff_tag_data(input, TIFF_IPTC_TAG)? {
+                return Ok(Some(data.to_vec()));
+            }
+            let irb = extract_tiff_tag_data(input, TIFF_PHOTOSHOP_TAG)?;
+            Ok(irb.and_then(find_irb_iptc).map(|x| x.to_vec()))
+        }
+        _ => Err(ParsingError::Failed(format!(
+            "IPTC is not supported for {mime:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::{parse_sample, read_sample};
+    use crate::{EntryValue, ExifTag, ExifWriter, IfdGroup};
+    use test_case::test_case;
+
+    fn dataset(record: u8, dataset: u8, data: &[u8]) -> Vec<u8> {
+        [
+            &[0x1C, record, dataset][..],
+            &(data.len() as u16).to_be_bytes(),
+            data,
+        ]
+        .concat()
+    }
+
+    fn irb(id: u16, data: &[u8]) -> Vec<u8> {
+        let mut irb = [
+            &b"8BIM"[..],
+            &id.to_be_bytes(),
+            &[0, 0],
+            &(data.len() as u32).to_be_bytes(),
+            data,
+        ]
+        .concat();
+        if irb.len() % 2 == 1 {
+            irb.push(0);
+        }
+        irb
+    }
+
+    fn sample_iim(utf8: bool) -> Vec<u8> {
+        let charset = if utf8 {
+            dataset(1, 90, b"\x1b%G")
+        } else {
+            Vec::new()
+        };
+        let caption: &[u8] = if utf8 {
+            "Caf\u{e9} \u{4e2d}".as_bytes()
+        } else {
+            b"Caf\xe9"
+        };
+        [
+            charset,
+            dataset(2, 0, &[0, 4]),
+            dataset(2, 25, b"cat"),
+            dataset(2, 25, b"dog"),
+            dataset(2, 120, caption),
+            dataset(2, 80, b"Alice"),
+            dataset(2, 116, b"(c) Bob"),
+            dataset(2, 55, b"20240301"),
+            dataset(2, 60, b"123045+0800"),
+            dataset(2, 200, b"custom"),
+        ]
+        .concat()
+    }
+
+    #[test_case(true, "Caf\u{e9} \u{4e2d}")]
+    #[test_case(false, "Caf\u{e9}")]
+    fn parse_iim(utf8: bool, caption: &str) {
+        let iptc = Iptc::parse(&sample_iim(utf8)).unwrap();
+        assert_eq!(iptc.keywords(), ["cat", "dog"]);
+        assert_eq!(iptc.caption(), Some(caption));
+        assert_eq!(iptc.byline(), Some("Alice"));
+        assert_eq!(iptc.copyright_notice(), Some("(c) Bob"));
+        assert_eq!(
+            iptc.date_created().unwrap().to_rfc3339(),
+            "2024-03-01T12:30:45+08:00"
+        );
+        assert_eq!(iptc.get_all_by_code(200).collect::<Vec<_>>(), ["custom"]);
+        assert_eq!(iptc.iter().count(), 8);
+        assert_eq!(IptcTag::try_from(25).unwrap(), IptcTag::Keywords);
+        IptcTag::try_from(200).unwrap_err();
+    }
+
+    #[test]
+    fn parse_extended_dataset() {
+        let value = vec![b'a'; 40000];
+        let data = [
+            &[0x1C, 2, 120, 0x80, 0x04][..],
+            &(value.len() as u32).to_be_bytes(),
+            &value,
+            &dataset(2, 25, b"k"),
+        ]
+        .concat();
+        let iptc = Iptc::parse(&data).unwrap();
+        assert_eq!(iptc.caption().unwrap().len(), 40000);
+        assert_eq!(iptc.keywords(), ["k"]);
+
+        Iptc::parse(&data[..100]).unwrap_err();
+    }
+
+    #[test]
+    fn jpeg_iptc() {
+        let irbs = [irb(0x040C, b"thumbnail"), irb(IRB_IPTC, &sample_iim(true))].concat();
+        let payload = [&b"Photoshop 3.0\0"[..], &irbs].concat();
+        let segment = [
+            &[0xFF, 0xED][..],
+            &((payload.len() + 2) as u16).to_be_bytes(),
+            &payload,
+        ]
+        .concat();
+
+        let mut buf = read_sample("exif.jpg").unwrap();
+        buf.splice(2..2, segment);
+        let iptc: Iptc = parse_sample(buf).unwrap();
+        assert_eq!(iptc.keywords(), ["cat", "dog"]);
+
+        parse_sample::<Iptc>(read_sample("exif.jpg").unwrap()).unwrap_err();
+    }
+
+    #[test_case(TIFF_IPTC_TAG)]
+    #[test_case(TIFF_PHOTOSHOP_TAG)]
+    fn tiff_iptc(tag: u16) {
+        let data = if tag == TIFF_IPTC_TAG {
+            sample_iim(false)
+        } else {
+            irb(IRB_IPTC, &sample_iim(false))
+        };
+        let mut writer = ExifWriter::new();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "abc".into())
+            .set_by_tag_code(IfdGroup::Ifd0, tag, EntryValue::Undefined(data));
+        let iptc: Iptc = parse_sample(writer.write().unwrap()).unwrap();
+        assert_eq!(iptc.caption(), Some("Caf\u{e9}"));
+    }
+}
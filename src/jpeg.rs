@@ -1,9 +1,10 @@
 use crate::{ExifIter, MediaParser, MediaSource};
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
 
 use nom::{bytes::streaming, combinator::fail, number, sequence::tuple, IResult};
 
-use crate::exif::{check_exif_header, Exif};
+use crate::exif::{check_exif_header, Exif, EXIF_IDENT};
+use crate::xmp::XmpPackets;
 
 /// *Deprecated*: Please use [`MediaParser`] + [`MediaSource`] instead.
 ///
@@ -34,7 +35,7 @@ use crate::exif::{check_exif_header, Exif};
 ///         .collect::<Vec<_>>(),
 ///     [
 ///         ("DateTimeOriginal", "2023-07-09T20:36:33+08:00"),
-///         ("CreateDate", "2023-07-09T20:36:33+08:00"),
+///         ("CreateDate", "2023-07-09T20:36:33"),
 ///         ("ModifyDate", "2023-07-09T20:36:33+08:00")
 ///     ]
 ///     .into_iter()
@@ -62,6 +63,194 @@ pub(crate) fn extract_exif_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
     Ok((remain, data))
 }
 
+/// Rewrite the Exif data of a JPEG file without re-encoding the image.
+///
+/// Reads a JPEG file from `reader`, and writes the result to `writer`:
+///
+/// - If `exif` is `Some`, the existing APP1 Exif segment will be replaced
+///   with it. If there is no Exif segment in the file, a new one will be
+///   inserted right after the SOI marker (or the JFIF APP0 segment if
+///   present).
+///
+/// - If `exif` is `None`, the existing APP1 Exif segment(s) will be removed.
+///
+/// `exif` is the TIFF/Exif data, e.g. the output of
+/// [`ExifWriter::write`](crate::ExifWriter::write), the "Exif\0\0"
+/// identifier will be added automatically.
+///
+/// All other segments and the entropy-coded image data are copied
+/// byte-for-byte.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+/// use std::fs::File;
+///
+/// fn main() -> Result<()> {
+///     let mut writer = ExifWriter::new();
+///     writer.set(IfdGroup::Ifd0, ExifTag::Make, "nom-exif".into());
+///     let exif = writer.write()?;
+///
+///     let mut output = Vec::new();
+///     let f = File::open("./testdata/no-exif.jpg")?;
+///     rewrite_jpeg_exif(f, &mut output, Some(&exif))?;
+///
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::seekable(std::io::Cursor::new(output))?;
+///     let exif: Exif = parser.parse::<_, _, ExifIter>(ms)?.into();
+///     assert_eq!(exif.get(ExifTag::Make).unwrap(), &"nom-exif".into());
+///     Ok(())
+/// }
+/// ```
+pub fn rewrite_jpeg_exif<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    exif: Option<&[u8]>,
+) -> crate::Result<()> {
+    let payload = exif.map(|data| {
+        if data.starts_with(EXIF_IDENT.as_bytes()) {
+            data.to_vec()
+        } else {
+            [EXIF_IDENT.as_bytes(), data].concat()
+        }
+    });
+
+    rewrite_segments(reader, writer, |segments| {
+        // Only the first Exif segment will be replaced, others are removed.
+        let pos = segments.iter().position(SegmentBuf::is_exif);
+        segments.retain(|s| !s.is_exif());
+
+        if let Some(payload) = payload {
+            let pos = pos.unwrap_or_else(|| {
+                // Insert after the JFIF APP0 segments
+                segments
+                    .iter()
+                    .take_while(|s| s.marker_code == MarkerCode::APP0.code())
+                    .count()
+            });
+            segments.insert(
+                pos,
+                SegmentBuf {
+                    marker_code: MarkerCode::APP1.code(),
+                    payload,
+                },
+            );
+        }
+        Ok(())
+    })
+}
+
+/// An owned JPEG segment, used when rewriting a JPEG file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SegmentBuf {
+    pub marker_code: u8,
+    pub payload: Vec<u8>,
+}
+
+impl SegmentBuf {
+    pub fn is_exif(&self) -> bool {
+        self.marker_code == MarkerCode::APP1.code()
+            && self.payload.starts_with(EXIF_IDENT.as_bytes())
+    }
+}
+
+/// Read all the segments before the image data (SOS) into memory, let `f`
+/// edit them, then write them out, followed by the image data.
+///
+/// The SOI & SOS segments are not passed to `f`.
+pub(crate) fn rewrite_segments<R, W, F>(mut reader: R, mut writer: W, f: F) -> crate::Result<()>
+where
+    R: Read,
+    W: Write,
+    F: FnOnce(&mut Vec<SegmentBuf>) -> crate::Result<()>,
+{
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    if header != [0xFF, MarkerCode::Soi.code()] {
+        return Err("invalid JPEG file; SOI marker not found".into());
+    }
+
+    let mut segments = Vec::new();
+    let tail = loop {
+        let marker_code = read_marker_code(&mut reader)?;
+        if marker_code == MarkerCode::Eoi.code() {
+            break None;
+        }
+        if is_standalone_marker(marker_code) {
+            segments.push(SegmentBuf {
+                marker_code,
+                payload: Vec::new(),
+            });
+            continue;
+        }
+
+        reader.read_exact(&mut header)?;
+        let size = u16::from_be_bytes(header);
+        if size < 2 {
+            return Err(format!("invalid JPEG segment size: {size}").into());
+        }
+        // size contains the two bytes of `size` itself
+        let mut payload = vec![0u8; size as usize - 2];
+        reader.read_exact(&mut payload)?;
+
+        let segment = SegmentBuf {
+            marker_code,
+            payload,
+        };
+        if marker_code == MarkerCode::Sos.code() {
+            break Some(segment);
+        }
+        segments.push(segment);
+    };
+
+    f(&mut segments)?;
+
+    // size contains the two bytes of `size` itself
+    if let Some(s) = segments
+        .iter()
+        .find(|s| s.payload.len() + 2 > u16::MAX as usize)
+    {
+        return Err(format!(
+            "JPEG segment 0x{:02x} is too big: {} bytes",
+            s.marker_code,
+            s.payload.len()
+        )
+        .into());
+    }
+
+    writer.write_all(&[0xFF, MarkerCode::Soi.code()])?;
+    for s in segments.iter().chain(tail.iter()) {
+        writer.write_all(&[0xFF, s.marker_code])?;
+        if is_standalone_marker(s.marker_code) {
+            continue;
+        }
+        let size = (s.payload.len() + 2) as u16;
+        writer.write_all(&size.to_be_bytes())?;
+        writer.write_all(&s.payload)?;
+    }
+
+    if tail.is_none() {
+        writer.write_all(&[0xFF, MarkerCode::Eoi.code()])?;
+    }
+    std::io::copy(&mut reader, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn read_marker_code<R: Read>(reader: &mut R) -> crate::Result<u8> {
+    let mut b = [0u8; 1];
+    reader.read_exact(&mut b)?;
+    if b[0] != 0xFF {
+        return Err(format!("invalid JPEG marker: 0x{:02x}", b[0]).into());
+    }
+    // Skip fill bytes
+    while b[0] == 0xFF {
+        reader.read_exact(&mut b)?;
+    }
+    Ok(b[0])
+}
+
 struct Segment<'a> {
     marker_code: u8,
     payload: &'a [u8],
@@ -77,7 +266,7 @@ fn find_exif_segment(input: &[u8]) -> IResult<&[u8], Option<Segment<'_>>> {
     let mut remain = input;
 
     let (remain, segment) = loop {
-        let (rem, (_, code)) = tuple((streaming::tag([0xFF]), number::streaming::u8))(remain)?;
+        let (rem, code) = parse_marker_code(remain)?;
         let (rem, segment) = parse_segment(code, rem)?;
         // Sanity check
         assert!(rem.len() < remain.len());
@@ -104,6 +293,125 @@ fn find_exif_segment(input: &[u8]) -> IResult<&[u8], Option<Segment<'_>>> {
     }
 }
 
+pub(crate) const XMP_IDENT: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+pub(crate) const XMP_EXT_IDENT: &[u8] = b"http://ns.adobe.com/xmp/extension/\0";
+
+type XmpResult<'a> = IResult<&'a [u8], Option<XmpPackets>>;
+
+/// Extract the XMP packet from APP1 segments, and reassemble the Extended XMP
+/// (if any) whose GUID is referred by the main packet.
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_xmp_data(input: &[u8]) -> XmpResult<'_> {
+    let mut packet = None;
+    // (GUID, full length, offset, data)
+    let mut chunks = Vec::new();
+    let (remain, _) = travel_until(input, |s| {
+        if s.marker_code == MarkerCode::APP1.code() {
+            if let Some(data) = s.payload.strip_prefix(XMP_IDENT) {
+                packet.get_or_insert(data);
+            } else if let Some(data) = s.payload.strip_prefix(XMP_EXT_IDENT) {
+                if data.len() >= 40 {
+                    let full_len = u32::from_be_bytes(data[32..36].try_into().unwrap());
+                    let offset = u32::from_be_bytes(data[36..40].try_into().unwrap());
+                    chunks.push((&data[..32], full_len, offset, &data[40..]));
+                }
+            }
+        }
+        s.marker_code == MarkerCode::Sos.code()
+    })?;
+
+    let Some(packet) = packet else {
+        return Ok((remain, None));
+    };
+
+    let guid = chunks
+        .iter()
+        .map(|c| c.0)
+        .find(|guid| packet.windows(guid.len()).any(|x| x == *guid));
+    let extended = guid.and_then(|guid| {
+        let chunks = chunks.iter().filter(|c| c.0 == guid).collect::<Vec<_>>();
+        let full_len = chunks[0].1 as usize;
+        let total: usize = chunks.iter().map(|c| c.3.len()).sum();
+        if total != full_len || chunks.iter().any(|c| c.1 as usize != full_len) {
+            tracing::warn!(full_len, total, "Ignored: broken Extended XMP");
+            return None;
+        }
+
+        let mut data = vec![0u8; full_len];
+        for (_, _, offset, chunk) in chunks {
+            let start = *offset as usize;
+            let Some(dst) = data.get_mut(start..start + chunk.len()) else {
+                tracing::warn!(offset, "Ignored: broken Extended XMP");
+                return None;
+            };
+            dst.copy_from_slice(chunk);
+        }
+        Some(data)
+    });
+
+    Ok((remain, Some((packet.to_vec(), extended))))
+}
+
+pub(crate) const PHOTOSHOP_IDENT: &[u8] = b"Photoshop 3.0\0";
+
+/// Extract Photoshop image resource blocks from APP13 segments, the resource
+/// data may be split into several segments.
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_photoshop_irb(input: &[u8]) -> IResult<&[u8], Option<Vec<u8>>> {
+    let mut irb: Option<Vec<u8>> = None;
+    let (remain, _) = travel_until(input, |s| {
+        if s.marker_code == MarkerCode::APP13.code() {
+            if let Some(data) = s.payload.strip_prefix(PHOTOSHOP_IDENT) {
+                irb.get_or_insert_with(Vec::new).extend_from_slice(data);
+            }
+        }
+        s.marker_code == MarkerCode::Sos.code()
+    })?;
+
+    Ok((remain, irb))
+}
+
+pub(crate) const ICC_IDENT: &[u8] = b"ICC_PROFILE\0";
+
+/// Extract the ICC profile from APP2 segments, a large profile is split into
+/// several chunks, which are reassembled by their sequence numbers.
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_icc_data(input: &[u8]) -> IResult<&[u8], Option<Vec<u8>>> {
+    // (sequence number, chunks count, chunk data)
+    let mut chunks: Vec<(u8, u8, &[u8])> = Vec::new();
+    let (remain, _) = travel_until(input, |s| {
+        if s.marker_code == MarkerCode::APP2.code() {
+            if let Some(data) = s.payload.strip_prefix(ICC_IDENT) {
+                if data.len() >= 2 {
+                    chunks.push((data[0], data[1], &data[2..]));
+                }
+            }
+        }
+        s.marker_code == MarkerCode::Sos.code()
+    })?;
+
+    if chunks.is_empty() {
+        return Ok((remain, None));
+    }
+
+    chunks.sort_by_key(|c| c.0);
+    let count = chunks[0].1;
+    let valid = chunks.len() == count as usize
+        && chunks
+            .iter()
+            .enumerate()
+            .all(|(i, c)| c.0 as usize == i + 1 && c.1 == count);
+    if !valid {
+        tracing::warn!(count, chunks = chunks.len(), "Ignored: broken ICC profile");
+        return Ok((remain, None));
+    }
+
+    Ok((
+        remain,
+        Some(chunks.iter().flat_map(|c| c.2).copied().collect()),
+    ))
+}
+
 pub fn check_jpeg(input: &[u8]) -> crate::Result<()> {
     // check soi marker [0xff, 0xd8]
     let (_, (_, code)) = tuple((nom::bytes::complete::tag([0xFF]), number::complete::u8))(input)?;
@@ -126,7 +434,7 @@ where
     let mut remain = input;
 
     loop {
-        let (rem, (_, code)) = tuple((streaming::tag([0xFF]), number::streaming::u8))(remain)?;
+        let (rem, code) = parse_marker_code(remain)?;
         let (rem, segment) = parse_segment(code, rem)?;
         // Sanity check
         assert!(rem.len() < remain.len());
@@ -155,13 +463,17 @@ pub(crate) fn check_jpeg_exif<'a>(input: &'a [u8]) -> IResult<&'a [u8], bool> {
     }
 
     loop {
-        let (rem, (_, code)) = tuple((streaming::tag([0xFF]), number::streaming::u8))(loop_remain)?;
+        let (rem, code) = parse_marker_code(loop_remain)?;
         tracing::debug!("Got segment: 0x{:02x}", code);
 
         // Stop searching at SOS
         if code == MarkerCode::Sos.code() {
             return Ok((rem, false));
         }
+        if is_standalone_marker(code) {
+            loop_remain = rem;
+            continue;
+        }
 
         let (rem, size) = number::streaming::be_u16(rem)?;
         if code == MarkerCode::APP1.code() {
@@ -183,11 +495,19 @@ pub(crate) fn check_jpeg_exif<'a>(input: &'a [u8]) -> IResult<&'a [u8], bool> {
     }
 }
 
+/// Parse a marker, i.e. 0xFF followed by the marker code, fill bytes (0xFF)
+/// before the marker code are skipped.
+fn parse_marker_code(input: &[u8]) -> IResult<&[u8], u8> {
+    let (remain, _) = streaming::tag([0xFF])(input)?;
+    let (remain, _) = streaming::take_while(|b| b == 0xFF)(remain)?;
+    number::streaming::u8(remain)
+}
+
 fn parse_segment(marker_code: u8, input: &[u8]) -> IResult<&[u8], Segment<'_>> {
     let remain = input;
 
-    // SOI has no payload
-    if marker_code == MarkerCode::Soi.code() {
+    // SOI & standalone markers have no payload
+    if marker_code == MarkerCode::Soi.code() || is_standalone_marker(marker_code) {
         Ok((
             remain,
             Segment {
@@ -221,13 +541,18 @@ fn read_image_data<T: Read + Seek>(mut reader: T) -> crate::Result<Vec<u8>> {
     let mut header = [0u8; 2];
     loop {
         reader.read_exact(&mut header)?;
-        let (tag, marker) = (header[0], header[1]);
+        let (tag, mut marker) = (header[0], header[1]);
         if tag != 0xFF {
             return Err("".into());
         }
+        // Skip fill bytes
+        while marker == 0xFF {
+            reader.read_exact(&mut header[..1])?;
+            marker = header[0];
+        }
 
-        if marker == MarkerCode::Soi.code() {
-            // SOI has no body
+        if marker == MarkerCode::Soi.code() || is_standalone_marker(marker) {
+            // SOI & standalone markers have no body
             continue;
         }
         if marker == MarkerCode::Eoi.code() {
@@ -269,9 +594,18 @@ enum MarkerCode {
     // Start of Image
     Soi = 0xD8,
 
+    // APP0 marker
+    APP0 = 0xE0,
+
     // APP1 marker
     APP1 = 0xE1,
 
+    // APP2 marker
+    APP2 = 0xE2,
+
+    // APP13 marker
+    APP13 = 0xED,
+
     // Start of Scan
     Sos = 0xDA,
 
@@ -285,6 +619,11 @@ impl MarkerCode {
     }
 }
 
+/// Standalone markers (TEM & RST0..RST7) have no length & payload.
+fn is_standalone_marker(code: u8) -> bool {
+    matches!(code, 0x01 | 0xD0..=0xD7)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,7 +663,7 @@ mod tests {
                 .collect::<Vec<_>>(),
             [
                 ("DateTimeOriginal", "2023-07-09T20:36:33+08:00"),
-                ("CreateDate", "2023-07-09T20:36:33+08:00"),
+                ("CreateDate", "2023-07-09T20:36:33"),
                 ("ModifyDate", "2023-07-09T20:36:33+08:00")
             ]
             .into_iter()
@@ -392,6 +731,86 @@ mod tests {
         );
     }
 
+    #[test_case("exif.jpg")]
+    #[test_case("no-exif.jpg")]
+    fn rewrite_exif(path: &str) {
+        use crate::{ExifWriter, IfdGroup};
+        use std::io::Cursor;
+
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, Make, "nom-exif".into());
+        let exif = writer.write().unwrap();
+
+        let buf = read_sample(path).unwrap();
+        let mut output = Vec::new();
+        rewrite_jpeg_exif(Cursor::new(&buf), &mut output, Some(&exif)).unwrap();
+
+        let (_, data) = extract_exif_data(&output).unwrap();
+        assert_eq!(data.unwrap(), exif);
+        assert_eq!(
+            read_image_data(Cursor::new(&output)).unwrap(),
+            read_image_data(Cursor::new(&buf)).unwrap()
+        );
+
+        // The Exif segment is inserted after JFIF APP0 segment
+        if buf[3] == MarkerCode::APP0.code() {
+            let size = u16::from_be_bytes([buf[4], buf[5]]) as usize;
+            assert_eq!(output[..size + 4], buf[..size + 4]);
+            assert_eq!(output[size + 5], MarkerCode::APP1.code());
+        }
+
+        // Remove the Exif segment
+        let mut removed = Vec::new();
+        rewrite_jpeg_exif(Cursor::new(&output), &mut removed, None).unwrap();
+        let (_, ok) = check_jpeg_exif(&removed).unwrap();
+        assert!(!ok);
+        assert_eq!(
+            read_image_data(Cursor::new(&removed)).unwrap(),
+            read_image_data(Cursor::new(&buf)).unwrap()
+        );
+    }
+
+    #[test_case(&[0xFF]; "fill byte")]
+    #[test_case(&[0xFF, 0x01]; "TEM")]
+    #[test_case(&[0xFF, 0xD3, 0xFF]; "RST3")]
+    fn rewrite_exif_standalone_markers(extra: &[u8]) {
+        use crate::{ExifWriter, IfdGroup};
+        use std::io::Cursor;
+
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, Make, "nom-exif".into());
+        let exif = writer.write().unwrap();
+
+        // Insert the marker / fill bytes between SOI & the Exif APP1 segment
+        let src = read_sample("exif.jpg").unwrap();
+        let buf = [&src[..2], extra, &src[2..]].concat();
+        let (_, data) = extract_exif_data(&buf).unwrap();
+        assert!(data.is_some());
+        assert!(check_jpeg_exif(&buf).unwrap().1);
+
+        let mut output = Vec::new();
+        rewrite_jpeg_exif(Cursor::new(&buf), &mut output, Some(&exif)).unwrap();
+        let (_, data) = extract_exif_data(&output).unwrap();
+        assert_eq!(data.unwrap(), exif);
+        assert_eq!(
+            read_image_data(Cursor::new(&output)).unwrap(),
+            read_image_data(Cursor::new(&src)).unwrap()
+        );
+        if extra.len() > 1 {
+            // Standalone markers are kept
+            assert_eq!(output[2..4], extra[..2]);
+        }
+    }
+
+    #[test]
+    fn rewrite_exif_too_big() {
+        let buf = read_sample("exif.jpg").unwrap();
+        let exif = vec![0u8; u16::MAX as usize];
+        let mut output = Vec::new();
+        rewrite_jpeg_exif(&buf[..], &mut output, Some(&exif)).unwrap_err();
+        assert!(output.is_empty());
+    }
+
     #[allow(deprecated)]
     #[test]
     fn broken_jpg() {
@@ -0,0 +1,214 @@
+use std::{borrow::Cow, io::Read};
+
+use brotli_decompressor::Decompressor;
+
+use crate::{
+    bbox::BoxHeader, error::ParsingError, heif::exif_item_tiff_data, parser::MAX_ALLOC_SIZE,
+};
+
+/// Signature of a bare JPEG XL codestream.
+const CODESTREAM_SIGNATURE: &[u8] = &[0xFF, 0x0A];
+
+/// Signature of the JPEG XL ISOBMFF-like container, which is a `JXL ` box.
+const CONTAINER_SIGNATURE: &[u8] = &[
+    0, 0, 0, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A,
+];
+
+pub(crate) fn check_jxl(input: &[u8]) -> crate::Result<()> {
+    if input.starts_with(CONTAINER_SIGNATURE) || input.starts_with(CODESTREAM_SIGNATURE) {
+        Ok(())
+    } else {
+        Err("invalid JPEG XL file; signature not found".into())
+    }
+}
+
+/// Walks through the top level boxes until a box of `box_type` is found, then
+/// returns its body. The box may be stored as is, or compressed in a `brob`
+/// box (the first 4 bytes of which is the original box type), in which case
+/// the decompressed body is returned.
+///
+/// `input` starts with either the container signature, or a box (after a
+/// `ClearAndSkip`). A bare codestream has no metadata boxes.
+fn find_box<'a>(input: &'a [u8], box_type: &str) -> Result<Option<Cow<'a, [u8]>>, ParsingError> {
+    if input.starts_with(CODESTREAM_SIGNATURE) {
+        return Ok(None);
+    }
+
+    let mut remain = input;
+    loop {
+        // A box with size 0 extends to the end of the file, it can only be
+        // the last box (the codestream).
+        if remain.starts_with(&[0; 4]) {
+            return Ok(None);
+        }
+
+        let (rem, header) = BoxHeader::parse(remain)?;
+        tracing::debug!(?header.box_type, ?header.box_size, "Got");
+        let size: usize = header.body_size().try_into().expect("must fit");
+
+        if header.box_type == box_type || header.box_type == "brob" {
+            if rem.len() < size {
+                return Err(ParsingError::Need(size - rem.len()));
+            }
+            let body = &rem[..size];
+            if header.box_type == box_type {
+                return Ok(Some(Cow::Borrowed(body)));
+            } else if let Some(data) = body.strip_prefix(box_type.as_bytes()) {
+                return brotli_decompress(data, MAX_ALLOC_SIZE).map(|x| Some(Cow::Owned(x)));
+            }
+        } else if rem.len() < size {
+            // skip unused box data
+            return Err(ParsingError::ClearAndSkip(input.len() - rem.len() + size));
+        }
+
+        remain = &rem[size..];
+    }
+}
+
+/// Decompress a `brob` box payload, fails if the decompressed data is larger
+/// than `limit`, rather than returning truncated data.
+fn brotli_decompress(data: &[u8], limit: usize) -> Result<Vec<u8>, ParsingError> {
+    let mut out = Vec::new();
+    Decompressor::new(data, 4096)
+        .take(limit as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| ParsingError::Failed(format!("decompress brob box failed: {e}")))?;
+    if out.len() > limit {
+        return Err(ParsingError::Failed(format!(
+            "decompressed brob box is too big (> {limit} bytes)"
+        )));
+    }
+    Ok(out)
+}
+
+/// Extract Exif TIFF data from the `Exif` box (or its `brob` variant). Like
+/// the HEIF Exif item, the box body starts with a 4-bytes offset of the TIFF
+/// header.
+pub(crate) fn extract_exif_data(input: &[u8]) -> Result<Option<Vec<u8>>, ParsingError> {
+    let Some(data) = find_box(input, "Exif")? else {
+        return Ok(None);
+    };
+    exif_item_tiff_data(&data)
+        .map(|x| Some(x.to_vec()))
+        .ok_or_else(|| ParsingError::Failed("invalid JPEG XL Exif box".into()))
+}
+
+/// Extract the XMP packet from the `xml ` box (or its `brob` variant).
+pub(crate) fn extract_xmp_data(input: &[u8]) -> Result<Option<Vec<u8>>, ParsingError> {
+    Ok(find_box(input, "xml ")?.map(Cow::into_owned))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use super::*;
+    use crate::testkit::{bmff_box, open_sample, parse_sample};
+    use crate::{ExifIter, ExifTag, ExifWriter, IfdGroup, MediaParser, MediaSource, Xmp};
+    use test_case::test_case;
+
+    fn brob(box_type: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 9, 22);
+        writer.write_all(body).unwrap();
+        drop(writer);
+        bmff_box(b"brob", &[&box_type[..], &compressed].concat())
+    }
+
+    fn container(boxes: &[Vec<u8>]) -> Vec<u8> {
+        let ftyp = bmff_box(b"ftyp", b"jxl \0\0\0\0jxl ");
+        // The codestream box (size 0: extends to the end of file), which is
+        // large enough for the header parsing buffer of `MediaSource`
+        let jxlc = [&[0, 0, 0, 0][..], b"jxlc", &[0xFF, 0x0A], &[0; 256]].concat();
+        [CONTAINER_SIGNATURE.to_vec(), ftyp, boxes.concat(), jxlc].concat()
+    }
+
+    fn exif_body() -> (Vec<u8>, Vec<u8>) {
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, ExifTag::Make, "abc".into());
+        let tiff = writer.write().unwrap();
+        let body = [&0u32.to_be_bytes()[..], &tiff].concat();
+        (body, tiff)
+    }
+
+    #[test]
+    fn jxl_exif_xmp() {
+        let mut parser = MediaParser::new();
+
+        let ms = MediaSource::file(open_sample("exif.jxl").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(
+            exif.get(ExifTag::Model).unwrap().to_string(),
+            "vivo X90 Pro+"
+        );
+
+        let ms = MediaSource::unseekable(open_sample("exif.jxl").unwrap()).unwrap();
+        let xmp: Xmp = parser.parse(ms).unwrap();
+        assert_eq!(
+            xmp.get(Xmp::NS_XMP, "CreatorTool").unwrap().as_str(),
+            Some("nom-exif test")
+        );
+    }
+
+    #[test_case(false, 10, true)]
+    #[test_case(true, 10, true)]
+    #[test_case(true, 1024 * 1024 + 1, true)]
+    #[test_case(true, 1024 * 1024 + 1, false)]
+    fn jxl_exif(compressed: bool, skip: usize, seekable: bool) {
+        let (body, tiff) = exif_body();
+        let exif_box = if compressed {
+            brob(b"Exif", &body)
+        } else {
+            bmff_box(b"Exif", &body)
+        };
+        let data = container(&[
+            bmff_box(b"jxll", &[5]),
+            brob(b"jumb", b"not wanted"),
+            bmff_box(b"free", &vec![0; skip]),
+            exif_box,
+        ]);
+
+        assert_eq!(extract_exif_data(&data).unwrap().unwrap(), tiff);
+
+        let mut parser = MediaParser::new();
+        let mut iter: ExifIter = if seekable {
+            parser.parse(MediaSource::seekable(Cursor::new(data)).unwrap())
+        } else {
+            parser.parse(MediaSource::unseekable(Cursor::new(data)).unwrap())
+        }
+        .unwrap();
+        let make = iter.next().unwrap();
+        assert_eq!(make.get_value().unwrap().to_string(), "abc");
+    }
+
+    #[test]
+    fn jxl_xmp_brob() {
+        let packet = b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>";
+        let data = container(&[brob(b"xml ", packet)]);
+        assert_eq!(extract_xmp_data(&data).unwrap().unwrap(), packet);
+    }
+
+    #[test]
+    fn brob_size_limit() {
+        let body = vec![b'a'; 1000];
+        let data = &brob(b"xml ", &body)[12..];
+        assert_eq!(brotli_decompress(data, 1000).unwrap(), body);
+        assert!(matches!(
+            brotli_decompress(data, 999),
+            Err(ParsingError::Failed(_))
+        ));
+    }
+
+    #[test]
+    fn jxl_no_data() {
+        let data = container(&[]);
+        assert!(extract_exif_data(&data).unwrap().is_none());
+        assert!(extract_xmp_data(&data).unwrap().is_none());
+
+        let codestream = [&[0xFF, 0x0A][..], &[0; 256]].concat();
+        check_jxl(&codestream).unwrap();
+        assert!(extract_exif_data(&codestream).unwrap().is_none());
+        parse_sample::<ExifIter>(codestream).unwrap_err();
+    }
+}
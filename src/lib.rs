@@ -6,8 +6,14 @@
 //! - Image
 //!   - *.heic, *.heif, etc.
 //!   - *.jpg, *.jpeg
-//!   - *.tiff, *.tif
+//!   - *.tiff, *.tif (including BigTIFF)
 //!   - *.RAF (Fujifilm RAW)
+//!   - *.CR3 (Canon RAW)
+//!   - TIFF based RAW: *.dng, *.nef, *.arw, *.cr2, *.orf, *.rw2, *.pef, *.srw
+//!   - *.png
+//!   - *.webp
+//!   - *.avif
+//!   - *.jxl
 //! - Video/Audio
 //!   - ISO base media file format (ISOBMFF): *.mp4, *.mov, *.3gp, etc.
 //!   - Matroska based file format: *.webm, *.mkv, *.mka, etc.
@@ -304,13 +310,20 @@
 //! ...
 //! ```
 
+pub use file::{detect, MediaClass, MediaKind};
 pub use parser::{MediaParser, MediaSource};
 pub use video::{TrackInfo, TrackInfoTag};
 
 #[cfg(feature = "async")]
 pub use parser_async::{AsyncMediaParser, AsyncMediaSource};
 
-pub use exif::{Exif, ExifIter, ExifTag, GPSInfo, LatLng, ParsedExifEntry};
+pub use exif::{
+    CaptureSettings, ColorSpace, Contrast, CustomRendered, Exif, ExifDateTime, ExifEnum, ExifIter,
+    ExifTag, ExifWriter, ExposureMode, ExposureProgram, Flash, FlashMode, FlashReturn, GPSInfo,
+    GainControl, IfdGroup, IfdId, LatLng, LensSpec, LightSource, MeteringMode, OffsetSource,
+    Orientation, ParsedExifEntry, PrintValue, ResolutionUnit, Saturation, SceneCaptureType,
+    SensingMethod, Sharpness, WhiteBalance,
+};
 pub use values::{EntryValue, IRational, URational};
 
 #[allow(deprecated)]
@@ -323,6 +336,16 @@ pub use exif::parse_exif_async;
 pub use heif::parse_heif_exif;
 #[allow(deprecated)]
 pub use jpeg::parse_jpeg_exif;
+pub use jpeg::rewrite_jpeg_exif;
+
+pub use icc::{IccHeader, IccProfile};
+pub use iptc::{Iptc, IptcTag};
+pub use metadata::{Dimensions, MediaMetadata, MetadataSource, Sourced};
+pub use png::{PngInfo, PngText};
+pub use redact::{redact, RedactPolicy};
+pub use shift::shift_timestamps;
+pub use webp::WebpInfo;
+pub use xmp::{Xmp, XmpName, XmpValue};
 
 pub use error::Error;
 pub type Result<T> = std::result::Result<T, Error>;
@@ -336,24 +359,34 @@ pub use mov::{parse_metadata, parse_mov_metadata};
 
 mod bbox;
 mod buffer;
+mod cr3;
 mod ebml;
 mod error;
 mod exif;
 mod file;
 mod heif;
+mod icc;
+mod iptc;
 mod jpeg;
+mod jxl;
 mod loader;
+mod metadata;
 mod mov;
 mod parser;
 #[cfg(feature = "async")]
 mod parser_async;
 mod partial_vec;
+mod png;
 mod raf;
+mod redact;
+mod shift;
 mod skip;
 mod slice;
 mod utils;
 mod values;
 mod video;
+mod webp;
+mod xmp;
 
 #[cfg(test)]
 mod testkit;
@@ -0,0 +1,475 @@
+use std::time::Duration;
+
+use chrono::FixedOffset;
+
+use crate::{
+    error::ParsingError,
+    file::{Mime, MimeImage},
+    png, webp, EntryValue, Exif, ExifDateTime, ExifTag, GPSInfo, OffsetSource, Orientation,
+    TrackInfo, TrackInfoTag,
+};
+
+/// Represents the commonly used metadata of an image or a video, normalized
+/// from [`Exif`] (images) or [`TrackInfo`] (videos), so that callers don't
+/// need to handle the two kinds of media separately.
+///
+/// Each field records the tag it comes from, see [`Sourced`]. Images without
+/// Exif data have only the MIME type, and the dimensions of the PNG & WebP
+/// image headers.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///
+///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+///     let meta: MediaMetadata = parser.parse(ms)?;
+///     assert_eq!(meta.mime, "image/jpeg");
+///     assert_eq!(meta.model.as_ref().unwrap().value, "vivo X90 Pro+");
+///     let time = meta.capture_time.unwrap();
+///     assert_eq!(time.source, MetadataSource::Exif(ExifTag::DateTimeOriginal));
+///     assert_eq!(
+///         time.value.to_datetime().unwrap().to_rfc3339(),
+///         "2023-07-09T20:36:33.616+08:00"
+///     );
+///
+///     let ms = MediaSource::file_path("./testdata/meta.mp4")?;
+///     let meta: MediaMetadata = parser.parse(ms)?;
+///     assert_eq!(meta.mime, "video/mp4");
+///     let dim = meta.dimensions.unwrap();
+///     assert_eq!(dim.source, MetadataSource::Track(TrackInfoTag::ImageWidth));
+///     assert_eq!((dim.value.width, dim.value.height), (1080, 1920));
+///     assert_eq!(dim.value.rotation, 90);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaMetadata {
+    /// MIME type detected from the file content, e.g. "image/jpeg".
+    pub mime: &'static str,
+
+    /// Time of the shot, see [`Exif::capture_time`]. For videos, it's the
+    /// creation date of the track.
+    pub capture_time: Option<Sourced<ExifDateTime>>,
+
+    pub make: Option<Sourced<String>>,
+    pub model: Option<Sourced<String>>,
+    pub software: Option<Sourced<String>>,
+
+    /// Display dimensions. The source is the width tag, the height comes
+    /// from the corresponding height tag. For PNG & WebP images without
+    /// dimension tags, it comes from the image header.
+    pub dimensions: Option<Sourced<Dimensions>>,
+
+    /// Only available for videos.
+    pub duration: Option<Sourced<Duration>>,
+
+    pub gps: Option<Sourced<GPSInfo>>,
+}
+
+/// A [`MediaMetadata`] field value, along with the tag it comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sourced<T> {
+    pub value: T,
+    pub source: MetadataSource,
+}
+
+/// The tag a [`MediaMetadata`] field comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MetadataSource {
+    Exif(ExifTag),
+    Track(TrackInfoTag),
+    /// The image header of the file format, i.e. the `IHDR` chunk of PNG,
+    /// or the `VP8X` chunk (`VP8 `/`VP8L` bitstream header) of WebP.
+    ImageHeader,
+}
+
+/// Dimensions of an image or a video, with the rotation applied, i.e. width &
+/// height are swapped when the media is rotated by 90 or 270 degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dimensions {
+    pub width: u32,
+    pub height: u32,
+
+    /// Clockwise rotation in degrees (0, 90, 180 or 270) which has been
+    /// applied, from the `Orientation` tag of images (mirroring is ignored),
+    /// or the `Rotation` of videos.
+    pub rotation: u32,
+}
+
+impl Dimensions {
+    fn rotated(width: u32, height: u32, rotation: u32) -> Self {
+        let (width, height) = if rotation % 180 == 90 {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        Self {
+            width,
+            height,
+            rotation,
+        }
+    }
+}
+
+impl<T> Sourced<T> {
+    fn exif(value: T, tag: ExifTag) -> Self {
+        Self {
+            value,
+            source: MetadataSource::Exif(tag),
+        }
+    }
+
+    fn track(value: T, tag: TrackInfoTag) -> Self {
+        Self {
+            value,
+            source: MetadataSource::Track(tag),
+        }
+    }
+}
+
+impl MediaMetadata {
+    /// `header_size` is the (width, height) from the image header, which is
+    /// used if `exif` has no dimension tags.
+    pub(crate) fn from_image(
+        mime: Mime,
+        exif: Option<&Exif>,
+        header_size: Option<(u32, u32)>,
+    ) -> Self {
+        let mut meta = match exif {
+            Some(exif) => Self::from_exif(mime, exif),
+            None => Self {
+                mime: mime.kind().mime_type(),
+                capture_time: None,
+                make: None,
+                model: None,
+                software: None,
+                dimensions: None,
+                duration: None,
+                gps: None,
+            },
+        };
+        if meta.dimensions.is_none() {
+            meta.dimensions = header_size.map(|(width, height)| Sourced {
+                value: Dimensions::rotated(width, height, exif.map_or(0, exif_rotation)),
+                source: MetadataSource::ImageHeader,
+            });
+        }
+        meta
+    }
+
+    fn from_exif(mime: Mime, exif: &Exif) -> Self {
+        let capture_time = exif.capture_time().map(|t| {
+            let tag = if exif.date_time(ExifTag::DateTimeOriginal).is_some() {
+                ExifTag::DateTimeOriginal
+            } else {
+                ExifTag::CreateDate
+            };
+            Sourced::exif(t, tag)
+        });
+        let text = |tag| {
+            exif.get(tag)
+                .and_then(text_value)
+                .map(|x| Sourced::exif(x, tag))
+        };
+
+        let rotation = exif_rotation(exif);
+        let dimensions = [
+            (ExifTag::ExifImageWidth, ExifTag::ExifImageHeight),
+            (ExifTag::ImageWidth, ExifTag::ImageHeight),
+        ]
+        .into_iter()
+        .find_map(|(w, h)| {
+            let width = exif.get(w).and_then(dimension_value)?;
+            let height = exif.get(h).and_then(dimension_value)?;
+            Some(Sourced::exif(
+                Dimensions::rotated(width, height, rotation),
+                w,
+            ))
+        });
+
+        let gps = exif
+            .get_gps_info()
+            .ok()
+            .flatten()
+            .map(|x| Sourced::exif(x, ExifTag::GPSLatitude));
+
+        Self {
+            mime: mime.kind().mime_type(),
+            capture_time,
+            make: text(ExifTag::Make),
+            model: text(ExifTag::Model),
+            software: text(ExifTag::Software),
+            dimensions,
+            duration: None,
+            gps,
+        }
+    }
+
+    pub(crate) fn from_track(mime: Mime, info: &TrackInfo) -> Self {
+        let capture_time = match info.get(TrackInfoTag::CreateDate) {
+            // The creation time of the containers is in UTC
+            Some(EntryValue::Time(t)) => Some(ExifDateTime {
+                local: t.naive_utc(),
+                offset: FixedOffset::east_opt(0),
+                offset_source: OffsetSource::Utc,
+            }),
+            Some(EntryValue::NaiveDateTime(t)) => Some(ExifDateTime {
+                local: *t,
+                offset: None,
+                offset_source: OffsetSource::Unknown,
+            }),
+            _ => None,
+        }
+        .map(|x| Sourced::track(x, TrackInfoTag::CreateDate));
+        let text = |tag| {
+            info.get(tag)
+                .and_then(text_value)
+                .map(|x| Sourced::track(x, tag))
+        };
+
+        let dimensions = info
+            .get(TrackInfoTag::ImageWidth)
+            .and_then(dimension_value)
+            .zip(
+                info.get(TrackInfoTag::ImageHeight)
+                    .and_then(dimension_value),
+            )
+            .map(|(width, height)| {
+                // Rounded to a multiple of 90 degrees
+                let rotation = info
+                    .get(TrackInfoTag::Rotation)
+                    .and_then(|x| x.as_u32())
+                    .map_or(0, |x| (x % 360 + 45) / 90 % 4 * 90);
+                Sourced::track(
+                    Dimensions::rotated(width, height, rotation),
+                    TrackInfoTag::ImageWidth,
+                )
+            });
+
+        let duration = info
+            .get(TrackInfoTag::DurationMs)
+            .and_then(|x| x.as_u64())
+            .map(|x| Sourced::track(Duration::from_millis(x), TrackInfoTag::DurationMs));
+
+        let gps = info
+            .get_gps_info()
+            .map(|x| Sourced::track(x.clone(), TrackInfoTag::GpsIso6709));
+
+        Self {
+            mime: mime.kind().mime_type(),
+            capture_time,
+            make: text(TrackInfoTag::Make),
+            model: text(TrackInfoTag::Model),
+            software: text(TrackInfoTag::Software),
+            dimensions,
+            duration,
+            gps,
+        }
+    }
+}
+
+/// Extract the (width, height) from the image header of PNG & WebP files,
+/// `None` for other file types.
+pub(crate) fn extract_image_size(
+    mime: Mime,
+    input: &[u8],
+) -> Result<Option<(u32, u32)>, ParsingError> {
+    match mime {
+        Mime::Image(MimeImage::Png) => png::extract_image_size(input),
+        Mime::Image(MimeImage::Webp) => webp::extract_canvas_size(input).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Clockwise rotation in degrees of the `Orientation` tag, mirroring is
+/// ignored.
+fn exif_rotation(exif: &Exif) -> u32 {
+    match exif.get_enum::<Orientation>().ok().flatten() {
+        Some(Orientation::Rotate90 | Orientation::MirrorHorizontalRotate90) => 90,
+        Some(Orientation::Rotate180 | Orientation::MirrorVertical) => 180,
+        Some(Orientation::Rotate270 | Orientation::MirrorHorizontalRotate270) => 270,
+        _ => 0,
+    }
+}
+
+fn text_value(v: &EntryValue) -> Option<String> {
+    let s = v
+        .as_str()?
+        .trim_matches(|c: char| c == '\0' || c.is_whitespace());
+    (!s.is_empty()).then(|| s.to_owned())
+}
+
+fn dimension_value(v: &EntryValue) -> Option<u32> {
+    match v {
+        EntryValue::U16(x) => Some(*x as u32),
+        EntryValue::U32(x) => Some(*x),
+        _ => None,
+    }
+    .filter(|x| *x > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::Path};
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::testkit::{parse_sample, png_chunk, webp_chunk, webp_sample};
+    use crate::{exif::input_into_iter, ExifWriter, MediaParser, MediaSource};
+    use test_case::test_case;
+
+    #[test_case("exif.jpg", "image/jpeg", Some((3072, 4096)), true)]
+    #[test_case("exif.png", "image/png", Some((3072, 4096)), true)]
+    #[test_case("exif.webp", "image/webp", Some((3072, 4096)), true)]
+    #[test_case("no-exif.jpg", "image/jpeg", None, false)]
+    #[test_case("tif.tif", "image/tiff", Some((640, 480)), false)]
+    #[test_case("meta.mov", "video/quicktime", Some((720, 1280)), true)]
+    #[test_case("meta.mp4", "video/mp4", Some((1080, 1920)), true)]
+    #[test_case("mkv_640x360.mkv", "video/x-matroska", Some((640, 360)), false)]
+    fn parse_media_metadata(path: &str, mime: &str, dim: Option<(u32, u32)>, gps: bool) {
+        let mut parser = MediaParser::new();
+        let ms = MediaSource::file_path(Path::new("testdata").join(path)).unwrap();
+        let video = ms.has_track();
+        let meta: MediaMetadata = parser.parse(ms).unwrap();
+
+        assert_eq!(meta.mime, mime);
+        assert_eq!(
+            meta.dimensions.map(|x| (x.value.width, x.value.height)),
+            dim
+        );
+        assert_eq!(meta.gps.is_some(), gps);
+        assert_eq!(meta.duration.is_some(), video);
+    }
+
+    #[test]
+    fn image_without_exif() {
+        let mut ihdr = [0; 13];
+        ihdr[..4].copy_from_slice(&640_u32.to_be_bytes());
+        ihdr[4..8].copy_from_slice(&480_u32.to_be_bytes());
+        let png = [
+            &b"\x89PNG\r\n\x1a\n"[..],
+            &png_chunk(b"IHDR", &ihdr),
+            &png_chunk(b"IDAT", &[0; 16]),
+            &png_chunk(b"IEND", &[]),
+        ]
+        .concat();
+        // simple file format (lossless), 14 bits width - 1 & height - 1
+        let bits: u32 = 319 | (239 << 14);
+        let vp8l = [&[0x2F][..], &bits.to_le_bytes()].concat();
+        let webp = webp_sample(&[webp_chunk(b"VP8L", &vp8l)]);
+        // extended file format without the `EXIF` flag
+        let vp8x = webp_sample(&[
+            webp_chunk(b"VP8X", &[0x10, 0, 0, 0, 99, 0, 0, 49, 0, 0]),
+            webp_chunk(b"VP8L", &vp8l),
+        ]);
+
+        for (data, mime, size) in [
+            (png, "image/png", (640, 480)),
+            (webp, "image/webp", (320, 240)),
+            (vp8x, "image/webp", (100, 50)),
+        ] {
+            let meta: MediaMetadata = parse_sample(data).unwrap();
+            assert_eq!(meta.mime, mime);
+            assert_eq!(
+                meta.dimensions,
+                Some(Sourced {
+                    value: Dimensions::rotated(size.0, size.1, 0),
+                    source: MetadataSource::ImageHeader,
+                })
+            );
+            assert!(meta.capture_time.is_none());
+            assert!(meta.make.is_none());
+            assert!(meta.gps.is_none());
+        }
+    }
+
+    #[test]
+    fn exif_rotates_header_size() {
+        let mut writer = ExifWriter::new();
+        writer.set(
+            ExifTag::Orientation.group(),
+            ExifTag::Orientation,
+            6_u16.into(),
+        );
+        let exif: Exif = input_into_iter(writer.write().unwrap(), None)
+            .unwrap()
+            .into();
+        let mime = Mime::Image(MimeImage::Png);
+        let dim = MediaMetadata::from_image(mime, Some(&exif), Some((640, 480)))
+            .dimensions
+            .unwrap();
+        assert_eq!(dim.source, MetadataSource::ImageHeader);
+        assert_eq!((dim.value.width, dim.value.height), (480, 640));
+    }
+
+    #[test_case(90, 90)]
+    #[test_case(270, 270)]
+    #[test_case(450, 90)]
+    #[test_case(89, 90)]
+    #[test_case(359, 0)]
+    fn track_metadata(rotation: u32, expect: u32) {
+        let time = DateTime::parse_from_rfc3339("2024-02-02T16:09:57+08:00").unwrap();
+        let info: TrackInfo = BTreeMap::from([
+            (TrackInfoTag::CreateDate, EntryValue::Time(time)),
+            (TrackInfoTag::ImageWidth, 1920_u32.into()),
+            (TrackInfoTag::ImageHeight, 1080_u32.into()),
+            (TrackInfoTag::Rotation, rotation.into()),
+        ])
+        .into();
+        let meta = MediaMetadata::from_track(Mime::Video(crate::file::MimeVideo::Mp4), &info);
+
+        let t = meta.capture_time.unwrap().value;
+        assert_eq!(t.offset_source, OffsetSource::Utc);
+        assert_eq!(t.local.to_string(), "2024-02-02 08:09:57");
+        assert_eq!(t.to_datetime(), Some(time));
+
+        let d = meta.dimensions.unwrap().value;
+        assert_eq!(d.rotation, expect);
+        assert_eq!(d.width < d.height, expect % 180 == 90);
+    }
+
+    #[test_case(1, (4000, 3000, 0))]
+    #[test_case(3, (4000, 3000, 180))]
+    #[test_case(6, (3000, 4000, 90))]
+    #[test_case(7, (3000, 4000, 90))]
+    #[test_case(8, (3000, 4000, 270))]
+    fn exif_dimensions(orientation: u16, expect: (u32, u32, u32)) {
+        let mut writer = ExifWriter::new();
+        writer.set(
+            ExifTag::Orientation.group(),
+            ExifTag::Orientation,
+            orientation.into(),
+        );
+        writer.set(
+            ExifTag::ImageWidth.group(),
+            ExifTag::ImageWidth,
+            4000_u32.into(),
+        );
+        writer.set(
+            ExifTag::ImageHeight.group(),
+            ExifTag::ImageHeight,
+            3000_u16.into(),
+        );
+        writer.set(ExifTag::Make.group(), ExifTag::Make, "Maker  ".into());
+        let exif: Exif = input_into_iter(writer.write().unwrap(), None)
+            .unwrap()
+            .into();
+
+        let meta = MediaMetadata::from_exif(Mime::Image(MimeImage::Tiff), &exif);
+        let dim = meta.dimensions.unwrap();
+        assert_eq!(dim.source, MetadataSource::Exif(ExifTag::ImageWidth));
+        assert_eq!(
+            (dim.value.width, dim.value.height, dim.value.rotation),
+            expect
+        );
+        assert_eq!(meta.make.unwrap().value, "Maker");
+        assert!(meta.model.is_none());
+        assert!(meta.capture_time.is_none());
+    }
+}
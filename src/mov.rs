@@ -10,7 +10,8 @@ use nom::{bytes::streaming, IResult};
 #[allow(deprecated)]
 use crate::{
     bbox::{
-        find_box, parse_video_tkhd_in_moov, travel_header, IlstBox, KeysBox, MvhdBox, ParseBox,
+        find_box, parse_video_tkhd_in_moov, travel_header, BoxHeader, IlstBox, KeysBox, MvhdBox,
+        ParseBox,
     },
     error::ParsingError,
     loader::{BufLoader, Load},
@@ -102,15 +103,17 @@ pub fn parse_metadata<R: Read + Seek>(reader: R) -> crate::Result<Vec<(String, E
         }
     }
 
-    entries.extend(extras.into_iter().map(|(k, v)| match k {
-        TrackInfoTag::ImageWidth => ("width".to_string(), v),
-        TrackInfoTag::ImageHeight => ("height".to_string(), v),
-        TrackInfoTag::DurationMs => (
+    entries.extend(extras.into_iter().filter_map(|(k, v)| match k {
+        TrackInfoTag::ImageWidth => Some(("width".to_string(), v)),
+        TrackInfoTag::ImageHeight => Some(("height".to_string(), v)),
+        TrackInfoTag::DurationMs => Some((
             "duration".to_string(),
             // For compatibility with older versions, convert to u32
             EntryValue::U32(v.as_u64().unwrap() as u32),
-        ),
-        TrackInfoTag::CreateDate => (CREATIONDATE_KEY.to_string(), v),
+        )),
+        TrackInfoTag::CreateDate => Some((CREATIONDATE_KEY.to_string(), v)),
+        // Not available in the old API
+        TrackInfoTag::Rotation => None,
         _ => unreachable!(),
     }));
 
@@ -193,6 +196,7 @@ fn parse_mvhd_tkhd(moov_body: &[u8]) -> BTreeMap<TrackInfoTag, EntryValue> {
     if let Ok(Some(tkhd)) = parse_video_tkhd_in_moov(moov_body) {
         entries.insert(TrackInfoTag::ImageWidth, tkhd.width.into());
         entries.insert(TrackInfoTag::ImageHeight, tkhd.height.into());
+        entries.insert(TrackInfoTag::Rotation, tkhd.rotation.into());
     }
 
     entries
@@ -345,6 +349,43 @@ pub(crate) fn extract_moov_body_from_buf(input: &[u8]) -> Result<Range<usize>, P
     Ok(skipped..skipped + body.len())
 }
 
+pub(crate) const XMP_UUID: [u8; 16] = [
+    0xBE, 0x7A, 0xCF, 0xCB, 0x97, 0xA9, 0x42, 0xE8, 0x9C, 0x71, 0x99, 0x94, 0x91, 0xE3, 0xAF, 0xAC,
+];
+
+/// Search for the XMP packet in a top level XMP `uuid` box, or in
+/// `moov/udta/XMP_`.
+///
+/// Regarding error handling, please refer to [Error] for more information.
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_xmp_from_buf(input: &[u8]) -> Result<Option<&[u8]>, ParsingError> {
+    let mut remain = input;
+    loop {
+        let (rem, header) = BoxHeader::parse(remain)?;
+        tracing::debug!(?header.box_type, ?header.box_size, "Got");
+        let size: usize = header.body_size().try_into().expect("must fit");
+
+        if header.box_type == "uuid" || header.box_type == "moov" {
+            if rem.len() < size {
+                return Err(ParsingError::Need(size - rem.len()));
+            }
+            let body = &rem[..size];
+            if header.box_type == "uuid" {
+                if let Some(data) = body.strip_prefix(&XMP_UUID[..]) {
+                    return Ok(Some(data));
+                }
+            } else if let Ok((_, Some(bbox))) = find_box(body, "udta/XMP_") {
+                return Ok(Some(bbox.body_data()));
+            }
+        } else if rem.len() < size {
+            // skip unused box data
+            return Err(ParsingError::ClearAndSkip(input.len() - rem.len() + size));
+        }
+
+        remain = &rem[size..];
+    }
+}
+
 type EntriesResult<'a> = IResult<&'a [u8], Option<Vec<(String, EntryValue)>>>;
 
 fn parse_moov_body(input: &[u8]) -> EntriesResult {
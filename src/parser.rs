@@ -12,12 +12,19 @@ use std::{
 use crate::{
     buffer::Buffers,
     error::{ParsedError, ParsingError, ParsingErrorState},
-    exif::{parse_exif_iter, TiffHeader},
-    file::Mime,
+    exif::{find_exif_iter, parse_exif_iter, TiffHeader},
+    file::{parse_tiff_mime, MediaKind, Mime, MimeImage},
+    icc::extract_icc,
+    iptc::extract_iptc,
+    metadata::extract_image_size,
     partial_vec::PartialVec,
+    png::extract_png_info,
     skip::Skip,
     video::parse_track_info,
-    ExifIter, Seekable, TrackInfo, Unseekable,
+    webp::extract_webp_info,
+    xmp::extract_xmp,
+    Exif, ExifIter, IccProfile, Iptc, MediaMetadata, PngInfo, Seekable, TrackInfo, Unseekable,
+    WebpInfo, Xmp,
 };
 
 /// `MediaSource` represents a media data source that can be parsed by
@@ -61,6 +68,9 @@ impl<R, S: Skip<R>> Debug for MediaSource<R, S> {
 // Should be enough for parsing header
 const HEADER_PARSE_BUF_SIZE: usize = 128;
 
+// TIFF based RAW formats are identified by IFD0 entries, which need more data
+pub(crate) const TIFF_HEADER_PARSE_BUF_SIZE: usize = 4096;
+
 impl<R: Read, S: Skip<R>> MediaSource<R, S> {
     fn build(mut reader: R) -> crate::Result<Self> {
         // TODO: reuse MediaParser to parse header
@@ -69,7 +79,14 @@ impl<R: Read, S: Skip<R>> MediaSource<R, S> {
             .by_ref()
             .take(HEADER_PARSE_BUF_SIZE as u64)
             .read_to_end(&mut buf)?;
-        let mime: Mime = buf.as_slice().try_into()?;
+        let mut mime: Mime = buf.as_slice().try_into()?;
+        if mime == Mime::Image(MimeImage::Tiff) {
+            reader
+                .by_ref()
+                .take((TIFF_HEADER_PARSE_BUF_SIZE - buf.len()) as u64)
+                .read_to_end(&mut buf)?;
+            mime = Mime::Image(parse_tiff_mime(&buf));
+        }
         Ok(Self {
             reader,
             buf,
@@ -78,6 +95,11 @@ impl<R: Read, S: Skip<R>> MediaSource<R, S> {
         })
     }
 
+    /// Returns the detected file type.
+    pub fn kind(&self) -> MediaKind {
+        self.mime.kind()
+    }
+
     pub fn has_track(&self) -> bool {
         match self.mime {
             Mime::Image(_) => false,
@@ -322,6 +344,72 @@ impl<R: Read, S: Skip<R>> ParseOutput<R, S> for TrackInfo {
     }
 }
 
+impl<R: Read, S: Skip<R>> ParseOutput<R, S> for Xmp {
+    fn parse(parser: &mut MediaParser, mut ms: MediaSource<R, S>) -> crate::Result<Self> {
+        let out = parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, _| {
+            extract_xmp(ms.mime, data).map_err(|e| ParsingErrorState::new(e, None))
+        })?;
+        let (packet, extended) = out.ok_or("XMP not found")?;
+        Xmp::from_packets(packet, extended)
+    }
+}
+
+impl<R: Read, S: Skip<R>> ParseOutput<R, S> for Iptc {
+    fn parse(parser: &mut MediaParser, mut ms: MediaSource<R, S>) -> crate::Result<Self> {
+        let out = parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, _| {
+            extract_iptc(ms.mime, data).map_err(|e| ParsingErrorState::new(e, None))
+        })?;
+        Iptc::parse(&out.ok_or("IPTC not found")?)
+    }
+}
+
+impl<R: Read, S: Skip<R>> ParseOutput<R, S> for IccProfile {
+    fn parse(parser: &mut MediaParser, mut ms: MediaSource<R, S>) -> crate::Result<Self> {
+        let out = parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, _| {
+            extract_icc(ms.mime, data).map_err(|e| ParsingErrorState::new(e, None))
+        })?;
+        IccProfile::try_from(out.ok_or("ICC profile not found")?)
+    }
+}
+
+impl<R: Read, S: Skip<R>> ParseOutput<R, S> for PngInfo {
+    fn parse(parser: &mut MediaParser, mut ms: MediaSource<R, S>) -> crate::Result<Self> {
+        let mut info = PngInfo::default();
+        parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, _| {
+            extract_png_info(ms.mime, data, &mut info).map_err(|e| ParsingErrorState::new(e, None))
+        })?;
+        Ok(info)
+    }
+}
+
+impl<R: Read, S: Skip<R>> ParseOutput<R, S> for WebpInfo {
+    fn parse(parser: &mut MediaParser, mut ms: MediaSource<R, S>) -> crate::Result<Self> {
+        let info = parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, _| {
+            extract_webp_info(ms.mime, data).map_err(|e| ParsingErrorState::new(e, None))
+        })?;
+        Ok(info)
+    }
+}
+
+impl<R: Read, S: Skip<R>> ParseOutput<R, S> for MediaMetadata {
+    fn parse(parser: &mut MediaParser, mut ms: MediaSource<R, S>) -> crate::Result<Self> {
+        let mime = ms.mime;
+        if ms.has_track() {
+            let info: TrackInfo = ParseOutput::parse(parser, ms)?;
+            Ok(MediaMetadata::from_track(mime, &info))
+        } else {
+            // Image headers are at the start of the file, so the parsing
+            // buffer still starts from there when looking for Exif data.
+            let size = parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, _| {
+                extract_image_size(mime, data).map_err(|e| ParsingErrorState::new(e, None))
+            })?;
+            let exif = find_exif_iter::<R, S>(parser, mime.unwrap_image(), &mut ms.reader)?
+                .map(Exif::from);
+            Ok(MediaMetadata::from_image(mime, exif.as_ref(), size))
+        }
+    }
+}
+
 /// A `MediaParser`/`AsyncMediaParser` can parse media info from a
 /// [`MediaSource`].
 ///
@@ -434,8 +522,10 @@ impl MediaParser {
     ///   Entry values ​​and save them, or convert the `ExifIter` into an
     ///   [`crate::Exif`] object to retain all Entry values.
     ///
-    /// - For [`TrackInfo`] as parse output, you don't need to worry about
-    ///   this, because `TrackInfo` dosn't reference the parsing buffer.
+    /// - For [`TrackInfo`], [`Xmp`], [`Iptc`], [`IccProfile`] &
+    ///   [`MediaMetadata`] as parse output, you don't
+    ///   need to worry about this, because they don't reference the parsing
+    ///   buffer.
     pub fn parse<R: Read, S, O: ParseOutput<R, S>>(
         &mut self,
         mut ms: MediaSource<R, S>,
@@ -454,7 +544,13 @@ impl MediaParser {
         &mut self,
         mut ms: MediaSource<R, S>,
     ) -> Result<O, crate::Error> {
-        self.fill_buf(&mut ms.reader, INIT_BUF_SIZE)?;
+        match self.fill_buf(&mut ms.reader, INIT_BUF_SIZE) {
+            // The whole file has been read into the header buffer
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && !self.buf().is_empty() => (),
+            res => {
+                res?;
+            }
+        }
         let res = ParseOutput::parse(self, ms)?;
         Ok(res)
     }
@@ -518,6 +614,11 @@ mod tests {
     #[case("embedded-in-heic.mov", Track)]
     #[case("exif.heic", Exif)]
     #[case("exif.jpg", Exif)]
+    #[case("exif.png", Exif)]
+    #[case("exif.webp", Exif)]
+    #[case("exif.avif", Exif)]
+    #[case("exif.jxl", Exif)]
+    #[case("exif.cr3", Exif)]
     #[case("fujifilm_x_t1_01.raf.meta", Exif)]
     #[case("meta.mov", Track)]
     #[case("meta.mp4", Track)]
@@ -572,6 +673,24 @@ mod tests {
         }
     }
 
+    // The whole file is read into the header buffer of `MediaSource`
+    #[test]
+    fn parse_small_file() {
+        let mut writer = crate::ExifWriter::new();
+        writer.set(ExifTag::Make.group(), ExifTag::Make, "Apple".into());
+        let data = writer.write().unwrap();
+        assert!(data.len() < INIT_BUF_SIZE);
+
+        let mut parser = MediaParser::new();
+        let ms = MediaSource::seekable(std::io::Cursor::new(data.clone())).unwrap();
+        let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap().as_str(), Some("Apple"));
+
+        let ms = MediaSource::unseekable(std::io::Cursor::new(data)).unwrap();
+        let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap().as_str(), Some("Apple"));
+    }
+
     use crate::testkit::open_sample;
     use crate::{EntryValue, ExifTag, TrackInfoTag};
     use chrono::DateTime;
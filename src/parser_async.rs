@@ -15,15 +15,23 @@ use tokio::{
 use crate::{
     buffer::Buffers,
     error::{ParsedError, ParsingError, ParsingErrorState},
-    exif::parse_exif_iter_async,
-    file::Mime,
+    exif::{find_exif_iter_async, parse_exif_iter_async},
+    file::{parse_tiff_mime, MediaKind, Mime, MimeImage},
+    icc::extract_icc,
+    iptc::extract_iptc,
+    metadata::extract_image_size,
     parser::{
         Buf, ParsingState, ShareBuf, INIT_BUF_SIZE, MAX_ALLOC_SIZE, MAX_GROW_SIZE, MIN_GROW_SIZE,
+        TIFF_HEADER_PARSE_BUF_SIZE,
     },
     partial_vec::PartialVec,
+    png::extract_png_info,
     skip::AsyncSkip,
     video::parse_track_info,
-    ExifIter, Seekable, TrackInfo, Unseekable,
+    webp::extract_webp_info,
+    xmp::extract_xmp,
+    Exif, ExifIter, IccProfile, Iptc, MediaMetadata, PngInfo, Seekable, TrackInfo, Unseekable,
+    WebpInfo, Xmp,
 };
 
 // Should be enough for parsing header
@@ -44,7 +52,14 @@ impl<R: AsyncRead + Unpin, S: AsyncSkip<R>> AsyncMediaSource<R, S> {
             .take(HEADER_PARSE_BUF_SIZE as u64)
             .read_to_end(&mut buf)
             .await?;
-        let mime: Mime = buf.as_slice().try_into()?;
+        let mut mime: Mime = buf.as_slice().try_into()?;
+        if mime == Mime::Image(MimeImage::Tiff) {
+            (&mut reader)
+                .take((TIFF_HEADER_PARSE_BUF_SIZE - buf.len()) as u64)
+                .read_to_end(&mut buf)
+                .await?;
+            mime = Mime::Image(parse_tiff_mime(&buf));
+        }
         Ok(Self {
             reader,
             buf,
@@ -53,6 +68,11 @@ impl<R: AsyncRead + Unpin, S: AsyncSkip<R>> AsyncMediaSource<R, S> {
         })
     }
 
+    /// Returns the detected file type.
+    pub fn kind(&self) -> MediaKind {
+        self.mime.kind()
+    }
+
     pub fn has_track(&self) -> bool {
         match self.mime {
             Mime::Image(_) => false,
@@ -100,10 +120,10 @@ pub(crate) trait AsyncBufParser: Buf + Debug {
     async fn load_and_parse<R: AsyncRead + Unpin, S: AsyncSkip<R>, P, O>(
         &mut self,
         reader: &mut R,
-        parse: P,
+        mut parse: P,
     ) -> Result<O, ParsedError>
     where
-        P: Fn(&[u8], Option<ParsingState>) -> Result<O, ParsingErrorState>,
+        P: FnMut(&[u8], Option<ParsingState>) -> Result<O, ParsingErrorState>,
     {
         self.load_and_parse_with_offset::<R, S, _, _>(
             reader,
@@ -117,11 +137,11 @@ pub(crate) trait AsyncBufParser: Buf + Debug {
     async fn load_and_parse_with_offset<R: AsyncRead + Unpin, S: AsyncSkip<R>, P, O>(
         &mut self,
         reader: &mut R,
-        parse: P,
+        mut parse: P,
         offset: usize,
     ) -> Result<O, ParsedError>
     where
-        P: Fn(&[u8], usize, Option<ParsingState>) -> Result<O, ParsingErrorState>,
+        P: FnMut(&[u8], usize, Option<ParsingState>) -> Result<O, ParsingErrorState>,
     {
         if offset >= self.buffer().len() {
             self.fill_buf(reader, MIN_GROW_SIZE).await?;
@@ -243,6 +263,108 @@ impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S>
     }
 }
 
+impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S> for Xmp {
+    async fn parse(
+        parser: &mut AsyncMediaParser,
+        mut ms: AsyncMediaSource<R, S>,
+    ) -> crate::Result<Self> {
+        let mime = ms.mime;
+        let out = parser
+            .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, _| {
+                extract_xmp(mime, data).map_err(|e| ParsingErrorState::new(e, None))
+            })
+            .await?;
+        let (packet, extended) = out.ok_or("XMP not found")?;
+        Xmp::from_packets(packet, extended)
+    }
+}
+
+impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S> for Iptc {
+    async fn parse(
+        parser: &mut AsyncMediaParser,
+        mut ms: AsyncMediaSource<R, S>,
+    ) -> crate::Result<Self> {
+        let mime = ms.mime;
+        let out = parser
+            .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, _| {
+                extract_iptc(mime, data).map_err(|e| ParsingErrorState::new(e, None))
+            })
+            .await?;
+        Iptc::parse(&out.ok_or("IPTC not found")?)
+    }
+}
+
+impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S> for IccProfile {
+    async fn parse(
+        parser: &mut AsyncMediaParser,
+        mut ms: AsyncMediaSource<R, S>,
+    ) -> crate::Result<Self> {
+        let mime = ms.mime;
+        let out = parser
+            .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, _| {
+                extract_icc(mime, data).map_err(|e| ParsingErrorState::new(e, None))
+            })
+            .await?;
+        IccProfile::try_from(out.ok_or("ICC profile not found")?)
+    }
+}
+
+impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S> for PngInfo {
+    async fn parse(
+        parser: &mut AsyncMediaParser,
+        mut ms: AsyncMediaSource<R, S>,
+    ) -> crate::Result<Self> {
+        let mime = ms.mime;
+        let mut info = PngInfo::default();
+        parser
+            .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, _| {
+                extract_png_info(mime, data, &mut info).map_err(|e| ParsingErrorState::new(e, None))
+            })
+            .await?;
+        Ok(info)
+    }
+}
+
+impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S> for WebpInfo {
+    async fn parse(
+        parser: &mut AsyncMediaParser,
+        mut ms: AsyncMediaSource<R, S>,
+    ) -> crate::Result<Self> {
+        let mime = ms.mime;
+        let info = parser
+            .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, _| {
+                extract_webp_info(mime, data).map_err(|e| ParsingErrorState::new(e, None))
+            })
+            .await?;
+        Ok(info)
+    }
+}
+
+impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S> for MediaMetadata {
+    async fn parse(
+        parser: &mut AsyncMediaParser,
+        mut ms: AsyncMediaSource<R, S>,
+    ) -> crate::Result<Self> {
+        let mime = ms.mime;
+        if ms.has_track() {
+            let info = <TrackInfo as AsyncParseOutput<R, S>>::parse(parser, ms).await?;
+            Ok(MediaMetadata::from_track(mime, &info))
+        } else {
+            // Image headers are at the start of the file, so the parsing
+            // buffer still starts from there when looking for Exif data.
+            let size = parser
+                .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, _| {
+                    extract_image_size(mime, data).map_err(|e| ParsingErrorState::new(e, None))
+                })
+                .await?;
+            let exif = find_exif_iter_async::<R, S>(parser, mime.unwrap_image(), &mut ms.reader)
+                .await?
+                .map(Exif::from);
+            Ok(MediaMetadata::from_image(mime, exif.as_ref(), size))
+        }
+    }
+}
+
 /// An async version of `MediaParser`. See [`crate::MediaParser`] for more
 /// information.
 ///
@@ -378,7 +500,13 @@ impl AsyncMediaParser {
         &mut self,
         mut ms: AsyncMediaSource<R, S>,
     ) -> Result<O, crate::Error> {
-        self.fill_buf(&mut ms.reader, INIT_BUF_SIZE).await?;
+        match self.fill_buf(&mut ms.reader, INIT_BUF_SIZE).await {
+            // The whole file has been read into the header buffer
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && !self.buf().is_empty() => (),
+            res => {
+                res?;
+            }
+        }
         let res = O::parse(self, ms).await?;
         Ok(res)
     }
@@ -531,6 +659,30 @@ mod tests {
         }
     }
 
+    // The whole file is read into the header buffer of `AsyncMediaSource`
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn parse_small_file() {
+        let mut writer = crate::ExifWriter::new();
+        writer.set(ExifTag::Make.group(), ExifTag::Make, "Apple".into());
+        let data = writer.write().unwrap();
+        assert!(data.len() < INIT_BUF_SIZE);
+
+        let mut parser = AsyncMediaParser::new();
+        let ms = AsyncMediaSource::seekable(std::io::Cursor::new(data.clone()))
+            .await
+            .unwrap();
+        let iter: ExifIter = parser.parse(ms).await.unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap().as_str(), Some("Apple"));
+
+        let ms = AsyncMediaSource::unseekable(std::io::Cursor::new(data))
+            .await
+            .unwrap();
+        let iter: ExifIter = parser.parse(ms).await.unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap().as_str(), Some("Apple"));
+    }
+
     use crate::{EntryValue, ExifTag, TrackInfoTag};
     use chrono::DateTime;
     use test_case::test_case;
@@ -0,0 +1,404 @@
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+
+use crate::{
+    error::ParsingError,
+    exif::EXIF_IDENT,
+    file::{Mime, MimeImage},
+    parser::MAX_ALLOC_SIZE,
+};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+const TEXT_CHUNKS: &[&[u8; 4]] = &[b"tEXt", b"zTXt", b"iTXt"];
+
+/// Represents the basic image information & the textual data of a PNG file.
+///
+/// - Image information comes from the `IHDR` chunk.
+///
+/// - Textual data comes from `tEXt`, `zTXt` & `iTXt` chunks, compressed text
+///   is decompressed. XMP packet (`iTXt` with keyword `XML:com.adobe.xmp`) is
+///   included as well, use [`crate::Xmp`] as parse output to get the parsed
+///   XMP properties.
+///
+/// Exif data (`eXIf` chunk) can be parsed by using [`crate::ExifIter`] as
+/// parse output.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::file_path("./testdata/exif.png")?;
+///     let info: PngInfo = parser.parse(ms)?;
+///
+///     assert_eq!((info.width(), info.height()), (160, 160));
+///     assert_eq!(info.get_text("Comment"), Some("A compressed comment"));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PngInfo {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+    interlaced: bool,
+    texts: Vec<PngText>,
+}
+
+/// A textual chunk (`tEXt`, `zTXt` or `iTXt`) of a PNG file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PngText {
+    pub keyword: String,
+    pub text: String,
+    /// Language tag of an `iTXt` chunk, e.g. `"en-US"`.
+    pub language: Option<String>,
+    /// Keyword translated into `language`, only available in `iTXt` chunks.
+    pub translated_keyword: Option<String>,
+}
+
+impl PngInfo {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Bits per sample (or per palette index), 1, 2, 4, 8 or 16.
+    pub fn bit_depth(&self) -> u8 {
+        self.bit_depth
+    }
+
+    /// Color type defined in PNG specification:
+    ///
+    /// - 0: grayscale
+    /// - 2: truecolor (RGB)
+    /// - 3: indexed-color
+    /// - 4: grayscale with alpha
+    /// - 6: truecolor with alpha (RGBA)
+    pub fn color_type(&self) -> u8 {
+        self.color_type
+    }
+
+    /// Whether Adam7 interlace method is used.
+    pub fn interlaced(&self) -> bool {
+        self.interlaced
+    }
+
+    /// Returns all of the textual chunks, in the original order.
+    pub fn texts(&self) -> &[PngText] {
+        &self.texts
+    }
+
+    /// Get the text of the first textual chunk with `keyword`.
+    pub fn get_text(&self, keyword: &str) -> Option<&str> {
+        self.texts
+            .iter()
+            .find(|x| x.keyword == keyword)
+            .map(|x| x.text.as_str())
+    }
+}
+
+pub(crate) fn check_png(input: &[u8]) -> crate::Result<()> {
+    if input.starts_with(PNG_SIGNATURE) {
+        Ok(())
+    } else {
+        Err("invalid PNG file; signature not found".into())
+    }
+}
+
+/// Walks through the chunks in `input`, and calls `f` with (chunk type, chunk
+/// data) of each chunk in `types`, until `f` returns true or `IEND` is
+/// reached. Data of other chunks is skipped without being loaded.
+///
+/// `input` starts with either the PNG signature, or a chunk (after a
+/// `ClearAndSkip`). Chunks which have been handled are skipped by
+/// `ClearAndSkip` when more data is needed, so that `f` won't be called with
+/// the same chunk twice.
+fn travel_chunks<'a, F>(input: &'a [u8], types: &[&[u8; 4]], mut f: F) -> Result<(), ParsingError>
+where
+    F: FnMut(&[u8], &'a [u8]) -> bool,
+{
+    let incomplete = |pos: usize, end: usize| {
+        if pos == 0 {
+            ParsingError::Need(end - input.len())
+        } else {
+            ParsingError::ClearAndSkip(pos)
+        }
+    };
+
+    let mut pos = if input.starts_with(PNG_SIGNATURE) {
+        PNG_SIGNATURE.len()
+    } else {
+        0
+    };
+    loop {
+        let Some(header) = input.get(pos..pos + 8) else {
+            return Err(incomplete(pos, pos + 8));
+        };
+        let size = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+        let chunk_type = &header[4..];
+        if size > i32::MAX as usize {
+            return Err(ParsingError::Failed("invalid PNG chunk size".into()));
+        }
+
+        let data_start = pos + 8;
+        // chunk data & CRC
+        let end = data_start + size + 4;
+        if types.iter().any(|x| &x[..] == chunk_type) {
+            let Some(data) = input.get(data_start..data_start + size) else {
+                return Err(incomplete(pos, data_start + size));
+            };
+            if f(chunk_type, data) {
+                return Ok(());
+            }
+        } else if chunk_type == b"IEND" {
+            return Ok(());
+        } else if end > input.len() {
+            // skip unused chunk data
+            return Err(ParsingError::ClearAndSkip(end));
+        }
+        pos = end;
+    }
+}
+
+/// Extract the image size (width, height) from the `IHDR` chunk.
+pub(crate) fn extract_image_size(input: &[u8]) -> Result<Option<(u32, u32)>, ParsingError> {
+    let mut size = None;
+    travel_chunks(input, &[b"IHDR"], |_, data| {
+        if data.len() >= 8 {
+            let width = u32::from_be_bytes(data[..4].try_into().unwrap());
+            let height = u32::from_be_bytes(data[4..8].try_into().unwrap());
+            size = Some((width, height)).filter(|_| width > 0 && height > 0);
+        }
+        true
+    })?;
+    Ok(size)
+}
+
+/// Extract Exif TIFF data from the `eXIf` chunk.
+pub(crate) fn extract_exif_data(input: &[u8]) -> Result<Option<&[u8]>, ParsingError> {
+    let mut exif = None;
+    travel_chunks(input, &[b"eXIf"], |_, data| {
+        // Some writers keep the Exif identifier of JPEG APP1
+        exif = Some(data.strip_prefix(EXIF_IDENT.as_bytes()).unwrap_or(data));
+        true
+    })?;
+    Ok(exif)
+}
+
+/// Extract the XMP packet from the `iTXt` chunk with keyword
+/// `XML:com.adobe.xmp`.
+pub(crate) fn extract_xmp_data(input: &[u8]) -> Result<Option<Vec<u8>>, ParsingError> {
+    let mut xmp = None;
+    travel_chunks(input, &[b"iTXt"], |chunk_type, data| {
+        xmp = parse_text_chunk(chunk_type, data)
+            .filter(|x| x.keyword == XMP_KEYWORD)
+            .map(|x| x.text.into_bytes());
+        xmp.is_some()
+    })?;
+    Ok(xmp)
+}
+
+/// Parse `IHDR` & textual chunks into `info`. Since the chunks may be
+/// distributed in multiple calls (see [`travel_chunks`]), `info` should be
+/// reused between calls.
+pub(crate) fn extract_png_info(
+    mime: Mime,
+    input: &[u8],
+    info: &mut PngInfo,
+) -> Result<(), ParsingError> {
+    if mime != Mime::Image(MimeImage::Png) {
+        return Err(ParsingError::Failed(format!(
+            "PngInfo is not supported for {mime:?}"
+        )));
+    }
+
+    let types = [&[b"IHDR"][..], TEXT_CHUNKS].concat();
+    travel_chunks(input, &types, |chunk_type, data| {
+        if chunk_type == b"IHDR" {
+            if data.len() >= 13 {
+                info.width = u32::from_be_bytes(data[..4].try_into().unwrap());
+                info.height = u32::from_be_bytes(data[4..8].try_into().unwrap());
+                info.bit_depth = data[8];
+                info.color_type = data[9];
+                info.interlaced = data[12] == 1;
+            }
+        } else if let Some(text) = parse_text_chunk(chunk_type, data) {
+            info.texts.push(text);
+        }
+        false
+    })?;
+
+    // IHDR must be present, and zero width is invalid
+    if info.width == 0 {
+        return Err(ParsingError::Failed("PNG IHDR chunk not found".into()));
+    }
+    Ok(())
+}
+
+fn parse_text_chunk(chunk_type: &[u8], data: &[u8]) -> Option<PngText> {
+    let (keyword, rest) = split_null(data)?;
+    let keyword = latin1(keyword);
+
+    let (text, language, translated_keyword) = match chunk_type {
+        b"tEXt" => (latin1(rest), None, None),
+        b"zTXt" => {
+            // compression method must be 0 (zlib)
+            let (&0, compressed) = rest.split_first()? else {
+                return None;
+            };
+            (latin1(&inflate(compressed)?), None, None)
+        }
+        b"iTXt" => {
+            let [flag, 0, rest @ ..] = rest else {
+                return None;
+            };
+            let (language, rest) = split_null(rest)?;
+            let (translated, text) = split_null(rest)?;
+            let text = if *flag == 1 {
+                String::from_utf8_lossy(&inflate(text)?).into_owned()
+            } else {
+                String::from_utf8_lossy(text).into_owned()
+            };
+            let non_empty =
+                |x: &[u8]| (!x.is_empty()).then(|| String::from_utf8_lossy(x).into_owned());
+            (text, non_empty(language), non_empty(translated))
+        }
+        _ => return None,
+    };
+
+    Some(PngText {
+        keyword,
+        text,
+        language,
+        translated_keyword,
+    })
+}
+
+fn split_null(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let pos = data.iter().position(|x| *x == 0)?;
+    Some((&data[..pos], &data[pos + 1..]))
+}
+
+fn latin1(data: &[u8]) -> String {
+    data.iter().map(|b| *b as char).collect()
+}
+
+fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    decompress_to_vec_zlib_with_limit(data, MAX_ALLOC_SIZE)
+        .map_err(|e| tracing::warn!(?e, "Ignored: broken compressed PNG text"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::{parse_sample, png_chunk, read_sample};
+    use crate::{ExifIter, ExifTag, Xmp};
+    use test_case::test_case;
+
+    fn sample_png(idat_size: usize, exif: &[u8]) -> Vec<u8> {
+        let ihdr = [
+            &2u32.to_be_bytes()[..],
+            &3u32.to_be_bytes(),
+            &[16, 6, 0, 0, 1],
+        ]
+        .concat();
+        [
+            PNG_SIGNATURE.to_vec(),
+            png_chunk(b"IHDR", &ihdr),
+            png_chunk(b"tEXt", b"Author\0Alice"),
+            png_chunk(b"IDAT", &vec![0; idat_size]),
+            png_chunk(b"eXIf", exif),
+            png_chunk(b"tEXt", b"Author\0Bob"),
+            png_chunk(b"IEND", b""),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn png_info() {
+        let info: PngInfo = parse_sample(read_sample("exif.png").unwrap()).unwrap();
+        assert_eq!((info.width(), info.height()), (160, 160));
+        assert_eq!(info.bit_depth(), 8);
+        assert_eq!(info.color_type(), 2);
+        assert!(!info.interlaced());
+
+        let keywords = info
+            .texts()
+            .iter()
+            .map(|x| x.keyword.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keywords, ["Title", "Comment", XMP_KEYWORD, "Description"]);
+        assert_eq!(info.get_text("Title"), Some("Caf\u{e9}"));
+        assert_eq!(info.get_text("Comment"), Some("A compressed comment"));
+
+        let desc = &info.texts()[3];
+        assert_eq!(desc.text, "Ein Bild \u{fc}ber Dinge");
+        assert_eq!(desc.language.as_deref(), Some("de"));
+        assert_eq!(desc.translated_keyword.as_deref(), Some("Beschreibung"));
+    }
+
+    #[test]
+    fn png_exif() {
+        let mut iter: ExifIter = parse_sample(read_sample("exif.png").unwrap()).unwrap();
+        let entry = iter.find(|x| x.tag() == Some(ExifTag::Make)).unwrap();
+        assert_eq!(entry.get_value().unwrap().to_string(), "vivo");
+    }
+
+    #[test]
+    fn png_xmp() {
+        let xmp: Xmp = parse_sample(read_sample("exif.png").unwrap()).unwrap();
+        assert_eq!(
+            xmp.get(Xmp::NS_XMP, "CreatorTool").unwrap().as_str(),
+            Some("nom-exif test")
+        );
+    }
+
+    // Large IDAT chunks are skipped, and chunks before them won't be parsed
+    // twice.
+    #[test_case(10)]
+    #[test_case(1024 * 1024)]
+    fn skip_chunks(idat_size: usize) {
+        let mut writer = crate::ExifWriter::new();
+        writer.set(crate::IfdGroup::Ifd0, ExifTag::Make, "abc".into());
+        let tiff = writer.write().unwrap();
+        let exif_with_ident = [EXIF_IDENT.as_bytes(), &tiff].concat();
+
+        for exif in [tiff, exif_with_ident] {
+            let png = sample_png(idat_size, &exif);
+            let info: PngInfo = parse_sample(png.clone()).unwrap();
+            assert_eq!((info.width(), info.height(), info.bit_depth()), (2, 3, 16));
+            assert!(info.interlaced());
+            let texts = info
+                .texts()
+                .iter()
+                .map(|x| x.text.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(texts, ["Alice", "Bob"]);
+
+            let mut iter: ExifIter = parse_sample(png).unwrap();
+            let make = iter.next().unwrap();
+            assert_eq!(make.get_value().unwrap().to_string(), "abc");
+        }
+    }
+
+    #[test]
+    fn no_data() {
+        let png = [
+            PNG_SIGNATURE.to_vec(),
+            png_chunk(b"IHDR", &[0; 13]),
+            png_chunk(b"IEND", b""),
+        ]
+        .concat();
+        parse_sample::<ExifIter>(png.clone()).unwrap_err();
+        parse_sample::<Xmp>(png).unwrap_err();
+        parse_sample::<PngInfo>(read_sample("exif.jpg").unwrap()).unwrap_err();
+    }
+}
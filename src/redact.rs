@@ -0,0 +1,1137 @@
+use std::{
+    collections::HashSet,
+    io::{Cursor, Read, Write},
+    ops::Range,
+};
+
+use nom::number::{complete::be_u32, Endianness};
+
+use crate::{
+    bbox::{find_box, KeysBox, MetaBox, ParseBox},
+    exif::{TiffHeader, EXIF_IDENT, IFD_ENTRY_SIZE},
+    file::{Mime, MimeImage, MimeVideo},
+    jpeg::{rewrite_segments, SegmentBuf, XMP_EXT_IDENT, XMP_IDENT},
+    mov,
+    parser::MAX_ALLOC_SIZE,
+    slice::SubsliceRange,
+    values::DataFormat,
+    ExifTag, MediaSource,
+};
+
+/// Specifies which metadata should be removed by [`redact`].
+///
+/// The default policy removes nothing from Exif data, use the builder methods
+/// to add what should be removed, e.g.:
+///
+/// ```rust
+/// use nom_exif::RedactPolicy;
+///
+/// let policy = RedactPolicy::new().drop_gps().drop_serial_numbers();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedactPolicy {
+    gps: bool,
+    serial_numbers: bool,
+    maker_note: bool,
+    essential_only: bool,
+    xmp: bool,
+    iptc: bool,
+}
+
+impl RedactPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove GPS info, including the GPS sub-IFD in Exif data, and the
+    /// location entries in QuickTime/MP4 files.
+    pub fn drop_gps(mut self) -> Self {
+        self.gps = true;
+        self
+    }
+
+    /// Remove serial numbers of camera body & lens.
+    pub fn drop_serial_numbers(mut self) -> Self {
+        self.serial_numbers = true;
+        self
+    }
+
+    /// Remove the MakerNote entry.
+    pub fn drop_maker_note(mut self) -> Self {
+        self.maker_note = true;
+        self
+    }
+
+    /// Remove everything except `Orientation` & `ColorSpace`.
+    ///
+    /// For QuickTime/MP4 files, all the `moov/meta` items and the
+    /// `moov/udta/©xyz` box are cleared.
+    pub fn keep_essential_only(mut self) -> Self {
+        self.essential_only = true;
+        self
+    }
+
+    /// Remove XMP packets, i.e. the XMP `APP1` segments in JPEG files, the
+    /// XMP item in HEIF files, and the XMP `uuid` & `moov/udta/XMP_` boxes in
+    /// QuickTime/MP4 files.
+    ///
+    /// They are also removed by [`Self::drop_gps`],
+    /// [`Self::drop_serial_numbers`] & [`Self::keep_essential_only`], since
+    /// XMP may carry GPS info & serial numbers as well.
+    pub fn drop_xmp(mut self) -> Self {
+        self.xmp = true;
+        self
+    }
+
+    /// Remove IPTC data, i.e. the Photoshop `APP13` segments (JPEG only).
+    ///
+    /// They are also removed by [`Self::keep_essential_only`].
+    pub fn drop_iptc(mut self) -> Self {
+        self.iptc = true;
+        self
+    }
+
+    fn drops_xmp(&self) -> bool {
+        self.xmp || self.gps || self.serial_numbers || self.essential_only
+    }
+
+    fn drops_iptc(&self) -> bool {
+        self.iptc || self.essential_only
+    }
+}
+
+/// Remove metadata from `ms` according to `policy`, and write a cleaned copy
+/// to `writer`.
+///
+/// Currently supported file formats are:
+///
+/// - JPEG: The APP1 Exif segment is redacted in place. XMP (APP1) & IPTC
+///   (APP13) segments are removed according to the policy, see
+///   [`RedactPolicy::drop_xmp`] & [`RedactPolicy::drop_iptc`], other
+///   segments are kept. Files with MPF (APP2) secondary images are rejected,
+///   since the Exif data of the secondary images can't be redacted.
+///
+/// - HEIF/HEIC: The Exif item is redacted in place, or blanked if it can't
+///   be parsed. The XMP item is blanked according to the policy.
+///
+/// Exif data is never re-encoded: the selected entries are removed from
+/// their IFDs and their values are zeroed, everything else (byte order,
+/// IFD1 & the thumbnail etc.) is kept as-is. `keep_essential_only` drops
+/// IFD1 & the thumbnail as well.
+///
+/// - QuickTime/MP4: Location entries in `moov/meta` keys/ilst and the
+///   `moov/udta/©xyz` box are cleared in place. The XMP `uuid` &
+///   `moov/udta/XMP_` boxes are blanked according to the policy.
+///
+/// An `Err` will be returned for unsupported file formats, or if the
+/// metadata can't be located reliably.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+///     let mut output = Vec::new();
+///     redact(ms, &RedactPolicy::new().drop_gps(), &mut output)?;
+///
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::seekable(std::io::Cursor::new(output))?;
+///     let exif: Exif = parser.parse::<_, _, ExifIter>(ms)?.into();
+///     assert!(exif.get_gps_info()?.is_none());
+///     assert_eq!(exif.get(ExifTag::Make).unwrap(), &"vivo".into());
+///     Ok(())
+/// }
+/// ```
+pub fn redact<R: Read, S, W: Write>(
+    ms: MediaSource<R, S>,
+    policy: &RedactPolicy,
+    writer: W,
+) -> crate::Result<()> {
+    let mime = ms.mime;
+    let reader = Cursor::new(ms.buf).chain(ms.reader);
+    match mime {
+        Mime::Image(MimeImage::Jpeg) => redact_jpeg(reader, policy, writer),
+        Mime::Image(MimeImage::Heic | MimeImage::Heif | MimeImage::Avif) => {
+            BmffRedactor::new(reader, writer, policy).run(true)
+        }
+        Mime::Video(MimeVideo::QuickTime | MimeVideo::Mp4 | MimeVideo::_3gpp | MimeVideo::M4a) => {
+            BmffRedactor::new(reader, writer, policy).run(false)
+        }
+        _ => Err(format!("redaction is not supported for {mime:?}").into()),
+    }
+}
+
+const SERIAL_NUMBER_TAGS: &[u16] = &[
+    0xa431, // BodySerialNumber
+    0xa435, // LensSerialNumber
+    0xc62f, // CameraSerialNumber
+];
+
+const APP2: u8 = 0xE2;
+const APP13: u8 = 0xED;
+
+const MPF_IDENT: &[u8] = b"MPF\0";
+
+/// Redact TIFF/Exif data in place.
+///
+/// Only the selected entries are touched: they are removed from their IFD
+/// tables, and their values (including the sub-IFDs they point to) are
+/// zeroed. Everything else, e.g. the byte order, offsets & the thumbnail, is
+/// kept as-is, so an empty policy leaves the data unchanged.
+pub(crate) fn redact_exif(tiff: &mut [u8], policy: &RedactPolicy) -> crate::Result<()> {
+    let (_, header) = TiffHeader::parse(tiff)?;
+    if header.bigtiff {
+        return Err("redaction is not supported for BigTIFF data".into());
+    }
+    let mut tiff = TiffPatcher {
+        data: tiff,
+        endian: header.endian,
+    };
+    let ifd0 = header.ifd0_offset as usize;
+
+    let drop_exif_tag = |tag: u16| {
+        (policy.essential_only && tag != ExifTag::ColorSpace.code())
+            || (policy.serial_numbers && SERIAL_NUMBER_TAGS.contains(&tag))
+            || (policy.maker_note && tag == ExifTag::MakerNote.code())
+    };
+    let mut exif_empty = false;
+    if let Some(exif_ifd) = tiff.pointer(ifd0, ExifTag::ExifOffset.code())? {
+        exif_empty = tiff.remove_entries(exif_ifd, drop_exif_tag)? == 0;
+    }
+
+    tiff.remove_entries(ifd0, |tag| {
+        if tag == ExifTag::ExifOffset.code() {
+            policy.essential_only && exif_empty
+        } else if tag == ExifTag::GPSInfo.code() {
+            policy.gps || policy.essential_only
+        } else {
+            (policy.essential_only && tag != ExifTag::Orientation.code())
+                || (policy.serial_numbers && SERIAL_NUMBER_TAGS.contains(&tag))
+        }
+    })?;
+
+    if policy.essential_only {
+        // Drop IFD1 (the thumbnail) and the rest of the IFD chain
+        if let Some(next) = tiff.unlink_next_ifd(ifd0)? {
+            tiff.blank_ifd(next, 0)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sub-IFD pointer tags, the IFDs pointed by them are blanked along with the
+/// entries.
+const POINTER_TAGS: &[u16] = &[
+    0x8769, // ExifOffset
+    0x8825, // GPSInfo
+    0xa005, // InteropOffset
+    0x014a, // SubIFDs
+];
+
+/// Max depth of nested sub-IFDs to be blanked.
+const MAX_IFD_DEPTH: usize = 4;
+
+/// Edits TIFF/Exif data in place, without moving anything.
+struct TiffPatcher<'a> {
+    data: &'a mut [u8],
+    endian: Endianness,
+}
+
+/// A raw IFD entry.
+struct RawEntry {
+    tag: u16,
+    format: u16,
+    count: u32,
+    // Position of the 4-bytes value/offset field
+    value_pos: usize,
+}
+
+impl TiffPatcher<'_> {
+    fn u16_at(&self, pos: usize) -> crate::Result<u16> {
+        let b = self.bytes(pos, 2)?;
+        let b = [b[0], b[1]];
+        Ok(match self.endian {
+            Endianness::Little => u16::from_le_bytes(b),
+            _ => u16::from_be_bytes(b),
+        })
+    }
+
+    fn u32_at(&self, pos: usize) -> crate::Result<u32> {
+        let b = self.bytes(pos, 4)?;
+        let b = [b[0], b[1], b[2], b[3]];
+        Ok(match self.endian {
+            Endianness::Little => u32::from_le_bytes(b),
+            _ => u32::from_be_bytes(b),
+        })
+    }
+
+    fn put_u16(&mut self, pos: usize, v: u16) {
+        let b = match self.endian {
+            Endianness::Little => v.to_le_bytes(),
+            _ => v.to_be_bytes(),
+        };
+        self.data[pos..pos + 2].copy_from_slice(&b);
+    }
+
+    fn put_u32(&mut self, pos: usize, v: u32) {
+        let b = match self.endian {
+            Endianness::Little => v.to_le_bytes(),
+            _ => v.to_be_bytes(),
+        };
+        self.data[pos..pos + 4].copy_from_slice(&b);
+    }
+
+    fn bytes(&self, pos: usize, len: usize) -> crate::Result<&[u8]> {
+        pos.checked_add(len)
+            .and_then(|end| self.data.get(pos..end))
+            .ok_or_else(|| "invalid Exif data; offset is out of range".into())
+    }
+
+    fn entries(&self, ifd: usize) -> crate::Result<Vec<RawEntry>> {
+        let num = self.u16_at(ifd)? as usize;
+        // Make sure the whole IFD table is in range
+        self.bytes(ifd, 2 + num * IFD_ENTRY_SIZE + 4)?;
+        (0..num)
+            .map(|i| {
+                let pos = ifd + 2 + i * IFD_ENTRY_SIZE;
+                Ok(RawEntry {
+                    tag: self.u16_at(pos)?,
+                    format: self.u16_at(pos + 2)?,
+                    count: self.u32_at(pos + 4)?,
+                    value_pos: pos + 8,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the offset of the sub-IFD pointed by `tag` in `ifd`.
+    fn pointer(&self, ifd: usize, tag: u16) -> crate::Result<Option<usize>> {
+        let entries = self.entries(ifd)?;
+        match entries.iter().find(|e| e.tag == tag) {
+            Some(e) => Ok(Some(self.u32_at(e.value_pos)? as usize)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the range of the value of `entry`, `None` if the value is
+    /// stored in the entry itself.
+    fn value_range(&self, entry: &RawEntry) -> crate::Result<Option<Range<usize>>> {
+        let size = DataFormat::try_from(entry.format)
+            .map(|x| x.component_size())
+            .unwrap_or(1)
+            .saturating_mul(entry.count as usize);
+        if size <= 4 {
+            return Ok(None);
+        }
+        let start = self.u32_at(entry.value_pos)? as usize;
+        self.bytes(start, size)?;
+        Ok(Some(start..start + size))
+    }
+
+    /// Removes the entries of `ifd` which match `pred`, and blanks their
+    /// values. Returns the number of the remaining entries.
+    fn remove_entries<F>(&mut self, ifd: usize, pred: F) -> crate::Result<usize>
+    where
+        F: Fn(u16) -> bool,
+    {
+        let entries = self.entries(ifd)?;
+        let next = self.u32_at(ifd + 2 + entries.len() * IFD_ENTRY_SIZE)?;
+        let (removed, kept): (Vec<_>, Vec<_>) = entries.into_iter().partition(|e| pred(e.tag));
+        if removed.is_empty() {
+            return Ok(kept.len());
+        }
+
+        for e in removed.iter() {
+            tracing::debug!(tag = format!("0x{:04x}", e.tag), "entry removed");
+            self.blank_value(e, 0)?;
+        }
+
+        // Move the kept entries forward, followed by the next IFD offset
+        let table: Vec<u8> = kept
+            .iter()
+            .flat_map(|e| self.data[e.value_pos - 8..e.value_pos + 4].to_vec())
+            .collect();
+        let end = ifd + 2 + (kept.len() + removed.len()) * IFD_ENTRY_SIZE + 4;
+        self.put_u16(ifd, kept.len() as u16);
+        self.data[ifd + 2..ifd + 2 + table.len()].copy_from_slice(&table);
+        let pos = ifd + 2 + table.len();
+        self.put_u32(pos, next);
+        self.data[pos + 4..end].fill(0);
+        Ok(kept.len())
+    }
+
+    /// Zero the value of `entry`, and the sub-IFDs it points to.
+    fn blank_value(&mut self, entry: &RawEntry, depth: usize) -> crate::Result<()> {
+        let range = self.value_range(entry)?;
+        if POINTER_TAGS.contains(&entry.tag) {
+            let offsets = match range {
+                Some(ref r) => (r.start..r.end)
+                    .step_by(4)
+                    .map(|pos| self.u32_at(pos))
+                    .collect::<crate::Result<Vec<_>>>()?,
+                None => vec![self.u32_at(entry.value_pos)?],
+            };
+            for offset in offsets {
+                self.blank_ifd(offset as usize, depth + 1)?;
+            }
+        }
+        if let Some(range) = range {
+            self.data[range].fill(0);
+        }
+        Ok(())
+    }
+
+    /// Zero the whole `ifd`, including the values & the thumbnail data.
+    fn blank_ifd(&mut self, ifd: usize, depth: usize) -> crate::Result<()> {
+        if depth > MAX_IFD_DEPTH {
+            return Err("invalid Exif data; too many nested IFDs".into());
+        }
+        let entries = self.entries(ifd)?;
+        let value = |tag: ExifTag| {
+            entries
+                .iter()
+                .find(|e| e.tag == tag.code())
+                .map(|e| self.u32_at(e.value_pos))
+                .transpose()
+        };
+        let thumbnail = value(ExifTag::ThumbnailOffset)?.zip(value(ExifTag::ThumbnailLength)?);
+        if let Some((offset, len)) = thumbnail {
+            self.bytes(offset as usize, len as usize)?;
+            self.data[offset as usize..(offset + len) as usize].fill(0);
+        }
+
+        for e in entries.iter() {
+            self.blank_value(e, depth)?;
+        }
+        let end = ifd + 2 + entries.len() * IFD_ENTRY_SIZE + 4;
+        self.data[ifd..end].fill(0);
+        Ok(())
+    }
+
+    /// Clear the next IFD offset of `ifd`, returns the original one.
+    fn unlink_next_ifd(&mut self, ifd: usize) -> crate::Result<Option<usize>> {
+        let pos = ifd + 2 + self.u16_at(ifd)? as usize * IFD_ENTRY_SIZE;
+        let next = self.u32_at(pos)?;
+        self.put_u32(pos, 0);
+        Ok((next != 0).then_some(next as usize))
+    }
+}
+
+fn redact_jpeg<R: Read, W: Write>(
+    reader: R,
+    policy: &RedactPolicy,
+    writer: W,
+) -> crate::Result<()> {
+    rewrite_segments(reader, writer, |segments| {
+        let mut result = Vec::with_capacity(segments.len());
+        for mut s in segments.drain(..) {
+            // The secondary images of MPF (e.g. depth maps, previews) are
+            // appended after the primary image, each with its own Exif data.
+            // They can't be redacted without breaking the MPF offsets.
+            if s.marker_code == APP2
+                && s.payload.starts_with(MPF_IDENT)
+                && *policy != RedactPolicy::default()
+            {
+                return Err(
+                    "redacting JPEG files with MPF secondary images is not supported".into(),
+                );
+            }
+            if s.is_exif() {
+                redact_exif(&mut s.payload[EXIF_IDENT.len()..], policy)?;
+            } else if (policy.drops_xmp() && is_xmp(&s))
+                || (policy.drops_iptc() && s.marker_code == APP13)
+            {
+                tracing::debug!(marker = s.marker_code, "segment removed");
+                continue;
+            }
+            result.push(s);
+        }
+        *segments = result;
+        Ok(())
+    })
+}
+
+fn is_xmp(s: &SegmentBuf) -> bool {
+    s.marker_code == 0xE1
+        && [XMP_IDENT, XMP_EXT_IDENT]
+            .iter()
+            .any(|x| s.payload.starts_with(x))
+}
+
+/// Copies an ISOBMFF file box by box, and rewrites metadata in place, so
+/// that all box sizes & offsets keep unchanged.
+struct BmffRedactor<'a, R, W> {
+    reader: R,
+    writer: W,
+    policy: &'a RedactPolicy,
+    pos: u64,
+    // Absolute file ranges of the HEIF items to be rewritten, sorted by the
+    // start offset
+    items: Vec<(Range<u64>, ItemKind)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    Exif,
+    Xmp,
+}
+
+impl ItemKind {
+    fn redact(self, item: &mut [u8], policy: &RedactPolicy) {
+        match self {
+            ItemKind::Exif => redact_exif_item(item, policy),
+            ItemKind::Xmp => item.fill(0),
+        }
+    }
+}
+
+impl<'a, R: Read, W: Write> BmffRedactor<'a, R, W> {
+    fn new(reader: R, writer: W, policy: &'a RedactPolicy) -> Self {
+        Self {
+            reader,
+            writer,
+            policy,
+            pos: 0,
+            items: Vec::new(),
+        }
+    }
+
+    fn run(mut self, heif: bool) -> crate::Result<()> {
+        loop {
+            let mut header = [0u8; 16];
+            let n = read_full(&mut self.reader, &mut header[..8])?;
+            if n == 0 {
+                break;
+            }
+            if n < 8 {
+                self.write_through(&header[..n])?;
+                break;
+            }
+
+            let box_type: [u8; 4] = header[4..8].try_into().unwrap();
+            let mut header_size = 8;
+            let size = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+                // box extends to the end of the file
+                0 => None,
+                1 => {
+                    self.reader.read_exact(&mut header[8..16])?;
+                    header_size = 16;
+                    Some(u64::from_be_bytes(header[8..16].try_into().unwrap()))
+                }
+                x => Some(x as u64),
+            };
+
+            let Some(size) = size else {
+                self.write_through(&header[..header_size])?;
+                self.copy(u64::MAX)?;
+                break;
+            };
+            if size < header_size as u64 {
+                return Err(format!("invalid box size: {size}").into());
+            }
+            let body_size = size - header_size as u64;
+
+            if &box_type == b"uuid" && self.policy.drops_xmp() && body_size >= 16 {
+                let mut uuid = [0u8; 16];
+                self.reader.read_exact(&mut uuid)?;
+                if uuid == mov::XMP_UUID {
+                    // Turn the XMP box into a `free` box
+                    tracing::debug!("XMP uuid box blanked");
+                    header[4..8].copy_from_slice(b"free");
+                    self.write_through(&header[..header_size])?;
+                    self.write_through(&[0; 16])?;
+                    self.blank(body_size - 16)?;
+                    continue;
+                }
+                self.write_through(&header[..header_size])?;
+                self.write_through(&uuid)?;
+                self.copy(body_size - 16)?;
+                continue;
+            }
+
+            let load = if heif {
+                &box_type == b"meta"
+            } else {
+                &box_type == b"moov"
+            };
+            if !load {
+                self.write_through(&header[..header_size])?;
+                self.copy(body_size)?;
+                continue;
+            }
+
+            if size > MAX_ALLOC_SIZE as u64 {
+                return Err(format!("box is too big: {size}").into());
+            }
+            let mut data = header[..header_size].to_vec();
+            data.resize(size as usize, 0);
+            self.reader.read_exact(&mut data[header_size..])?;
+            if heif {
+                self.locate_items(&mut data)?;
+            } else {
+                redact_moov(&mut data, header_size, self.policy)?;
+            }
+            self.write_through(&data)?;
+        }
+
+        if let Some((_, kind)) = self.items.first() {
+            return Err(format!("{kind:?} item is out of the file").into());
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn locate_items(&mut self, data: &mut [u8]) -> crate::Result<()> {
+        let (_, meta) = MetaBox::parse_box(data)?;
+        let meta_offset = self.pos as usize;
+
+        let mut items = vec![(
+            ItemKind::Exif,
+            meta.exif_item_id(),
+            meta.exif_data_offset(meta_offset),
+        )];
+        if self.policy.drops_xmp() {
+            items.push((
+                ItemKind::Xmp,
+                meta.xmp_item_id(),
+                meta.xmp_data_offset(meta_offset),
+            ));
+        }
+
+        let meta_end = meta_offset + data.len();
+        for (kind, id, range) in items {
+            let Some(range) = range else {
+                if id.is_some() {
+                    return Err(format!("can't locate the {kind:?} item").into());
+                }
+                continue;
+            };
+
+            // The item is stored in the idat box, rewrite it in place
+            if range.start >= meta_offset && range.start < meta_end {
+                if range.end > meta_end {
+                    return Err(format!("{kind:?} item crosses the box boundary").into());
+                }
+                let start = range.start - meta_offset;
+                kind.redact(&mut data[start..start + range.len()], self.policy);
+                continue;
+            }
+
+            if (range.start as u64) < self.pos {
+                return Err(format!("{kind:?} item is located before meta box").into());
+            }
+            self.items
+                .push((range.start as u64..range.end as u64, kind));
+        }
+
+        self.items.sort_by_key(|(range, _)| range.start);
+        if self.items.windows(2).any(|x| x[0].0.end > x[1].0.start) {
+            return Err("HEIF items overlap with each other".into());
+        }
+        Ok(())
+    }
+
+    /// Copy at most `len` bytes from reader to writer, the pending items
+    /// will be rewritten when we meet them.
+    fn copy(&mut self, len: u64) -> crate::Result<()> {
+        let end = self.pos.saturating_add(len);
+        let mut buf = vec![0u8; 64 * 1024];
+        while self.pos < end {
+            if let Some((item, kind)) = self.items.first().cloned() {
+                if self.pos == item.start {
+                    if item.end > end {
+                        return Err(format!("{kind:?} item crosses the box boundary").into());
+                    }
+                    let mut data = vec![0u8; (item.end - item.start) as usize];
+                    self.reader.read_exact(&mut data)?;
+                    kind.redact(&mut data, self.policy);
+                    self.write_through(&data)?;
+                    self.items.remove(0);
+                    continue;
+                }
+            }
+
+            let mut limit = (end - self.pos).min(buf.len() as u64);
+            if let Some((item, _)) = self.items.first() {
+                if item.start > self.pos {
+                    limit = limit.min(item.start - self.pos);
+                }
+            }
+            let n = self.reader.read(&mut buf[..limit as usize])?;
+            if n == 0 {
+                if len == u64::MAX {
+                    break;
+                }
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            self.writer.write_all(&buf[..n])?;
+            self.pos += n as u64;
+        }
+        Ok(())
+    }
+
+    /// Skip `len` bytes of the reader, and write zeros instead.
+    fn blank(&mut self, len: u64) -> crate::Result<()> {
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut remain = len;
+        while remain > 0 {
+            let n = remain.min(buf.len() as u64) as usize;
+            self.reader.read_exact(&mut buf[..n])?;
+            buf[..n].fill(0);
+            self.write_through(&buf[..n])?;
+            remain -= n as u64;
+        }
+        Ok(())
+    }
+
+    fn write_through(&mut self, data: &[u8]) -> crate::Result<()> {
+        self.writer.write_all(data)?;
+        self.pos += data.len() as u64;
+        Ok(())
+    }
+}
+
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..])? {
+            0 => break,
+            x => n += x,
+        }
+    }
+    Ok(n)
+}
+
+/// The HEIF Exif item: a 4-bytes offset of TIFF header, then the Exif data.
+fn redact_exif_item(item: &mut [u8], policy: &RedactPolicy) {
+    let Ok((_, offset)) = be_u32::<_, nom::error::Error<_>>(&item[..]) else {
+        item.fill(0);
+        return;
+    };
+    let mut start = (offset as usize).saturating_add(4).min(item.len());
+    if item[start..].starts_with(EXIF_IDENT.as_bytes()) {
+        start += EXIF_IDENT.len();
+    }
+    let tiff = &mut item[start..];
+
+    if let Err(e) = redact_exif(tiff, policy) {
+        tracing::warn!(error = ?e, "can't rewrite the Exif item, blank it");
+        tiff.fill(0);
+    }
+}
+
+/// Clear location entries (or all entries if `essential_only`) and the
+/// `udta/XMP_` box in a `moov` box, all sizes keep unchanged.
+fn redact_moov(moov: &mut [u8], header_size: usize, policy: &RedactPolicy) -> crate::Result<()> {
+    if !policy.drops_xmp() {
+        return Ok(());
+    }
+
+    // (range, header size of a box which should be renamed to "free")
+    let mut blanks: Vec<(Range<usize>, Option<usize>)> = Vec::new();
+    {
+        let data = &moov[..];
+        let body = &data[header_size..];
+
+        if policy.gps || policy.essential_only {
+            if let Ok((_, Some(meta))) = find_box(body, "meta") {
+                let keys = find_box(meta.body_data(), "keys").ok().and_then(|x| x.1);
+                let ilst = find_box(meta.body_data(), "ilst").ok().and_then(|x| x.1);
+                if let (Some(keys), Some(ilst)) = (keys, ilst) {
+                    let (_, entries) = KeysBox::parse_box(keys.data)?;
+                    let indices = entries
+                        .entries
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, k)| policy.essential_only || k.key.contains("location"))
+                        .map(|(i, _)| i as u32 + 1)
+                        .collect::<HashSet<_>>();
+
+                    // Clear both key names & values, so that the entries can't
+                    // be recognized any more.
+                    let start = data.subslice_in_range(keys.body_data()).unwrap().start;
+                    for range in key_name_ranges(keys.body_data(), &indices) {
+                        blanks.push((start + range.start..start + range.end, None));
+                    }
+                    let start = data.subslice_in_range(ilst.body_data()).unwrap().start;
+                    for range in ilst_value_ranges(ilst.body_data(), &indices) {
+                        blanks.push((start + range.start..start + range.end, None));
+                    }
+                }
+            }
+
+            if let Ok((_, Some(xyz))) = find_box(body, "udta/©xyz") {
+                let range = data.subslice_in_range(xyz.data).unwrap();
+                blanks.push((range, Some(xyz.header_size())));
+            }
+        }
+
+        if let Ok((_, Some(xmp))) = find_box(body, "udta/XMP_") {
+            let range = data.subslice_in_range(xmp.data).unwrap();
+            blanks.push((range, Some(xmp.header_size())));
+        }
+    }
+
+    for (range, rename) in blanks {
+        let b = &mut moov[range];
+        if let Some(header_size) = rename {
+            b[4..8].copy_from_slice(b"free");
+            b[header_size..].fill(0);
+        } else {
+            b.fill(0);
+        }
+    }
+    Ok(())
+}
+
+/// Returns key name ranges (relative to `keys_body`) of the entries whose
+/// 1-based index is in `indices`.
+fn key_name_ranges(keys_body: &[u8], indices: &HashSet<u32>) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    // version & flags, entry count
+    let mut pos = 8;
+    let mut index = 1;
+    while pos + 8 <= keys_body.len() {
+        let size = u32::from_be_bytes(keys_body[pos..pos + 4].try_into().unwrap()) as usize;
+        if size < 8 || pos + size > keys_body.len() {
+            break;
+        }
+        // entry: size, namespace, key name
+        if indices.contains(&index) {
+            ranges.push(pos + 8..pos + size);
+        }
+        pos += size;
+        index += 1;
+    }
+    ranges
+}
+
+/// Returns value ranges (relative to `ilst_body`) of the items whose key
+/// index is in `indices`.
+fn ilst_value_ranges(ilst_body: &[u8], indices: &HashSet<u32>) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut pos = 0;
+    while pos + 8 <= ilst_body.len() {
+        let size = u32::from_be_bytes(ilst_body[pos..pos + 4].try_into().unwrap()) as usize;
+        let index = u32::from_be_bytes(ilst_body[pos + 4..pos + 8].try_into().unwrap());
+        if size < 8 || pos + size > ilst_body.len() {
+            break;
+        }
+        // item: size, index, then a data atom: size, "data", type, locale, value
+        if indices.contains(&index) && size > 24 {
+            ranges.push(pos + 24..pos + size);
+        }
+        pos += size;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exif::input_into_iter;
+    use crate::jpeg::extract_exif_data;
+    use crate::testkit::{bmff_box, heif_xmp_sample, parse_sample, read_sample};
+    use crate::{EntryValue, Exif, ExifIter, ExifWriter, IfdGroup, TrackInfo, TrackInfoTag};
+    use test_case::test_case;
+
+    fn redact_data(data: &[u8], policy: RedactPolicy) -> crate::Result<Vec<u8>> {
+        let ms = MediaSource::seekable(Cursor::new(data)).unwrap();
+        let mut output = Vec::new();
+        redact(ms, &policy, &mut output)?;
+        Ok(output)
+    }
+
+    fn redact_sample(path: &str, policy: RedactPolicy) -> Vec<u8> {
+        redact_data(&read_sample(path).unwrap(), policy).unwrap()
+    }
+
+    fn parse_exif(data: Vec<u8>) -> Exif {
+        parse_sample::<ExifIter>(data).unwrap().into()
+    }
+
+    fn tiff_data(jpeg: &[u8]) -> Vec<u8> {
+        let (_, data) = extract_exif_data(jpeg).unwrap();
+        data.unwrap().to_vec()
+    }
+
+    fn redact_tiff(writer: &ExifWriter, policy: RedactPolicy) -> (Vec<u8>, Exif) {
+        let mut data = writer.write().unwrap();
+        redact_exif(&mut data, &policy).unwrap();
+        let exif = input_into_iter(data.clone(), None).unwrap().into();
+        (data, exif)
+    }
+
+    #[test]
+    fn redact_jpeg_empty_policy() {
+        let buf = read_sample("exif.jpg").unwrap();
+        let output = redact_sample("exif.jpg", RedactPolicy::new());
+        assert_eq!(tiff_data(&output), tiff_data(&buf));
+        assert_eq!(output, buf);
+    }
+
+    #[test]
+    fn redact_jpeg_gps() {
+        let buf = read_sample("exif.jpg").unwrap();
+        let orig = parse_exif(buf.clone());
+        let output = redact_sample("exif.jpg", RedactPolicy::new().drop_gps().drop_maker_note());
+        // Entries are removed in place
+        assert_eq!(tiff_data(&output).len(), tiff_data(&buf).len());
+        assert_eq!(output.len(), buf.len());
+
+        let exif = parse_exif(output);
+        assert!(exif.get_gps_info().unwrap().is_none());
+        assert!(exif.get(ExifTag::GPSLatitude).is_none());
+        assert!(exif.get(ExifTag::MakerNote).is_none());
+        assert_eq!(exif.get(ExifTag::Make).unwrap(), &"vivo".into());
+        assert!(exif.get(ExifTag::DateTimeOriginal).is_some());
+        assert!(orig.thumbnail().is_some());
+        assert_eq!(exif.thumbnail(), orig.thumbnail());
+    }
+
+    #[test_case(ExifWriter::new())]
+    #[test_case(ExifWriter::little_endian())]
+    fn redact_exif_in_place(mut writer: ExifWriter) {
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "nom-exif".into())
+            .set(IfdGroup::Ifd0, ExifTag::Orientation, 6_u16.into())
+            .set(IfdGroup::Ifd0, ExifTag::Artist, "someone".into())
+            .set_by_tag_code(IfdGroup::Ifd0, 0xc62f, "SN-0001".into())
+            .set(IfdGroup::Exif, ExifTag::ColorSpace, 1_u16.into())
+            .set(IfdGroup::Exif, ExifTag::BodySerialNumber, "SN-1234".into())
+            .set(IfdGroup::Exif, ExifTag::LensSerialNumber, "SN-5678".into())
+            .set(
+                IfdGroup::Exif,
+                ExifTag::MakerNote,
+                EntryValue::Undefined(vec![1; 32]),
+            )
+            .set(IfdGroup::Interop, ExifTag::InteropIndex, "R98".into())
+            .set(IfdGroup::Gps, ExifTag::GPSLatitudeRef, "N".into())
+            .set(IfdGroup::Ifd1, ExifTag::Compression, 6_u16.into())
+            .set_thumbnail(vec![0xff, 0xd8, 0xff, 0xd9]);
+        let orig = writer.write().unwrap();
+
+        let (data, _) = redact_tiff(&writer, RedactPolicy::new());
+        assert_eq!(data, orig);
+
+        let policy = RedactPolicy::new()
+            .drop_serial_numbers()
+            .drop_maker_note()
+            .drop_gps();
+        let (data, exif) = redact_tiff(&writer, policy);
+        assert_eq!(data.len(), orig.len());
+        assert_eq!(data[..4], orig[..4]);
+        for tag in [
+            ExifTag::BodySerialNumber,
+            ExifTag::LensSerialNumber,
+            ExifTag::MakerNote,
+            ExifTag::GPSLatitudeRef,
+        ] {
+            assert!(exif.get(tag).is_none(), "{tag}");
+        }
+        assert!(exif.get_by_ifd_tag_code(0, 0xc62f).is_none());
+        assert!(!data.windows(3).any(|x| x == b"SN-"));
+        assert_eq!(exif.get(ExifTag::Make).unwrap(), &"nom-exif".into());
+        assert_eq!(exif.get(ExifTag::InteropIndex).unwrap(), &"R98".into());
+        assert_eq!(exif.thumbnail(), Some(&[0xff, 0xd8, 0xff, 0xd9][..]));
+
+        let (data, exif) = redact_tiff(&writer, RedactPolicy::new().keep_essential_only());
+        assert_eq!(data.len(), orig.len());
+        assert_eq!(exif.get(ExifTag::Orientation).unwrap(), &6_u16.into());
+        assert_eq!(exif.get(ExifTag::ColorSpace).unwrap(), &1_u16.into());
+        for tag in [ExifTag::Make, ExifTag::Artist, ExifTag::InteropIndex] {
+            assert!(exif.get(tag).is_none(), "{tag}");
+        }
+        assert!(exif.thumbnail().is_none());
+        assert!(exif
+            .get_by_ifd_tag_code(1, ExifTag::Compression.code())
+            .is_none());
+        assert!(!data.windows(8).any(|x| x == b"nom-exif"));
+        assert!(!data.windows(4).any(|x| x == [0xff, 0xd8, 0xff, 0xd9]));
+    }
+
+    #[test]
+    fn redact_jpeg_essential_only() {
+        let buf = read_sample("exif.jpg").unwrap();
+        let orig = parse_exif(buf);
+        let output = redact_sample("exif.jpg", RedactPolicy::new().keep_essential_only());
+        let exif = parse_exif(output);
+        assert!(exif.get(ExifTag::Make).is_none());
+        assert!(exif.get(ExifTag::GPSLatitude).is_none());
+        assert_eq!(
+            exif.get(ExifTag::Orientation),
+            orig.get(ExifTag::Orientation)
+        );
+        assert_eq!(exif.get(ExifTag::ColorSpace), orig.get(ExifTag::ColorSpace));
+    }
+
+    fn segment(marker_code: u8, payload: &[u8]) -> Vec<u8> {
+        [
+            &[0xFF, marker_code][..],
+            &((payload.len() + 2) as u16).to_be_bytes(),
+            payload,
+        ]
+        .concat()
+    }
+
+    #[test_case(RedactPolicy::new(), false, false; "empty")]
+    #[test_case(RedactPolicy::new().drop_xmp(), true, false; "xmp")]
+    #[test_case(RedactPolicy::new().drop_iptc(), false, true; "iptc")]
+    #[test_case(RedactPolicy::new().drop_gps(), true, false; "gps")]
+    #[test_case(RedactPolicy::new().drop_serial_numbers(), true, false; "serial numbers")]
+    #[test_case(RedactPolicy::new().drop_maker_note(), false, false; "maker note")]
+    #[test_case(RedactPolicy::new().keep_essential_only(), true, true; "essential only")]
+    fn redact_jpeg_xmp_iptc(policy: RedactPolicy, drop_xmp: bool, drop_iptc: bool) {
+        let orig = read_sample("no-exif.jpg").unwrap();
+        // Insert XMP & IPTC segments after SOI
+        let xmp = segment(0xE1, &[XMP_IDENT, b"<x:xmpmeta/>"].concat());
+        let iptc = segment(APP13, crate::jpeg::PHOTOSHOP_IDENT);
+        let mut buf = orig.clone();
+        buf.splice(2..2, [xmp.clone(), iptc.clone()].concat());
+
+        let ms = MediaSource::seekable(Cursor::new(&buf)).unwrap();
+        let mut output = Vec::new();
+        redact(ms, &policy, &mut output).unwrap();
+
+        let mut expect = orig;
+        if !drop_iptc {
+            expect.splice(2..2, iptc);
+        }
+        if !drop_xmp {
+            expect.splice(2..2, xmp);
+        }
+        assert_eq!(output, expect);
+    }
+
+    #[test]
+    fn redact_jpeg_mpf() {
+        let mut buf = read_sample("no-exif.jpg").unwrap();
+        let mpf = segment(APP2, &[MPF_IDENT, b"MM\0\x2a\0\0\0\x08"].concat());
+        buf.splice(2..2, mpf);
+
+        // Nothing is removed, the file is copied as-is
+        assert_eq!(redact_data(&buf, RedactPolicy::new()).unwrap(), buf);
+        let err = redact_data(&buf, RedactPolicy::new().drop_gps()).unwrap_err();
+        assert!(err.to_string().contains("MPF"), "{err}");
+    }
+
+    #[test]
+    fn redact_heic() {
+        let mut buf = read_sample("exif-one-entry.heic").unwrap();
+
+        // Replace the only entry with a `Make` entry
+        let (_, tiff) = crate::heif::extract_exif_data(&buf).unwrap();
+        let range = buf.subslice_in_range(tiff.unwrap()).unwrap();
+        let tiff = [
+            &b"MM\0\x2a\0\0\0\x08\0\x01"[..],
+            &b"\x01\x0f\0\x02\0\0\0\x04abc\0"[..],
+            &[0; 4],
+        ]
+        .concat();
+        assert_eq!(tiff.len(), range.len());
+        buf[range].copy_from_slice(&tiff);
+        let exif = parse_exif(buf.clone());
+        assert_eq!(exif.get(ExifTag::Make).unwrap(), &"abc".into());
+
+        let ms = MediaSource::seekable(Cursor::new(&buf)).unwrap();
+        let mut output = Vec::new();
+        redact(ms, &RedactPolicy::new().keep_essential_only(), &mut output).unwrap();
+        assert_eq!(output.len(), buf.len());
+        let exif = parse_exif(output);
+        assert!(exif.get(ExifTag::Make).is_none());
+    }
+
+    #[test_case(true)]
+    #[test_case(false)]
+    fn redact_avif(in_idat: bool) {
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, ExifTag::Make, "abc".into());
+        writer.set(IfdGroup::Ifd0, ExifTag::Orientation, 6u16.into());
+        let item = [&0u32.to_be_bytes()[..], &writer.write().unwrap()].concat();
+        let buf = crate::testkit::avif_sample(b"avif", &item, in_idat);
+
+        let ms = MediaSource::seekable(Cursor::new(&buf)).unwrap();
+        let mut output = Vec::new();
+        redact(ms, &RedactPolicy::new().keep_essential_only(), &mut output).unwrap();
+        assert_eq!(output.len(), buf.len());
+        let exif = parse_exif(output);
+        assert!(exif.get(ExifTag::Make).is_none());
+        assert_eq!(exif.get(ExifTag::Orientation), Some(&6u16.into()));
+    }
+
+    #[test_case("meta.mov")]
+    #[test_case("meta.mp4")]
+    fn redact_mov(path: &str) {
+        let buf = read_sample(path).unwrap();
+        let output = redact_sample(path, RedactPolicy::new().drop_gps());
+        assert_eq!(output.len(), buf.len());
+
+        let info: TrackInfo = parse_sample(output).unwrap();
+        assert!(info.get_gps_info().is_none());
+        assert!(info.get(TrackInfoTag::ImageWidth).is_some());
+    }
+
+    const PACKET: &[u8] = b"<x:xmpmeta><exif:GPSLatitude>22,31.8N</exif:GPSLatitude></x:xmpmeta>";
+
+    #[test_case(RedactPolicy::new(), false; "empty")]
+    #[test_case(RedactPolicy::new().drop_xmp(), true; "xmp")]
+    #[test_case(RedactPolicy::new().drop_gps(), true; "gps")]
+    #[test_case(RedactPolicy::new().keep_essential_only(), true; "essential only")]
+    #[test_case(RedactPolicy::new().drop_maker_note(), false; "maker note")]
+    fn redact_heif_xmp(policy: RedactPolicy, drop_xmp: bool) {
+        let buf = heif_xmp_sample(PACKET);
+        let output = redact_data(&buf, policy).unwrap();
+        assert_eq!(output.len(), buf.len());
+
+        // The XMP item is stored at the end of the file
+        let start = buf.len() - PACKET.len();
+        assert_eq!(output[..start], buf[..start]);
+        if drop_xmp {
+            assert!(output[start..].iter().all(|x| *x == 0));
+        } else {
+            assert_eq!(output[start..], buf[start..]);
+        }
+    }
+
+    #[test_case("meta.mov", RedactPolicy::new().drop_gps(), true)]
+    #[test_case("meta.mp4", RedactPolicy::new().drop_xmp(), true)]
+    #[test_case("meta.mp4", RedactPolicy::new().drop_maker_note(), false)]
+    fn redact_mov_xmp_uuid(path: &str, policy: RedactPolicy, drop_xmp: bool) {
+        let mut buf = read_sample(path).unwrap();
+        let uuid = bmff_box(b"uuid", &[&mov::XMP_UUID[..], PACKET].concat());
+        buf.extend(&uuid);
+        let output = redact_data(&buf, policy).unwrap();
+        assert_eq!(output.len(), buf.len());
+
+        let start = buf.len() - uuid.len();
+        if drop_xmp {
+            assert_eq!(output[start..], bmff_box(b"free", &vec![0; uuid.len() - 8]));
+        } else {
+            assert_eq!(output[start..], uuid);
+        }
+        let info: TrackInfo = parse_sample(output).unwrap();
+        assert!(info.get(TrackInfoTag::ImageWidth).is_some());
+    }
+
+    #[test]
+    fn redact_moov_xmp() {
+        let moov = |udta: Vec<u8>| bmff_box(b"moov", &bmff_box(b"udta", &udta));
+        let orig = moov(bmff_box(b"XMP_", PACKET));
+
+        let mut data = orig.clone();
+        redact_moov(&mut data, 8, &RedactPolicy::new().drop_maker_note()).unwrap();
+        assert_eq!(data, orig);
+
+        redact_moov(&mut data, 8, &RedactPolicy::new().drop_xmp()).unwrap();
+        assert_eq!(data, moov(bmff_box(b"free", &vec![0; PACKET.len()])));
+    }
+
+    #[test]
+    fn redact_unsupported() {
+        let buf = read_sample("tif.tif").unwrap();
+        let ms = MediaSource::seekable(Cursor::new(buf)).unwrap();
+        redact(ms, &RedactPolicy::new(), Vec::new()).unwrap_err();
+    }
+}
@@ -0,0 +1,519 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Seek, SeekFrom, Write},
+};
+
+use chrono::{Duration, NaiveDateTime};
+use nom::number::complete::be_u32;
+
+use crate::{
+    bbox::{find_box, travel_while, MetaBox, ParseBox},
+    error::ParsingError,
+    exif::{IfdHeaderTravel, TiffHeader, EXIF_IDENT},
+    file::{Mime, MimeImage, MimeVideo},
+    parser::MAX_ALLOC_SIZE,
+    slice::SubsliceRange,
+    values::DataFormat,
+    ExifTag,
+};
+
+const EXIF_TIME_FORMAT: &str = "%Y:%m:%d %H:%M:%S";
+const EXIF_TIME_LEN: usize = 19;
+
+const TIME_TAGS: &[ExifTag] = &[
+    ExifTag::ModifyDate,
+    ExifTag::DateTimeOriginal,
+    ExifTag::CreateDate,
+];
+
+/// Shift the timestamps of a media file by `delta`, the file is patched in
+/// place (same length), without rewriting the whole file.
+///
+/// The following timestamps are shifted:
+///
+/// - JPEG/TIFF/HEIF/HEIC: `ModifyDate`, `DateTimeOriginal` and `CreateDate`
+///   in the Exif data. Time zone tags (`OffsetTime*`) are left unchanged.
+///
+/// - QuickTime/MP4: creation/modification times in `moov/mvhd` and every
+///   `moov/trak/tkhd`. `delta` is truncated to whole seconds, and unset (zero)
+///   times are left unchanged.
+///
+/// Returns the number of timestamps which have been patched. All new values
+/// are computed before any byte is written, so the file is left untouched if
+/// an `Err` is returned because of an out-of-range timestamp.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+/// use std::io::Cursor;
+///
+/// fn main() -> Result<()> {
+///     let mut file = Cursor::new(std::fs::read("./testdata/exif.jpg")?);
+///     let n = shift_timestamps(&mut file, chrono::Duration::hours(1))?;
+///     assert_eq!(n, 3);
+///
+///     file.set_position(0);
+///     let mut parser = MediaParser::new();
+///     let iter: ExifIter = parser.parse(MediaSource::seekable(file)?)?;
+///     let exif: Exif = iter.into();
+///     assert_eq!(
+///         exif.get(ExifTag::DateTimeOriginal).unwrap().to_string(),
+///         "2023-07-09T21:36:33+08:00",
+///     );
+///     Ok(())
+/// }
+/// ```
+pub fn shift_timestamps<F: Read + Write + Seek>(
+    file: &mut F,
+    delta: Duration,
+) -> crate::Result<usize> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut buf = Vec::new();
+    Read::by_ref(file).take(128).read_to_end(&mut buf)?;
+    let mime = Mime::try_from(&buf[..])?;
+
+    let patches = match mime {
+        Mime::Image(MimeImage::Jpeg) => match locate_jpeg_exif(file)? {
+            Some((start, end)) => exif_patches(file, start, Some(end), delta)?,
+            None => Vec::new(),
+        },
+        Mime::Image(img) if img.is_tiff() => exif_patches(file, 0, None, delta)?,
+        Mime::Image(MimeImage::Heic | MimeImage::Heif | MimeImage::Avif) => {
+            match locate_heif_exif(file)? {
+                Some((start, end)) => exif_patches(file, start, Some(end), delta)?,
+                None => Vec::new(),
+            }
+        }
+        Mime::Video(MimeVideo::QuickTime | MimeVideo::Mp4 | MimeVideo::_3gpp | MimeVideo::M4a) => {
+            moov_patches(file, delta)?
+        }
+        _ => return Err(format!("shifting timestamps is not supported for {mime:?}").into()),
+    };
+
+    for (offset, data) in patches.iter() {
+        file.seek(SeekFrom::Start(*offset))?;
+        file.write_all(data)?;
+    }
+    file.flush()?;
+    Ok(patches.len())
+}
+
+type Patches = Vec<(u64, Vec<u8>)>;
+
+/// Returns the absolute range of TIFF data in the Exif segment.
+fn locate_jpeg_exif<F: Read + Seek>(file: &mut F) -> crate::Result<Option<(u64, u64)>> {
+    file.seek(SeekFrom::Start(2))?;
+    loop {
+        let mut b = [0u8; 1];
+        file.read_exact(&mut b)?;
+        if b[0] != 0xFF {
+            return Err(format!("invalid JPEG marker: {:02x}", b[0]).into());
+        }
+        // skip fill bytes
+        let marker = loop {
+            file.read_exact(&mut b)?;
+            if b[0] != 0xFF {
+                break b[0];
+            }
+        };
+
+        match marker {
+            // SOS, EOI
+            0xDA | 0xD9 => return Ok(None),
+            // standalone markers
+            0x01 | 0xD0..=0xD7 => continue,
+            _ => (),
+        }
+
+        let mut size = [0u8; 2];
+        file.read_exact(&mut size)?;
+        let size = u16::from_be_bytes(size) as u64;
+        if size < 2 {
+            return Err(format!("invalid JPEG segment size: {size}").into());
+        }
+        let start = file.stream_position()?;
+        let end = start + size - 2;
+
+        if marker == 0xE1 && size >= 2 + EXIF_IDENT.len() as u64 {
+            let mut ident = [0u8; 6];
+            file.read_exact(&mut ident)?;
+            if ident == EXIF_IDENT.as_bytes() {
+                return Ok(Some((start + EXIF_IDENT.len() as u64, end)));
+            }
+        }
+        file.seek(SeekFrom::Start(end))?;
+    }
+}
+
+/// Returns the absolute range of TIFF data in the HEIF Exif item.
+fn locate_heif_exif<F: Read + Seek>(file: &mut F) -> crate::Result<Option<(u64, u64)>> {
+    let Some((start, meta)) = load_top_level_box(file, b"meta")? else {
+        return Ok(None);
+    };
+    let (_, meta) = MetaBox::parse_box(&meta)?;
+    let Some(range) = meta.exif_data_offset(start as usize) else {
+        if meta.exif_item_id().is_some() {
+            return Err("can't locate the Exif item".into());
+        }
+        return Ok(None);
+    };
+    tracing::debug!(meta_offset = start, ?range, "Exif item");
+
+    // The HEIF Exif item: a 4-bytes offset of TIFF header, then the Exif data.
+    let mut buf = [0u8; 4];
+    file.seek(SeekFrom::Start(range.start as u64))?;
+    file.read_exact(&mut buf)?;
+    let (_, offset) = be_u32::<_, nom::error::Error<_>>(&buf[..])?;
+    let start = range.start as u64 + 4 + offset as u64;
+    if start >= range.end as u64 {
+        return Err("invalid Exif item".into());
+    }
+    Ok(Some((start, range.end as u64)))
+}
+
+/// Find a top level box by `box_type`, returns its absolute offset and data
+/// (including the header).
+fn load_top_level_box<F: Read + Seek>(
+    file: &mut F,
+    box_type: &[u8; 4],
+) -> crate::Result<Option<(u64, Vec<u8>)>> {
+    let mut pos = file.seek(SeekFrom::Start(0))?;
+    loop {
+        let mut header = [0u8; 16];
+        let n = file.by_ref().take(8).read(&mut header[..8])?;
+        if n == 0 {
+            return Ok(None);
+        }
+        if n < 8 {
+            file.read_exact(&mut header[n..8])?;
+        }
+
+        let size = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+            // box extends to the end of the file
+            0 => file.seek(SeekFrom::End(0))? - pos,
+            1 => {
+                file.seek(SeekFrom::Start(pos + 8))?;
+                file.read_exact(&mut header[8..16])?;
+                u64::from_be_bytes(header[8..16].try_into().unwrap())
+            }
+            x => x as u64,
+        };
+        if size < 8 {
+            return Err(format!("invalid box size: {size}").into());
+        }
+
+        if &header[4..8] == box_type {
+            if size > MAX_ALLOC_SIZE as u64 {
+                return Err(format!("box is too big: {size}").into());
+            }
+            let mut data = vec![0u8; size as usize];
+            file.seek(SeekFrom::Start(pos))?;
+            file.read_exact(&mut data)?;
+            return Ok(Some((pos, data)));
+        }
+
+        pos = pos
+            .checked_add(size)
+            .ok_or_else(|| crate::Error::from("invalid box size"))?;
+        file.seek(SeekFrom::Start(pos))?;
+    }
+}
+
+/// Compute the new values of Exif timestamps, `start` is the absolute offset
+/// of the TIFF header, `end` is the end of the Exif data (if known).
+fn exif_patches<F: Read + Seek>(
+    file: &mut F,
+    start: u64,
+    end: Option<u64>,
+    delta: Duration,
+) -> crate::Result<Patches> {
+    file.seek(SeekFrom::Start(start))?;
+    // BigTIFF header is 16 bytes
+    let mut header = Vec::new();
+    file.by_ref().take(16).read_to_end(&mut header)?;
+    let (_, header) = TiffHeader::parse(&header)?;
+
+    let ifd0 = start + header.ifd0_offset;
+    let limit = end.map(|end| end.saturating_sub(ifd0)).unwrap_or(u64::MAX);
+    file.seek(SeekFrom::Start(ifd0))?;
+    let mut buf = Vec::new();
+    file.by_ref().take(limit.min(4096)).read_to_end(&mut buf)?;
+
+    // data offsets (relative to TIFF header) of timestamp entries
+    let offsets = loop {
+        let mut offsets = Vec::new();
+        let mut travel =
+            IfdHeaderTravel::new(&buf, header.ifd0_offset, header.endian, header.bigtiff);
+        let res = travel.travel_ifd_with(0, &mut |entry| {
+            if TIME_TAGS.iter().any(|t| t.code() == entry.tag)
+                && entry.data_format == DataFormat::Text
+                && entry.components_num as usize >= EXIF_TIME_LEN
+            {
+                offsets.push(entry.data_offset);
+            }
+        });
+        match res {
+            Ok(()) => break offsets,
+            Err(ParsingError::Need(n)) => {
+                let to_read = (n.max(4096) as u64).min(limit - buf.len() as u64);
+                if buf.len() + to_read as usize > MAX_ALLOC_SIZE {
+                    return Err("Exif data is too big".into());
+                }
+                let read = file.by_ref().take(to_read).read_to_end(&mut buf)?;
+                if read < n {
+                    return Err("Exif data is truncated".into());
+                }
+            }
+            Err(e) => return Err(e.to_string().into()),
+        }
+    };
+
+    // Entries may share the same data.
+    let mut patches = BTreeMap::new();
+    for offset in offsets {
+        let pos = start + offset;
+        if end.is_some_and(|end| pos + EXIF_TIME_LEN as u64 > end) {
+            tracing::warn!(offset, "Ignored: timestamp is out of the Exif data");
+            continue;
+        }
+
+        let mut data = [0u8; EXIF_TIME_LEN];
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut data)?;
+        let Some(t) = std::str::from_utf8(&data)
+            .ok()
+            .and_then(|s| NaiveDateTime::parse_from_str(s, EXIF_TIME_FORMAT).ok())
+        else {
+            tracing::warn!(offset, ?data, "Ignored: invalid timestamp");
+            continue;
+        };
+
+        let s = t
+            .checked_add_signed(delta)
+            .map(|t| t.format(EXIF_TIME_FORMAT).to_string())
+            .filter(|s| s.len() == EXIF_TIME_LEN)
+            .ok_or_else(|| format!("timestamp is out of range: {t} + {delta}"))?;
+        patches.insert(pos, s.into_bytes());
+    }
+
+    Ok(patches.into_iter().collect())
+}
+
+/// Compute the new values of creation/modification times in `moov/mvhd` and
+/// `moov/trak/tkhd`.
+fn moov_patches<F: Read + Seek>(file: &mut F, delta: Duration) -> crate::Result<Patches> {
+    let Some((start, moov)) = load_top_level_box(file, b"moov")? else {
+        return Ok(Vec::new());
+    };
+    let header_size = if u32::from_be_bytes(moov[..4].try_into().unwrap()) == 1 {
+        16
+    } else {
+        8
+    };
+    let body = &moov[header_size..];
+
+    let mut boxes = Vec::new();
+    if let Ok((_, Some(mvhd))) = find_box(body, "mvhd") {
+        boxes.push(mvhd.body_data());
+    }
+    let _ = travel_while(body, |bbox| {
+        if bbox.box_type() == "trak" {
+            if let Ok((_, Some(tkhd))) = find_box(bbox.body_data(), "tkhd") {
+                boxes.push(tkhd.body_data());
+            }
+        }
+        true
+    });
+
+    let seconds = delta.num_seconds();
+    let mut patches = Vec::new();
+    for body in boxes {
+        // version(1) + flags(3), then creation & modification time
+        let (version, times) = match body.first() {
+            Some(0) if body.len() >= 12 => (0, &body[4..12]),
+            Some(1) if body.len() >= 20 => (1, &body[4..20]),
+            _ => {
+                tracing::warn!("Ignored: invalid mvhd/tkhd box");
+                continue;
+            }
+        };
+        let pos = start + moov.subslice_in_range(times).unwrap().start as u64;
+
+        let size = if version == 0 { 4 } else { 8 };
+        for (i, time) in times.chunks(size).enumerate() {
+            let t = if version == 0 {
+                u32::from_be_bytes(time.try_into().unwrap()) as u64
+            } else {
+                u64::from_be_bytes(time.try_into().unwrap())
+            };
+            if t == 0 {
+                continue;
+            }
+
+            let new = i64::try_from(t)
+                .ok()
+                .and_then(|t| t.checked_add(seconds))
+                .filter(|t| *t >= 0)
+                .ok_or_else(|| format!("timestamp is out of range: {t} + {seconds}s"))?;
+            let data = if version == 0 {
+                u32::try_from(new)
+                    .map_err(|_| format!("timestamp is out of range: {t} + {seconds}s"))?
+                    .to_be_bytes()
+                    .to_vec()
+            } else {
+                (new as u64).to_be_bytes().to_vec()
+            };
+            patches.push((pos + (i * size) as u64, data));
+        }
+    }
+
+    Ok(patches)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::testkit::{parse_sample, read_sample};
+    use crate::{EntryValue, Exif, ExifIter, ExifWriter, IfdGroup, TrackInfo, TrackInfoTag};
+    use chrono::DateTime;
+    use test_case::test_case;
+
+    fn parse_exif(data: Vec<u8>) -> Option<Exif> {
+        parse_sample::<ExifIter>(data).ok().map(|x| x.into())
+    }
+
+    #[test_case("exif.jpg", 3)]
+    #[test_case("tif.tif", 0)]
+    #[test_case("exif-one-entry.heic", 0)]
+    #[test_case("no-exif.jpg", 0)]
+    fn shift_exif(path: &str, expect: usize) {
+        let buf = read_sample(path).unwrap();
+        check_shift_exif(buf, expect);
+    }
+
+    #[test]
+    fn shift_tiff() {
+        let t = DateTime::parse_from_rfc3339("2024-02-29T23:59:59+08:00").unwrap();
+        let mut writer = ExifWriter::new();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "abc".into())
+            .set(IfdGroup::Ifd0, ExifTag::ModifyDate, EntryValue::Time(t))
+            .set(
+                IfdGroup::Exif,
+                ExifTag::DateTimeOriginal,
+                EntryValue::Time(t),
+            )
+            .set(IfdGroup::Exif, ExifTag::CreateDate, EntryValue::Time(t));
+        check_shift_exif(writer.write().unwrap(), 3);
+    }
+
+    #[test_case(false)]
+    #[test_case(true)]
+    fn shift_heic(in_idat: bool) {
+        let t = DateTime::parse_from_rfc3339("2024-02-29T23:59:59+08:00").unwrap();
+        let mut writer = ExifWriter::little_endian();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "abc".into())
+            .set(
+                IfdGroup::Exif,
+                ExifTag::DateTimeOriginal,
+                EntryValue::Time(t),
+            )
+            .set(
+                IfdGroup::Exif,
+                ExifTag::CreateDate,
+                EntryValue::NaiveDateTime(t.naive_local()),
+            );
+        let item = [&0u32.to_be_bytes()[..], &writer.write().unwrap()].concat();
+        let buf = crate::testkit::avif_sample(b"heic", &item, in_idat);
+        assert_eq!(
+            Mime::try_from(&buf[..]).unwrap(),
+            Mime::Image(MimeImage::Heic)
+        );
+
+        check_shift_exif(buf.clone(), 2);
+
+        let mut file = Cursor::new(buf);
+        shift_timestamps(&mut file, Duration::hours(-9)).unwrap();
+        let exif = parse_exif(file.into_inner()).unwrap();
+        for tag in [ExifTag::DateTimeOriginal, ExifTag::CreateDate] {
+            assert_eq!(
+                exif.get(tag).and_then(|x| x.as_naive_datetime()).unwrap(),
+                "2024-02-29T14:59:59".parse().unwrap(),
+                "{tag}"
+            );
+        }
+    }
+
+    fn check_shift_exif(buf: Vec<u8>, expect: usize) {
+        let mut file = Cursor::new(buf.clone());
+        let delta = Duration::days(-1) + Duration::seconds(30);
+        assert_eq!(shift_timestamps(&mut file, delta).unwrap(), expect);
+
+        let output = file.into_inner();
+        assert_eq!(output.len(), buf.len());
+
+        let (Some(before), Some(after)) = (parse_exif(buf), parse_exif(output)) else {
+            assert_eq!(expect, 0);
+            return;
+        };
+        let mut n = 0;
+        for tag in TIME_TAGS {
+            let Some(t) = before.get(*tag).and_then(|x| x.as_naive_datetime()) else {
+                continue;
+            };
+            assert_eq!(
+                after.get(*tag).and_then(|x| x.as_naive_datetime()),
+                Some(t + delta)
+            );
+            n += 1;
+        }
+        assert_eq!(n, expect);
+        assert_eq!(after.get(ExifTag::Make), before.get(ExifTag::Make));
+    }
+
+    #[test_case("meta.mov")]
+    #[test_case("meta.mp4")]
+    fn shift_moov(path: &str) {
+        let buf = read_sample(path).unwrap();
+        let mut file = Cursor::new(buf.clone());
+        let n = shift_timestamps(&mut file, Duration::hours(2)).unwrap();
+        assert!(n > 0);
+
+        let output = file.into_inner();
+        assert_eq!(output.len(), buf.len());
+
+        let parse = |data: Vec<u8>| parse_sample::<TrackInfo>(data).unwrap();
+        let before = parse(buf);
+        let after = parse(output);
+        let time = |info: &TrackInfo| {
+            info.get(TrackInfoTag::CreateDate)
+                .and_then(|x| x.as_time())
+                .unwrap()
+        };
+        assert_eq!(time(&after), time(&before) + Duration::hours(2));
+    }
+
+    #[test]
+    fn shift_out_of_range() {
+        let buf = read_sample("exif.jpg").unwrap();
+        let mut file = Cursor::new(buf.clone());
+        shift_timestamps(&mut file, Duration::days(365 * 8000)).unwrap_err();
+        assert!(file.into_inner() == buf);
+
+        let buf = read_sample("meta.mov").unwrap();
+        let mut file = Cursor::new(buf.clone());
+        shift_timestamps(&mut file, Duration::days(-365 * 200)).unwrap_err();
+        assert!(file.into_inner() == buf);
+    }
+
+    #[test]
+    fn shift_unsupported() {
+        let buf = read_sample("webm_480.webm").unwrap();
+        let mut file = Cursor::new(buf);
+        shift_timestamps(&mut file, Duration::hours(1)).unwrap_err();
+    }
+}
@@ -1,7 +1,13 @@
-use std::{fs::File, io::Read, path::Path};
+use std::{
+    fs::File,
+    io::{Cursor, Read},
+    path::Path,
+};
 
 use crate::exif::Exif;
 use crate::exif::ExifTag::*;
+use crate::parser::ParseOutput;
+use crate::{MediaParser, MediaSource, Seekable};
 
 pub fn read_sample(path: &str) -> Result<Vec<u8>, std::io::Error> {
     let mut f = open_sample(path)?;
@@ -20,6 +26,13 @@ pub fn open_sample(path: &str) -> Result<File, std::io::Error> {
     File::open(p)
 }
 
+/// Parses an in-memory file with a new `MediaParser`.
+pub fn parse_sample<O: ParseOutput<Cursor<Vec<u8>>, Seekable>>(data: Vec<u8>) -> crate::Result<O> {
+    let mut parser = MediaParser::new();
+    let ms = MediaSource::seekable(Cursor::new(data))?;
+    parser.parse(ms)
+}
+
 #[allow(unused)]
 pub fn open_sample_w(path: &str) -> Result<File, std::io::Error> {
     let p = Path::new(path);
@@ -151,3 +164,169 @@ pub fn sorted_exif_entries(exif: &Exif) -> Vec<String> {
 
     entries
 }
+
+/// Builds an ISOBMFF box (also used by JPEG XL) with a 32-bit size.
+pub fn bmff_box(box_type: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    [&((body.len() + 8) as u32).to_be_bytes()[..], box_type, body].concat()
+}
+
+/// Builds an ISOBMFF full box, with the flags set to 0.
+pub fn bmff_full_box(box_type: &[u8; 4], version: u8, body: &[u8]) -> Vec<u8> {
+    bmff_box(box_type, &[&[version, 0, 0, 0][..], body].concat())
+}
+
+/// Builds a PNG chunk, the CRC isn't checked by the parser.
+pub fn png_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    [
+        &(data.len() as u32).to_be_bytes()[..],
+        chunk_type,
+        data,
+        &[0; 4],
+    ]
+    .concat()
+}
+
+/// Builds a RIFF chunk of WebP, padded to an even size.
+pub fn webp_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = [chunk_type, &(data.len() as u32).to_le_bytes()[..], data].concat();
+    if data.len() % 2 == 1 {
+        chunk.push(0);
+    }
+    chunk
+}
+
+/// Builds a WebP file of `chunks`.
+pub fn webp_sample(chunks: &[Vec<u8>]) -> Vec<u8> {
+    // Make sure the file is larger than the header parsing buffer of
+    // `MediaSource`
+    let padding = webp_chunk(b"JUNK", &[0; 128]);
+    let body = [b"WEBP".to_vec(), chunks.concat(), padding].concat();
+    [
+        b"RIFF".to_vec(),
+        (body.len() as u32).to_le_bytes().to_vec(),
+        body,
+    ]
+    .concat()
+}
+
+/// Builds a minimal AVIF file which contains an `av01` item and an `Exif`
+/// item. The Exif item is stored in the `idat` box if `in_idat`, otherwise in
+/// the `mdat` box.
+#[allow(unused)]
+pub fn avif_sample(major_brand: &[u8; 4], exif_item: &[u8], in_idat: bool) -> Vec<u8> {
+    let ftyp = bmff_box(
+        b"ftyp",
+        &[&major_brand[..], &[0; 4], b"mif1miafavif"].concat(),
+    );
+    let hdlr = bmff_full_box(b"hdlr", 0, &[&[0; 4][..], b"pict", &[0; 13]].concat());
+    let infe = |id: u16, item_type: &[u8; 4]| {
+        bmff_full_box(
+            b"infe",
+            2,
+            &[&id.to_be_bytes()[..], &[0, 0], item_type, &[0]].concat(),
+        )
+    };
+    let iinf = bmff_full_box(
+        b"iinf",
+        0,
+        &[
+            &2u16.to_be_bytes()[..],
+            &infe(1, b"av01"),
+            &infe(2, b"Exif"),
+        ]
+        .concat(),
+    );
+    let av01 = [0xAA; 32];
+    // Leading bytes in idat, to make sure the item offset is respected
+    let idat = bmff_box(b"idat", &[&[0xFF; 3][..], exif_item].concat());
+
+    // iloc v1: 4 bytes offset & length, no base offset & index
+    let iloc = |av01_offset: u32, exif_offset: u32| {
+        let extent = |method: u16, offset: u32, len: usize| {
+            [
+                &method.to_be_bytes()[..],
+                &[0, 0], // data reference index
+                &1u16.to_be_bytes(),
+                &offset.to_be_bytes(),
+                &(len as u32).to_be_bytes(),
+            ]
+            .concat()
+        };
+        bmff_full_box(
+            b"iloc",
+            1,
+            &[
+                &[0x44, 0x00][..],
+                &2u16.to_be_bytes(),
+                &1u16.to_be_bytes(),
+                &extent(0, av01_offset, av01.len()),
+                &2u16.to_be_bytes(),
+                &extent(in_idat as u16, exif_offset, exif_item.len()),
+            ]
+            .concat(),
+        )
+    };
+    let meta = |iloc: Vec<u8>| {
+        let idat = if in_idat { &idat[..] } else { &[] };
+        bmff_full_box(
+            b"meta",
+            0,
+            &[hdlr.clone(), iinf.clone(), iloc, idat.to_vec()].concat(),
+        )
+    };
+
+    let mdat_offset = (ftyp.len() + meta(iloc(0, 0)).len() + 8) as u32;
+    let (meta, mdat) = if in_idat {
+        (meta(iloc(mdat_offset, 3)), av01.to_vec())
+    } else {
+        let exif_offset = mdat_offset + av01.len() as u32;
+        (
+            meta(iloc(mdat_offset, exif_offset)),
+            [&av01[..], exif_item].concat(),
+        )
+    };
+    // Make sure the file is larger than the header parsing buffer of
+    // `MediaSource`
+    let free = bmff_box(b"free", &[0; 128]);
+
+    [ftyp, meta, bmff_box(b"mdat", &mdat), free].concat()
+}
+
+/// Builds a minimal HEIF file which contains an XMP item (a `mime` item with
+/// content type `application/rdf+xml`) stored in the `mdat` box.
+#[allow(unused)]
+pub fn heif_xmp_sample(packet: &[u8]) -> Vec<u8> {
+    let ftyp = bmff_box(b"ftyp", b"heic\0\0\0\0mif1heic");
+    let infe = bmff_full_box(
+        b"infe",
+        2,
+        &[
+            &1u16.to_be_bytes()[..],
+            &0u16.to_be_bytes(),
+            b"mime",
+            b"XMP\0",
+            b"application/rdf+xml\0",
+        ]
+        .concat(),
+    );
+    let iinf = bmff_full_box(b"iinf", 0, &[&1u16.to_be_bytes()[..], &infe].concat());
+    let meta = |offset: u32| {
+        let iloc = [
+            &[0x44, 0x00][..],
+            &1u16.to_be_bytes(), // item count
+            &1u16.to_be_bytes(), // item ID
+            &0u16.to_be_bytes(), // data reference index
+            &1u16.to_be_bytes(), // extent count
+            &offset.to_be_bytes(),
+            &(packet.len() as u32).to_be_bytes(),
+        ]
+        .concat();
+        bmff_full_box(
+            b"meta",
+            0,
+            &[iinf.clone(), bmff_full_box(b"iloc", 0, &iloc)].concat(),
+        )
+    };
+    let offset = ftyp.len() + meta(0).len() + 8;
+    [ftyp, meta(offset as u32), bmff_box(b"mdat", packet)].concat()
+}
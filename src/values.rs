@@ -1,8 +1,6 @@
 use std::{fmt::Display, string::FromUtf8Error};
 
-use chrono::{
-    offset::LocalResult, DateTime, FixedOffset, Local, NaiveDateTime, Offset, TimeZone as _, Utc,
-};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, Utc};
 
 use nom::{multi::many_m_n, number::Endianness};
 #[cfg(feature = "json_dump")]
@@ -12,7 +10,11 @@ use thiserror::Error;
 use crate::ExifTag;
 
 /// Represent a parsed entry value.
+///
+/// New variants may be added for new data types, so matching on it requires
+/// a wildcard arm.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum EntryValue {
     Text(String),
     URational(URational),
@@ -32,13 +34,58 @@ pub enum EntryValue {
     F64(f64),
 
     Time(DateTime<FixedOffset>),
+    /// A date/time whose UTC offset is unknown, e.g. a `DateTimeOriginal`
+    /// without `OffsetTimeOriginal`.
+    NaiveDateTime(NaiveDateTime),
     Undefined(Vec<u8>),
 
     URationalArray(Vec<URational>),
     IRationalArray(Vec<IRational>),
 
+    U8Array(Vec<u8>),
     U16Array(Vec<u16>),
     U32Array(Vec<u32>),
+    U64Array(Vec<u64>),
+
+    I8Array(Vec<i8>),
+    I16Array(Vec<i16>),
+    I32Array(Vec<i32>),
+    I64Array(Vec<i64>),
+
+    F32Array(Vec<f32>),
+    F64Array(Vec<f64>),
+}
+
+/// The `OffsetTime*` values of the date/time tags.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TimeOffsets {
+    /// `OffsetTime`, for `ModifyDate`
+    pub offset_time: Option<String>,
+    /// `OffsetTimeOriginal`, for `DateTimeOriginal`
+    pub original: Option<String>,
+    /// `OffsetTimeDigitized`, for `CreateDate`
+    pub digitized: Option<String>,
+}
+
+impl TimeOffsets {
+    /// Returns the offset of the date/time `tag`.
+    pub fn get(&self, tag: ExifTag) -> Option<&str> {
+        match tag {
+            ExifTag::ModifyDate => self.offset_time.as_deref(),
+            ExifTag::DateTimeOriginal => self.original.as_deref(),
+            ExifTag::CreateDate => self.digitized.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Fills the missing offsets with the ones of `other`.
+    pub fn or(self, other: TimeOffsets) -> TimeOffsets {
+        TimeOffsets {
+            offset_time: self.offset_time.or(other.offset_time),
+            original: self.original.or(other.original),
+            digitized: self.digitized.or(other.digitized),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -57,9 +104,6 @@ pub(crate) enum ParseEntryError {
 
     #[error("data is invalid: {0}")]
     InvalidData(String),
-
-    #[error("data format is unsupported (please file a bug): {0}")]
-    Unsupported(String),
 }
 
 impl From<chrono::ParseError> for ParseEntryError {
@@ -108,7 +152,14 @@ impl EntryValue {
     /// # Data format
     ///
     /// See: [`DataFormat`].
-    pub(crate) fn parse(entry: &EntryData, tz: &Option<String>) -> Result<EntryValue, Error> {
+    ///
+    /// # Date/time
+    ///
+    /// `DateTimeOriginal`, `CreateDate` & `ModifyDate` are parsed as
+    /// [`EntryValue::Time`] with the offset of their own `OffsetTime*` tag
+    /// (see [`TimeOffsets`]), or as [`EntryValue::NaiveDateTime`] if the
+    /// offset is missing or invalid.
+    pub(crate) fn parse(entry: &EntryData, offsets: &TimeOffsets) -> Result<EntryValue, Error> {
         if entry.data.is_empty() {
             return Err(Error::InvalidData(
                 "invalid DirectoryEntry: entry data is empty".into(),
@@ -139,33 +190,34 @@ impl EntryValue {
                 // }
                 let s = get_cstr(data).map_err(|e| Error::InvalidData(e.to_string()))?;
 
-                let t = if let Some(tz) = tz {
-                    let s = format!("{s} {tz}");
-                    DateTime::parse_from_str(&s, "%Y:%m:%d %H:%M:%S %z")?
-                } else {
-                    let t = NaiveDateTime::parse_from_str(&s, "%Y:%m:%d %H:%M:%S")?;
-                    let t = Local.from_local_datetime(&t);
-                    let t = if let LocalResult::Single(t) = t {
-                        Ok(t)
-                    } else {
-                        Err(Error::InvalidData(format!("parse time failed: {s}")))
-                    }?;
-
-                    t.with_timezone(t.offset())
-                };
-
-                return Ok(EntryValue::Time(t));
+                let t = NaiveDateTime::parse_from_str(&s, "%Y:%m:%d %H:%M:%S")?;
+                let t = offsets
+                    .get(tag)
+                    .and_then(|tz| {
+                        let s = format!("{s} {tz}");
+                        DateTime::parse_from_str(&s, "%Y:%m:%d %H:%M:%S %z").ok()
+                    })
+                    .map_or(EntryValue::NaiveDateTime(t), EntryValue::Time);
+
+                return Ok(t);
+            }
+
+            if let Some(s) = decode_encoded_text(tag, data_format, data, endian) {
+                return Ok(EntryValue::Text(s));
             }
         }
 
         match data_format {
             DataFormat::U8 => match components_num {
                 1 => Ok(Self::U8(data[0])),
-                x => Err(Error::Unsupported(format!(
-                    "usigned byte with {x} components"
-                ))),
+                x => Ok(Self::U8Array(parse_array(
+                    data,
+                    x,
+                    nom::number::complete::u8,
+                    "U8Array",
+                )?)),
             },
-            DataFormat::Text => Ok(EntryValue::Text(
+            DataFormat::Text | DataFormat::Utf8 => Ok(EntryValue::Text(
                 get_cstr(data).map_err(|e| Error::InvalidData(e.to_string()))?,
             )),
             DataFormat::U16 => {
@@ -183,7 +235,7 @@ impl EntryValue {
                     Ok(Self::U16Array(v))
                 }
             }
-            DataFormat::U32 => {
+            DataFormat::U32 | DataFormat::Ifd => {
                 if components_num == 1 {
                     Ok(Self::U32(u32::try_from_bytes(data, endian)?))
                 } else {
@@ -208,22 +260,31 @@ impl EntryValue {
             }
             DataFormat::I8 => match components_num {
                 1 => Ok(Self::I8(data[0] as i8)),
-                x => Err(Error::Unsupported(format!(
-                    "signed byte with {x} components"
-                ))),
+                x => Ok(Self::I8Array(parse_array(
+                    data,
+                    x,
+                    nom::number::complete::i8,
+                    "I8Array",
+                )?)),
             },
             DataFormat::Undefined => Ok(Self::Undefined(data.to_vec())),
             DataFormat::I16 => match components_num {
                 1 => Ok(Self::I16(i16::try_from_bytes(data, endian)?)),
-                x => Err(Error::Unsupported(format!(
-                    "signed short with {x} components"
-                ))),
+                x => Ok(Self::I16Array(parse_array(
+                    data,
+                    x,
+                    nom::number::complete::i16(endian),
+                    "I16Array",
+                )?)),
             },
             DataFormat::I32 => match components_num {
                 1 => Ok(Self::I32(i32::try_from_bytes(data, endian)?)),
-                x => Err(Error::Unsupported(format!(
-                    "signed long with {x} components"
-                ))),
+                x => Ok(Self::I32Array(parse_array(
+                    data,
+                    x,
+                    nom::number::complete::i32(endian),
+                    "I32Array",
+                )?)),
             },
             DataFormat::IRational => {
                 let rationals = entry.try_as_rationals::<i32>()?;
@@ -235,19 +296,59 @@ impl EntryValue {
             }
             DataFormat::F32 => match components_num {
                 1 => Ok(Self::F32(f32::try_from_bytes(data, endian)?)),
-                x => Err(Error::Unsupported(format!("float with {x} components"))),
+                x => Ok(Self::F32Array(parse_array(
+                    data,
+                    x,
+                    nom::number::complete::f32(endian),
+                    "F32Array",
+                )?)),
             },
             DataFormat::F64 => match components_num {
                 1 => Ok(Self::F64(f64::try_from_bytes(data, endian)?)),
-                x => Err(Error::Unsupported(format!("double with {x} components"))),
+                x => Ok(Self::F64Array(parse_array(
+                    data,
+                    x,
+                    nom::number::complete::f64(endian),
+                    "F64Array",
+                )?)),
             },
+            DataFormat::U64 | DataFormat::Ifd64 => {
+                let (_, v) = many_m_n::<_, _, nom::error::Error<_>, _>(
+                    components_num as usize,
+                    components_num as usize,
+                    nom::number::complete::u64(endian),
+                )(data)
+                .map_err(|e| {
+                    ParseEntryError::InvalidData(format!("parse U64Array error: {e:?}"))
+                })?;
+                if v.len() == 1 {
+                    Ok(Self::U64(v[0]))
+                } else {
+                    Ok(Self::U64Array(v))
+                }
+            }
+            DataFormat::I64 => {
+                let (_, v) = many_m_n::<_, _, nom::error::Error<_>, _>(
+                    components_num as usize,
+                    components_num as usize,
+                    nom::number::complete::i64(endian),
+                )(data)
+                .map_err(|e| {
+                    ParseEntryError::InvalidData(format!("parse I64Array error: {e:?}"))
+                })?;
+                if v.len() == 1 {
+                    Ok(Self::I64(v[0]))
+                } else {
+                    Ok(Self::I64Array(v))
+                }
+            }
         }
     }
 
     fn variant_default(data_format: DataFormat) -> EntryValue {
         match data_format {
             DataFormat::U8 => Self::U8(0),
-            DataFormat::Text => Self::Text(String::default()),
+            DataFormat::Text | DataFormat::Utf8 => Self::Text(String::default()),
             DataFormat::U16 => Self::U16(0),
             DataFormat::U32 => Self::U32(0),
             DataFormat::URational => Self::URational(URational::default()),
@@ -258,6 +359,9 @@ impl EntryValue {
             DataFormat::IRational => Self::IRational(IRational::default()),
             DataFormat::F32 => Self::F32(0.0),
             DataFormat::F64 => Self::F64(0.0),
+            DataFormat::Ifd => Self::U32(0),
+            DataFormat::U64 | DataFormat::Ifd64 => Self::U64(0),
+            DataFormat::I64 => Self::I64(0),
         }
     }
 
@@ -275,6 +379,16 @@ impl EntryValue {
         }
     }
 
+    /// Returns the date/time without offset of a [`EntryValue::NaiveDateTime`]
+    /// or a [`EntryValue::Time`] (its local date/time).
+    pub fn as_naive_datetime(&self) -> Option<NaiveDateTime> {
+        match self {
+            EntryValue::NaiveDateTime(v) => Some(*v),
+            EntryValue::Time(v) => Some(v.naive_local()),
+            _ => None,
+        }
+    }
+
     pub fn as_u8(&self) -> Option<u8> {
         match self {
             EntryValue::U8(v) => Some(*v),
@@ -355,6 +469,22 @@ impl EntryValue {
             None
         }
     }
+
+    /// Numeric value of an integer or rational entry, `None` if the
+    /// denominator is zero.
+    pub(crate) fn to_f64(&self) -> Option<f64> {
+        let n = match self {
+            EntryValue::URational(r) => r.as_float(),
+            EntryValue::IRational(r) => r.as_float(),
+            EntryValue::U8(v) => *v as f64,
+            EntryValue::U16(v) => *v as f64,
+            EntryValue::U32(v) => *v as f64,
+            EntryValue::I16(v) => *v as f64,
+            EntryValue::I32(v) => *v as f64,
+            _ => return None,
+        };
+        n.is_finite().then_some(n)
+    }
 }
 
 /// # Exif Data format
@@ -369,8 +499,16 @@ impl EntryValue {
 /// |-----------------+---------------+---------------+----------------+-----------------+-------------------+--------------|
 /// | Format          |     undefined |  signed short |    signed long | signed rational |      single float | double float |
 /// | Bytes/component |             1 |             2 |              4 |               8 |                 4 |            8 |
+///
+/// | Value           |            13 |            16 |             17 |              18 |
+/// |-----------------+---------------+---------------+----------------+-----------------|
+/// | Format          |    IFD offset | unsigned long8|   signed long8 |     IFD8 offset |
+/// | Bytes/component |             4 |             8 |              8 |               8 |
 /// ```
 ///
+/// 16, 17 & 18 are defined by BigTIFF, 129 (UTF-8 strings) is defined by
+/// Exif 3.0.
+///
 /// See: [Exif](https://www.media.mit.edu/pia/Research/deepview/exif.html).
 #[repr(u16)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -388,15 +526,21 @@ pub(crate) enum DataFormat {
     IRational = 10,
     F32 = 11,
     F64 = 12,
+    Ifd = 13,
+    U64 = 16,
+    I64 = 17,
+    Ifd64 = 18,
+    Utf8 = 129,
 }
 
 impl DataFormat {
     pub fn component_size(&self) -> usize {
         match self {
-            Self::U8 | Self::I8 | Self::Text | Self::Undefined => 1,
+            Self::U8 | Self::I8 | Self::Text | Self::Undefined | Self::Utf8 => 1,
             Self::U16 | Self::I16 => 2,
-            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U32 | Self::I32 | Self::F32 | Self::Ifd => 4,
             Self::URational | Self::IRational | Self::F64 => 8,
+            Self::U64 | Self::I64 | Self::Ifd64 => 8,
         }
     }
 }
@@ -404,7 +548,10 @@ impl DataFormat {
 impl TryFrom<u16> for DataFormat {
     type Error = Error;
     fn try_from(v: u16) -> Result<Self, Self::Error> {
-        if v >= Self::U8 as u16 && v <= Self::F64 as u16 {
+        if (v >= Self::U8 as u16 && v <= Self::Ifd as u16)
+            || (v >= Self::U64 as u16 && v <= Self::Ifd64 as u16)
+            || v == Self::Utf8 as u16
+        {
             Ok(unsafe { std::mem::transmute::<u16, Self>(v) })
         } else {
             Err(Error::InvalidData(format!("data format {v}")))
@@ -443,6 +590,7 @@ impl Display for EntryValue {
             EntryValue::U8(v) => Display::fmt(&v, f),
             EntryValue::I8(v) => Display::fmt(&v, f),
             EntryValue::Time(v) => Display::fmt(&v.to_rfc3339(), f),
+            EntryValue::NaiveDateTime(v) => Display::fmt(&v.format("%Y-%m-%dT%H:%M:%S%.f"), f),
             EntryValue::Undefined(v) => {
                 // Display up to MAX_DISPLAY_NUM components, and replace the rest with ellipsis
                 const MAX_DISPLAY_NUM: usize = 8;
@@ -468,8 +616,16 @@ impl Display for EntryValue {
             EntryValue::IRationalArray(v) => {
                 format!("IRationalArray[{}]", rationals_to_string::<i32>(v)).fmt(f)
             }
+            EntryValue::U8Array(v) => array_to_string("U8Array", v, f),
             EntryValue::U32Array(v) => array_to_string("U32Array", v, f),
             EntryValue::U16Array(v) => array_to_string("U16Array", v, f),
+            EntryValue::U64Array(v) => array_to_string("U64Array", v, f),
+            EntryValue::I8Array(v) => array_to_string("I8Array", v, f),
+            EntryValue::I16Array(v) => array_to_string("I16Array", v, f),
+            EntryValue::I32Array(v) => array_to_string("I32Array", v, f),
+            EntryValue::I64Array(v) => array_to_string("I64Array", v, f),
+            EntryValue::F32Array(v) => array_to_string("F32Array", v, f),
+            EntryValue::F64Array(v) => array_to_string("F64Array", v, f),
         }
     }
 }
@@ -525,6 +681,12 @@ impl From<DateTime<FixedOffset>> for EntryValue {
     }
 }
 
+impl From<NaiveDateTime> for EntryValue {
+    fn from(value: NaiveDateTime) -> Self {
+        EntryValue::NaiveDateTime(value)
+    }
+}
+
 impl From<u8> for EntryValue {
     fn from(value: u8) -> Self {
         EntryValue::U8(value)
@@ -662,6 +824,80 @@ fn get_cstr(data: &[u8]) -> std::result::Result<String, FromUtf8Error> {
     )
 }
 
+fn parse_array<'a, T, P>(
+    data: &'a [u8],
+    components_num: u32,
+    parser: P,
+    name: &str,
+) -> Result<Vec<T>, Error>
+where
+    P: FnMut(&'a [u8]) -> nom::IResult<&'a [u8], T>,
+{
+    let (_, v) = many_m_n(components_num as usize, components_num as usize, parser)(data)
+        .map_err(|e| Error::InvalidData(format!("parse {name} error: {e:?}")))?;
+    Ok(v)
+}
+
+/// Decode the text of tags which are not stored as ASCII strings. Returns
+/// `None` if it's not such a tag, or the text can't be decoded, in which case
+/// the entry is parsed according to its data format.
+fn decode_encoded_text(
+    tag: ExifTag,
+    data_format: DataFormat,
+    data: &[u8],
+    endian: Endianness,
+) -> Option<String> {
+    match tag {
+        // Written by Windows, UCS-2 (little endian) text stored as bytes
+        ExifTag::XPTitle
+        | ExifTag::XPComment
+        | ExifTag::XPAuthor
+        | ExifTag::XPKeywords
+        | ExifTag::XPSubject
+            if data_format == DataFormat::U8 =>
+        {
+            Some(decode_ucs2(data, Endianness::Little))
+        }
+        ExifTag::UserComment if data_format == DataFormat::Undefined => {
+            decode_user_comment(data, endian)
+        }
+        _ => None,
+    }
+}
+
+/// The first 8 bytes of `UserComment` identify the character code of the
+/// comment. JIS encoded comments are left undecoded.
+fn decode_user_comment(data: &[u8], endian: Endianness) -> Option<String> {
+    let (code, text) = data.split_at_checked(8)?;
+    let s = match code {
+        // Undefined character code, which is mostly ASCII in practice
+        b"ASCII\0\0\0" | [0, 0, 0, 0, 0, 0, 0, 0] => get_cstr(text).ok()?,
+        b"UNICODE\0" => {
+            // Follows the byte order of the TIFF header, unless there is a BOM
+            match text {
+                [0xFF, 0xFE, rest @ ..] => decode_ucs2(rest, Endianness::Little),
+                [0xFE, 0xFF, rest @ ..] => decode_ucs2(rest, Endianness::Big),
+                _ => decode_ucs2(text, endian),
+            }
+        }
+        _ => return None,
+    };
+    Some(s.trim_end_matches(' ').to_owned())
+}
+
+fn decode_ucs2(data: &[u8], endian: Endianness) -> String {
+    let units = data
+        .chunks_exact(2)
+        .map(|x| match endian {
+            Endianness::Little => u16::from_le_bytes([x[0], x[1]]),
+            _ => u16::from_be_bytes([x[0], x[1]]),
+        })
+        .take_while(|x| *x != 0);
+    char::decode_utf16(units)
+        .map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
 pub(crate) trait TryFromBytes: Sized {
     fn try_from_bytes(bs: &[u8], endian: Endianness) -> Result<Self, Error>;
 }
@@ -863,25 +1099,107 @@ pub(crate) fn decode_rational<T: TryFromBytes>(
 
 #[cfg(test)]
 mod tests {
-    use chrono::{Local, NaiveDateTime, TimeZone};
+    use chrono::NaiveDateTime;
+    use test_case::test_case;
 
     use super::*;
 
-    #[test]
-    fn test_parse_time() {
-        let tz = Local::now().format("%:z").to_string();
+    fn parse(
+        tag: u16,
+        data_format: DataFormat,
+        components_num: u32,
+        data: &[u8],
+        endian: Endianness,
+    ) -> EntryValue {
+        let entry = EntryData {
+            endian,
+            tag,
+            data,
+            data_format,
+            components_num,
+        };
+        EntryValue::parse(&entry, &TimeOffsets::default()).unwrap()
+    }
 
-        let s = format!("2023:07:09 20:36:33 {tz}");
-        let t1 = DateTime::parse_from_str(&s, "%Y:%m:%d %H:%M:%S %z").unwrap();
+    #[test_case(ExifTag::GPSVersionID, DataFormat::U8, 4, b"\x02\x02\0\0", EntryValue::U8Array(vec![2, 2, 0, 0]))]
+    #[test_case(ExifTag::XPTitle, DataFormat::U8, 8, b"a\0b\0\x2d\x4e\0\0", "ab中".into())]
+    #[test_case(ExifTag::UserComment, DataFormat::Undefined, 13, b"ASCII\0\0\0abc  ", "abc".into())]
+    #[test_case(ExifTag::UserComment, DataFormat::Undefined, 11, b"\0\0\0\0\0\0\0\0abc", "abc".into())]
+    #[test_case(ExifTag::UserComment, DataFormat::Undefined, 12, b"UNICODE\0\0a\0b", "ab".into())]
+    #[test_case(ExifTag::UserComment, DataFormat::Undefined, 12, b"UNICODE\0\xff\xfea\0", "a".into())]
+    #[test_case(ExifTag::UserComment, DataFormat::Undefined, 9, b"JIS\0\0\0\0\0a", EntryValue::Undefined(b"JIS\0\0\0\0\0a".to_vec()))]
+    #[test_case(ExifTag::UserComment, DataFormat::Undefined, 3, b"abc", EntryValue::Undefined(b"abc".to_vec()))]
+    #[test_case(ExifTag::ImageDescription, DataFormat::Utf8, 7, "中文\0".as_bytes(), "中文".into())]
+    fn parse_entry(
+        tag: ExifTag,
+        data_format: DataFormat,
+        components_num: u32,
+        data: &[u8],
+        expect: EntryValue,
+    ) {
+        let value = parse(
+            tag.code(),
+            data_format,
+            components_num,
+            data,
+            Endianness::Big,
+        );
+        assert_eq!(value, expect);
+    }
 
-        let s = "2023:07:09 20:36:33";
-        let t2 = NaiveDateTime::parse_from_str(s, "%Y:%m:%d %H:%M:%S").unwrap();
-        let t2 = Local.from_local_datetime(&t2).unwrap();
+    #[test_case(DataFormat::I16, 2, b"\xff\xfe\0\x02", EntryValue::I16Array(vec![-2, 2]))]
+    #[test_case(DataFormat::I32, 2, b"\xff\xff\xff\xfe\0\0\0\x02", EntryValue::I32Array(vec![-2, 2]))]
+    #[test_case(DataFormat::F32, 2, b"\x3f\xc0\0\0\xc0\0\0\0", EntryValue::F32Array(vec![1.5, -2.0]))]
+    #[test_case(DataFormat::F64, 1, b"\x3f\xf8\0\0\0\0\0\0", EntryValue::F64(1.5))]
+    fn parse_numbers(
+        data_format: DataFormat,
+        components_num: u32,
+        data: &[u8],
+        expect: EntryValue,
+    ) {
+        let value = parse(0xfe00, data_format, components_num, data, Endianness::Big);
+        assert_eq!(value, expect);
+    }
 
-        let t3 = t2.with_timezone(t2.offset());
+    #[test]
+    fn parse_array_too_short() {
+        let entry = EntryData {
+            endian: Endianness::Big,
+            tag: 0xfe00,
+            data: &[0, 1, 2],
+            data_format: DataFormat::I16,
+            components_num: 2,
+        };
+        EntryValue::parse(&entry, &TimeOffsets::default()).unwrap_err();
+    }
 
-        assert_eq!(t1, t2);
-        assert_eq!(t1, t3);
+    #[test_case(ExifTag::DateTimeOriginal, "2023-07-09T20:36:33+08:00")]
+    #[test_case(ExifTag::CreateDate, "2023-07-09T20:36:33-05:30")]
+    #[test_case(ExifTag::ModifyDate, "2023-07-09T20:36:33")]
+    fn test_parse_time(tag: ExifTag, expect: &str) {
+        let offsets = TimeOffsets {
+            offset_time: Some("   :  ".to_owned()),
+            original: Some("+08:00".to_owned()),
+            digitized: Some("-05:30".to_owned()),
+        };
+        let entry = EntryData {
+            endian: Endianness::Big,
+            tag: tag.code(),
+            data: b"2023:07:09 20:36:33\0",
+            data_format: DataFormat::Text,
+            components_num: 20,
+        };
+        let value = EntryValue::parse(&entry, &offsets).unwrap();
+        assert_eq!(value.to_string(), expect);
+        assert_eq!(
+            value.as_naive_datetime().unwrap(),
+            NaiveDateTime::parse_from_str("2023:07:09 20:36:33", "%Y:%m:%d %H:%M:%S").unwrap()
+        );
+
+        // Never depends on the local time zone
+        let value = EntryValue::parse(&entry, &TimeOffsets::default()).unwrap();
+        assert_eq!(value.to_string(), "2023-07-09T20:36:33");
+        assert!(value.as_time().is_none());
     }
 
     #[test]
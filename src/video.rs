@@ -40,6 +40,10 @@ pub enum TrackInfoTag {
     /// Its value is an `EntryValue::U32`.
     ImageHeight,
 
+    /// Clockwise rotation in degrees (0, 90, 180 or 270) to apply when
+    /// displaying the video, its value is an `EntryValue::U32`.
+    Rotation,
+
     /// Its value is an `EntryValue::Text`, location presented in ISO6709.
     ///
     /// If you need a parsed [`GPSInfo`] which provides more detailed GPS info,
@@ -143,14 +147,15 @@ pub(crate) fn parse_track_info(
     let mut info: TrackInfo = match mime_video {
         crate::file::MimeVideo::QuickTime
         | crate::file::MimeVideo::_3gpp
-        | crate::file::MimeVideo::Mp4 => {
+        | crate::file::MimeVideo::Mp4
+        | crate::file::MimeVideo::M4a => {
             let range = extract_moov_body_from_buf(input)?;
             let moov_body = &input[range];
 
             match mime_video {
                 MimeVideo::QuickTime => parse_qt(moov_body)?.into(),
 
-                MimeVideo::Mp4 | MimeVideo::_3gpp => parse_mp4(moov_body)?.into(),
+                MimeVideo::Mp4 | MimeVideo::_3gpp | MimeVideo::M4a => parse_mp4(moov_body)?.into(),
                 _ => unreachable!(),
             }
         }
@@ -201,6 +206,7 @@ impl From<TrackInfoTag> for &str {
             TrackInfoTag::DurationMs => "DurationMs",
             TrackInfoTag::ImageWidth => "ImageWidth",
             TrackInfoTag::ImageHeight => "ImageHeight",
+            TrackInfoTag::Rotation => "Rotation",
             TrackInfoTag::GpsIso6709 => "GpsIso6709",
         }
     }
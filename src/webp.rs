@@ -0,0 +1,242 @@
+use crate::{
+    error::ParsingError,
+    exif::EXIF_IDENT,
+    file::{Mime, MimeImage},
+};
+
+const RIFF_HEADER_SIZE: usize = 12;
+// `EXIF` flag in the first byte of the `VP8X` chunk
+const VP8X_EXIF_FLAG: u8 = 0x08;
+
+/// Represents the basic image information of a WebP file.
+///
+/// The canvas size comes from the `VP8X` chunk for the extended file format,
+/// or from the `VP8 `/`VP8L` bitstream header for the simple file formats.
+///
+/// Exif data (`EXIF` chunk) and XMP data (`XMP ` chunk) can be parsed by
+/// using [`crate::ExifIter`] and [`crate::Xmp`] as parse output.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::file_path("./testdata/exif.webp")?;
+///     let info: WebpInfo = parser.parse(ms)?;
+///
+///     assert_eq!((info.width(), info.height()), (1, 1));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebpInfo {
+    width: u32,
+    height: u32,
+}
+
+impl WebpInfo {
+    /// Canvas width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Canvas height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+pub(crate) fn check_webp(input: &[u8]) -> crate::Result<()> {
+    if input.len() >= RIFF_HEADER_SIZE && &input[..4] == b"RIFF" && &input[8..12] == b"WEBP" {
+        Ok(())
+    } else {
+        Err("invalid WebP file; RIFF/WEBP header not found".into())
+    }
+}
+
+/// Walks through the RIFF chunks in `input` until a chunk in `types` is found,
+/// then returns (chunk type, chunk data) of it. Data of other chunks is
+/// skipped without being loaded.
+///
+/// `input` starts with either the RIFF header, or a chunk (after a
+/// `ClearAndSkip`). Since there is no terminating chunk, reaching the end of
+/// the file results in a `Need` error.
+fn find_chunk<'a>(
+    input: &'a [u8],
+    types: &[&[u8; 4]],
+) -> Result<(&'a [u8], &'a [u8]), ParsingError> {
+    let mut pos = if check_webp(input).is_ok() {
+        RIFF_HEADER_SIZE
+    } else {
+        0
+    };
+    loop {
+        let Some(header) = input.get(pos..pos + 8) else {
+            return Err(ParsingError::Need(pos + 8 - input.len()));
+        };
+        let chunk_type = &header[..4];
+        let size = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+
+        let data_start = pos + 8;
+        // chunks are padded to even sizes
+        let end = data_start + size + (size & 1);
+        if types.iter().any(|x| &x[..] == chunk_type) {
+            let Some(data) = input.get(data_start..data_start + size) else {
+                return Err(ParsingError::Need(data_start + size - input.len()));
+            };
+            return Ok((chunk_type, data));
+        } else if end > input.len() {
+            // skip unused chunk data
+            return Err(ParsingError::ClearAndSkip(end));
+        }
+        pos = end;
+    }
+}
+
+/// Extract Exif TIFF data from the `EXIF` chunk.
+pub(crate) fn extract_exif_data(input: &[u8]) -> Result<Option<&[u8]>, ParsingError> {
+    // The simple file formats (`VP8 `/`VP8L`) can't have an `EXIF` chunk,
+    // and the extended one declares it in the `VP8X` flags. Without this
+    // check, a file without Exif would be read to the end in vain.
+    if check_webp(input).is_ok() {
+        let (chunk_type, data) = find_chunk(input, &[b"VP8X", b"VP8 ", b"VP8L"])?;
+        if chunk_type != b"VP8X" || data.first().map_or(true, |x| x & VP8X_EXIF_FLAG == 0) {
+            return Ok(None);
+        }
+    }
+
+    let (_, data) = find_chunk(input, &[b"EXIF"])?;
+    // Some writers keep the Exif identifier of JPEG APP1
+    Ok(Some(
+        data.strip_prefix(EXIF_IDENT.as_bytes()).unwrap_or(data),
+    ))
+}
+
+/// Extract the XMP packet from the `XMP ` chunk.
+pub(crate) fn extract_xmp_data(input: &[u8]) -> Result<Option<&[u8]>, ParsingError> {
+    let (_, data) = find_chunk(input, &[b"XMP "])?;
+    Ok(Some(data))
+}
+
+pub(crate) fn extract_webp_info(mime: Mime, input: &[u8]) -> Result<WebpInfo, ParsingError> {
+    if mime != Mime::Image(MimeImage::Webp) {
+        return Err(ParsingError::Failed(format!(
+            "WebpInfo is not supported for {mime:?}"
+        )));
+    }
+
+    extract_canvas_size(input).map(|(width, height)| WebpInfo { width, height })
+}
+
+/// Extract the canvas size (width, height) from the `VP8X` chunk or the
+/// bitstream header of the simple file formats.
+pub(crate) fn extract_canvas_size(input: &[u8]) -> Result<(u32, u32), ParsingError> {
+    let (chunk_type, data) = find_chunk(input, &[b"VP8X", b"VP8 ", b"VP8L"])?;
+    parse_canvas_size(chunk_type, data)
+        .ok_or_else(|| ParsingError::Failed("invalid WebP bitstream header".into()))
+}
+
+fn parse_canvas_size(chunk_type: &[u8], data: &[u8]) -> Option<(u32, u32)> {
+    let le_u24 = |x: &[u8]| u32::from_le_bytes([x[0], x[1], x[2], 0]);
+    match chunk_type {
+        b"VP8X" if data.len() >= 10 => Some((le_u24(&data[4..7]) + 1, le_u24(&data[7..10]) + 1)),
+        // frame tag (3 bytes), start code (0x9D 0x01 0x2A), then 14 bits
+        // width & height
+        b"VP8 " if data.len() >= 10 && data[3..6] == [0x9D, 0x01, 0x2A] => {
+            let width = u16::from_le_bytes([data[6], data[7]]) & 0x3FFF;
+            let height = u16::from_le_bytes([data[8], data[9]]) & 0x3FFF;
+            Some((width.into(), height.into()))
+        }
+        // signature (0x2F), then 14 bits width - 1 & height - 1
+        b"VP8L" if data.len() >= 5 && data[0] == 0x2F => {
+            let bits = u32::from_le_bytes(data[1..5].try_into().unwrap());
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::testkit::{open_sample, parse_sample, read_sample, webp_chunk, webp_sample};
+    use crate::{ExifIter, ExifTag, ExifWriter, IfdGroup, MediaParser, MediaSource, Xmp};
+    use test_case::test_case;
+
+    #[test]
+    fn webp_exif_xmp() {
+        let mut parser = MediaParser::new();
+
+        let ms = MediaSource::file(open_sample("exif.webp").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(
+            exif.get(ExifTag::Model).unwrap().to_string(),
+            "vivo X90 Pro+"
+        );
+
+        let ms = MediaSource::unseekable(open_sample("exif.webp").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(
+            exif.get(ExifTag::Model).unwrap().to_string(),
+            "vivo X90 Pro+"
+        );
+
+        let ms = MediaSource::unseekable(open_sample("exif.webp").unwrap()).unwrap();
+        let xmp: Xmp = parser.parse(ms).unwrap();
+        assert_eq!(
+            xmp.get(Xmp::NS_XMP, "CreatorTool").unwrap().as_str(),
+            Some("nom-exif test")
+        );
+    }
+
+    // Large chunks before the wanted chunk are skipped, both for seekable &
+    // unseekable sources.
+    #[test_case(10, true)]
+    #[test_case(1024 * 1024 + 1, true)]
+    #[test_case(1024 * 1024 + 1, false)]
+    fn webp_exif_skip(size: usize, seekable: bool) {
+        let mut writer = ExifWriter::new();
+        writer.set(IfdGroup::Ifd0, ExifTag::Make, "abc".into());
+        let tiff = writer.write().unwrap();
+        let exif = [EXIF_IDENT.as_bytes(), &tiff].concat();
+
+        let data = webp_sample(&[
+            webp_chunk(b"VP8X", &[0x08, 0, 0, 0, 99, 0, 0, 49, 0, 0]),
+            webp_chunk(b"ALPH", &vec![0; size]),
+            webp_chunk(b"VP8L", &[0x2F, 0, 0, 0, 0]),
+            webp_chunk(b"EXIF", &exif),
+        ]);
+
+        let mut parser = MediaParser::new();
+        let mut iter: ExifIter = if seekable {
+            parser.parse(MediaSource::seekable(Cursor::new(data)).unwrap())
+        } else {
+            parser.parse(MediaSource::unseekable(Cursor::new(data)).unwrap())
+        }
+        .unwrap();
+        let make = iter.next().unwrap();
+        assert_eq!(make.get_value().unwrap().to_string(), "abc");
+    }
+
+    #[test_case(b"VP8X", vec![0, 0, 0, 0, 0x7F, 0x07, 0, 0x37, 0x04, 0], (1920, 1080))]
+    #[test_case(b"VP8 ", vec![0, 0, 0, 0x9D, 0x01, 0x2A, 0x80, 0x02, 0xE0, 0x41], (640, 480))]
+    #[test_case(b"VP8L", vec![0x2F, 0x7F, 0xC2, 0x0D, 0x00], (640, 56))]
+    fn webp_canvas_size(chunk_type: &[u8; 4], data: Vec<u8>, size: (u32, u32)) {
+        let info: WebpInfo = parse_sample(webp_sample(&[webp_chunk(chunk_type, &data)])).unwrap();
+        assert_eq!((info.width(), info.height()), size);
+    }
+
+    #[test]
+    fn webp_no_data() {
+        let data = webp_sample(&[webp_chunk(b"VP8L", &[0x2F, 0, 0, 0, 0])]);
+        parse_sample::<ExifIter>(data.clone()).unwrap_err();
+        parse_sample::<Xmp>(data).unwrap_err();
+        parse_sample::<WebpInfo>(read_sample("exif.png").unwrap()).unwrap_err();
+    }
+}
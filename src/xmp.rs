@@ -0,0 +1,510 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    error::ParsingError,
+    exif::extract_tiff_tag_data,
+    file::{Mime, MimeImage, MimeVideo},
+    heif, jpeg, jxl, mov, png, webp,
+};
+
+mod xml;
+
+use xml::Element;
+
+const NS_RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const NS_XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// The name of an XMP property, a local name qualified by its namespace URI.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct XmpName {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl XmpName {
+    pub fn new(namespace: &str, name: &str) -> Self {
+        Self {
+            namespace: namespace.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl Display for XmpName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.namespace, self.name)
+    }
+}
+
+/// Represents the value of an XMP property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum XmpValue {
+    /// A simple value, URIs (`rdf:resource`) are presented as text too.
+    Text(String),
+
+    /// An ordered array (`rdf:Seq`).
+    Seq(Vec<XmpValue>),
+
+    /// An unordered array (`rdf:Bag`).
+    Bag(Vec<XmpValue>),
+
+    /// An alternative array (`rdf:Alt`), each item comes with its `xml:lang`
+    /// qualifier, if any.
+    Alt(Vec<(Option<String>, XmpValue)>),
+
+    /// A structure, its fields are XMP properties too.
+    Struct(BTreeMap<XmpName, XmpValue>),
+}
+
+impl XmpValue {
+    /// Returns the text if this is a simple value; or the default item (i.e.
+    /// `x-default`, or the first one) if this is an `rdf:Alt`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            XmpValue::Text(s) => Some(s),
+            XmpValue::Alt(items) => items
+                .iter()
+                .find(|x| x.0.as_deref() == Some("x-default"))
+                .or(items.first())
+                .and_then(|x| x.1.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns the items if this is an `rdf:Seq` or `rdf:Bag`.
+    pub fn as_array(&self) -> Option<&[XmpValue]> {
+        match self {
+            XmpValue::Seq(v) | XmpValue::Bag(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the fields if this is a structure.
+    pub fn as_struct(&self) -> Option<&BTreeMap<XmpName, XmpValue>> {
+        match self {
+            XmpValue::Struct(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a parsed XMP packet.
+///
+/// XMP properties are parsed from the RDF data into a map, which is keyed by
+/// [`XmpName`]s (namespace URI + local name). If the packet has been split
+/// into an Extended XMP (JPEG only), the properties of the extension are
+/// merged in.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let packet = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+///       <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+///         <rdf:Description xmlns:xmp="http://ns.adobe.com/xap/1.0/" xmp:Rating="5"/>
+///       </rdf:RDF>
+///     </x:xmpmeta>"#;
+///
+///     let xmp: Xmp = packet.parse()?;
+///     assert_eq!(xmp.get(Xmp::NS_XMP, "Rating").unwrap().as_str(), Some("5"));
+///     Ok(())
+/// }
+/// ```
+///
+/// It can also be parsed from a [`crate::MediaSource`] by a
+/// [`crate::MediaParser`], see [`crate::MediaParser::parse`]. The packet is
+/// searched for in:
+///
+/// - JPEG: `APP1` segments (including Extended XMP).
+/// - TIFF: tag `0x02BC` in IFD0.
+/// - HEIF/HEIC: the `mime` item with content type `application/rdf+xml`.
+/// - QuickTime/MP4: the top level XMP `uuid` box, or `moov/udta/XMP_`.
+///
+/// WebM/Matroska files are not supported, since there is no standard place
+/// for XMP packets in them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xmp {
+    packet: String,
+    extended_packet: Option<String>,
+    properties: BTreeMap<XmpName, XmpValue>,
+}
+
+impl Xmp {
+    /// Dublin Core namespace
+    pub const NS_DC: &'static str = "http://purl.org/dc/elements/1.1/";
+    /// XMP basic namespace
+    pub const NS_XMP: &'static str = "http://ns.adobe.com/xap/1.0/";
+    /// XMP media management namespace
+    pub const NS_XMP_MM: &'static str = "http://ns.adobe.com/xap/1.0/mm/";
+    /// Exif namespace
+    pub const NS_EXIF: &'static str = "http://ns.adobe.com/exif/1.0/";
+    /// TIFF namespace
+    pub const NS_TIFF: &'static str = "http://ns.adobe.com/tiff/1.0/";
+    /// Photoshop namespace
+    pub const NS_PHOTOSHOP: &'static str = "http://ns.adobe.com/photoshop/1.0/";
+
+    /// Get the value of property `name` in `namespace`.
+    pub fn get(&self, namespace: &str, name: &str) -> Option<&XmpValue> {
+        self.properties.get(&XmpName::new(namespace, name))
+    }
+
+    /// Iterate over all top level properties.
+    pub fn iter(&self) -> impl Iterator<Item = (&XmpName, &XmpValue)> {
+        self.properties.iter()
+    }
+
+    /// The raw XMP packet.
+    pub fn packet(&self) -> &str {
+        &self.packet
+    }
+
+    /// The raw Extended XMP packet, if any.
+    pub fn extended_packet(&self) -> Option<&str> {
+        self.extended_packet.as_deref()
+    }
+
+    pub(crate) fn from_packets(packet: Vec<u8>, extended: Option<Vec<u8>>) -> crate::Result<Self> {
+        let mut xmp: Xmp = packet_to_string(packet)?.parse()?;
+        if let Some(extended) = extended {
+            let extended = packet_to_string(extended)?;
+            let root = xml::parse(&extended).map_err(|e| format!("invalid Extended XMP: {e}"))?;
+            xmp.properties.extend(parse_rdf(&root));
+            xmp.extended_packet = Some(extended);
+        }
+        Ok(xmp)
+    }
+}
+
+impl std::str::FromStr for Xmp {
+    type Err = crate::Error;
+
+    fn from_str(packet: &str) -> Result<Self, Self::Err> {
+        let root = xml::parse(packet).map_err(|e| format!("invalid XMP: {e}"))?;
+        Ok(Xmp {
+            packet: packet.to_owned(),
+            extended_packet: None,
+            properties: parse_rdf(&root),
+        })
+    }
+}
+
+fn packet_to_string(packet: Vec<u8>) -> crate::Result<String> {
+    let mut s = String::from_utf8(packet)?;
+    // remove trailing NULs & padding
+    s.truncate(s.trim_end_matches(['\0', ' ', '\n', '\r', '\t']).len());
+    Ok(s)
+}
+
+fn parse_rdf(root: &Element) -> BTreeMap<XmpName, XmpValue> {
+    let mut props = BTreeMap::new();
+    let Some(rdf) = find_element(root, NS_RDF, "RDF") else {
+        tracing::warn!("rdf:RDF not found in XMP packet");
+        return props;
+    };
+
+    for desc in rdf.elements().filter(|e| e.is(NS_RDF, "Description")) {
+        props.extend(parse_properties(desc));
+    }
+    props
+}
+
+fn find_element<'a>(e: &'a Element, ns: &str, name: &str) -> Option<&'a Element> {
+    if e.is(ns, name) {
+        return Some(e);
+    }
+    e.elements().find_map(|x| find_element(x, ns, name))
+}
+
+/// Parse properties of a node element (e.g. `rdf:Description`), including the
+/// property attributes.
+fn parse_properties(e: &Element) -> BTreeMap<XmpName, XmpValue> {
+    let attrs = e
+        .attrs
+        .iter()
+        .filter(|a| a.ns != NS_RDF && a.ns != NS_XML && !a.ns.is_empty())
+        .map(|a| {
+            (
+                XmpName::new(&a.ns, &a.name),
+                XmpValue::Text(a.value.to_owned()),
+            )
+        });
+
+    let elements = e
+        .elements()
+        .map(|x| (XmpName::new(&x.ns, &x.name), parse_value(x)));
+
+    attrs.chain(elements).collect()
+}
+
+/// Parse the value of a property element.
+fn parse_value(e: &Element) -> XmpValue {
+    if let Some(resource) = e.attr(NS_RDF, "resource") {
+        return XmpValue::Text(resource.to_owned());
+    }
+    if e.attr(NS_RDF, "parseType") == Some("Resource") {
+        return XmpValue::Struct(parse_properties(e));
+    }
+
+    if let Some(child) = e.elements().next() {
+        let items = || child.elements().filter(|x| x.is(NS_RDF, "li"));
+        return if child.is(NS_RDF, "Seq") {
+            XmpValue::Seq(items().map(parse_value).collect())
+        } else if child.is(NS_RDF, "Bag") {
+            XmpValue::Bag(items().map(parse_value).collect())
+        } else if child.is(NS_RDF, "Alt") {
+            XmpValue::Alt(
+                items()
+                    .map(|x| (x.attr(NS_XML, "lang").map(|x| x.to_owned()), parse_value(x)))
+                    .collect(),
+            )
+        } else if child.is(NS_RDF, "Description") {
+            XmpValue::Struct(parse_properties(child))
+        } else {
+            XmpValue::Struct(parse_properties(e))
+        };
+    }
+
+    let properties = parse_properties(e);
+    if !properties.is_empty() {
+        return XmpValue::Struct(properties);
+    }
+    XmpValue::Text(e.text())
+}
+
+/// The XMP packet, and the Extended XMP packet (if any).
+pub(crate) type XmpPackets = (Vec<u8>, Option<Vec<u8>>);
+
+/// Extract the XMP packet (and the Extended XMP, if any) from `input`.
+pub(crate) fn extract_xmp(mime: Mime, input: &[u8]) -> Result<Option<XmpPackets>, ParsingError> {
+    match mime {
+        Mime::Image(MimeImage::Jpeg) => {
+            let (_, res) = jpeg::extract_xmp_data(input)?;
+            Ok(res)
+        }
+        Mime::Image(img) if img.is_tiff() => {
+            let data = extract_tiff_tag_data(input, TIFF_XMP_TAG)?;
+            Ok(data.map(|x| (x.to_vec(), None)))
+        }
+        Mime::Image(MimeImage::Heic | MimeImage::Heif | MimeImage::Avif) => {
+            let (_, data) = heif::extract_xmp_data(input)?;
+            Ok(data.map(|x| (x.to_vec(), None)))
+        }
+        Mime::Image(MimeImage::Png) => png::extract_xmp_data(input).map(|x| x.map(|x| (x, None))),
+        Mime::Image(MimeImage::Webp) => {
+            webp::extract_xmp_data(input).map(|x| x.map(|x| (x.to_vec(), None)))
+        }
+        Mime::Image(MimeImage::Jxl) => jxl::extract_xmp_data(input).map(|x| x.map(|x| (x, None))),
+        Mime::Video(MimeVideo::QuickTime | MimeVideo::Mp4 | MimeVideo::_3gpp | MimeVideo::M4a) => {
+            mov::extract_xmp_from_buf(input).map(|x| x.map(|x| (x.to_vec(), None)))
+        }
+        _ => Err(ParsingError::Failed(format!(
+            "XMP is not supported for {mime:?}"
+        ))),
+    }
+}
+
+const TIFF_XMP_TAG: u16 = 0x02BC;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::{bmff_box, heif_xmp_sample, parse_sample, read_sample};
+    use crate::{EntryValue, ExifTag, ExifWriter, IfdGroup};
+    use test_case::test_case;
+
+    const PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:exif="http://ns.adobe.com/exif/1.0/"
+    xmlns:GCamera="http://ns.google.com/photos/1.0/camera/"
+    xmp:Rating="3"
+    GCamera:MicroVideo="1">
+   <dc:subject>
+    <rdf:Bag>
+     <rdf:li>cat</rdf:li>
+     <rdf:li>dog</rdf:li>
+    </rdf:Bag>
+   </dc:subject>
+   <dc:title>
+    <rdf:Alt>
+     <rdf:li xml:lang="en">Title</rdf:li>
+     <rdf:li xml:lang="x-default">Default Title</rdf:li>
+    </rdf:Alt>
+   </dc:title>
+   <exif:Flash exif:Fired="False" exif:Mode="2"/>
+   <xmp:Identifier rdf:parseType="Resource">
+    <xmp:Scheme>isbn</xmp:Scheme>
+   </xmp:Identifier>
+   <dc:source rdf:resource="http://example.com/"/>
+  </rdf:Description>
+  <rdf:Description xmlns:xmp="http://ns.adobe.com/xap/1.0/">
+   <xmp:CreatorTool>nom-exif &amp; co</xmp:CreatorTool>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+    #[test]
+    fn parse_xmp() {
+        let xmp: Xmp = PACKET.parse().unwrap();
+        assert_eq!(xmp.packet(), PACKET);
+
+        let get = |ns, name| xmp.get(ns, name).unwrap();
+        assert_eq!(get(Xmp::NS_XMP, "Rating").as_str(), Some("3"));
+        assert_eq!(
+            get("http://ns.google.com/photos/1.0/camera/", "MicroVideo").as_str(),
+            Some("1")
+        );
+        assert_eq!(
+            get(Xmp::NS_DC, "subject"),
+            &XmpValue::Bag(vec![
+                XmpValue::Text("cat".into()),
+                XmpValue::Text("dog".into())
+            ])
+        );
+        assert_eq!(get(Xmp::NS_DC, "title").as_str(), Some("Default Title"));
+        assert_eq!(
+            get(Xmp::NS_EXIF, "Flash")
+                .as_struct()
+                .unwrap()
+                .get(&XmpName::new(Xmp::NS_EXIF, "Mode")),
+            Some(&XmpValue::Text("2".into()))
+        );
+        assert_eq!(
+            get(Xmp::NS_XMP, "Identifier")
+                .as_struct()
+                .unwrap()
+                .get(&XmpName::new(Xmp::NS_XMP, "Scheme")),
+            Some(&XmpValue::Text("isbn".into()))
+        );
+        assert_eq!(
+            get(Xmp::NS_DC, "source").as_str(),
+            Some("http://example.com/")
+        );
+        assert_eq!(
+            get(Xmp::NS_XMP, "CreatorTool").as_str(),
+            Some("nom-exif & co")
+        );
+        assert!(xmp.get(NS_RDF, "about").is_none());
+        assert_eq!(xmp.iter().count(), 8);
+    }
+
+    #[test]
+    fn parse_invalid_xmp() {
+        "<x:xmpmeta>".parse::<Xmp>().unwrap_err();
+    }
+
+    fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        [
+            &[0xFF, marker][..],
+            &((payload.len() + 2) as u16).to_be_bytes(),
+            payload,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn jpeg_xmp() {
+        const GUID: &[u8; 32] = b"0123456789ABCDEF0123456789ABCDEF";
+        let packet = PACKET.replace(
+            r#"xmp:Rating="3""#,
+            r#"xmp:Rating="3"
+    xmlns:xmpNote="http://ns.adobe.com/xmp/note/"
+    xmpNote:HasExtendedXMP="0123456789ABCDEF0123456789ABCDEF""#,
+        );
+        let extended = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description xmlns:GDepth="http://ns.google.com/photos/1.0/depthmap/"
+    GDepth:Mime="image/jpeg" GDepth:Data="AAAABBBBCCCCDDDD"/>
+ </rdf:RDF>
+</x:xmpmeta>"#;
+
+        let ext_chunk = |guid: &[u8], data: &[u8], offset: usize| {
+            let payload = [
+                jpeg::XMP_EXT_IDENT,
+                guid,
+                &(extended.len() as u32).to_be_bytes(),
+                &(offset as u32).to_be_bytes(),
+                data,
+            ]
+            .concat();
+            jpeg_segment(0xE1, &payload)
+        };
+        let (a, b) = extended.as_bytes().split_at(100);
+        let segments = [
+            jpeg_segment(0xE1, &[jpeg::XMP_IDENT, packet.as_bytes()].concat()),
+            ext_chunk(GUID, b, a.len()),
+            ext_chunk(&[b'F'; 32], b"broken", 0),
+            ext_chunk(GUID, a, 0),
+        ]
+        .concat();
+
+        let mut buf = read_sample("no-exif.jpg").unwrap();
+        buf.splice(2..2, segments);
+
+        let xmp: Xmp = parse_sample(buf).unwrap();
+        assert_eq!(xmp.packet(), packet);
+        assert_eq!(xmp.extended_packet(), Some(extended));
+        assert_eq!(xmp.get(Xmp::NS_XMP, "Rating").unwrap().as_str(), Some("3"));
+        assert_eq!(
+            xmp.get("http://ns.google.com/photos/1.0/depthmap/", "Data")
+                .unwrap()
+                .as_str(),
+            Some("AAAABBBBCCCCDDDD")
+        );
+
+        parse_sample::<Xmp>(read_sample("exif.jpg").unwrap()).unwrap_err();
+    }
+
+    #[test]
+    fn tiff_xmp() {
+        let mut writer = ExifWriter::new();
+        writer
+            .set(IfdGroup::Ifd0, ExifTag::Make, "abc".into())
+            .set_by_tag_code(
+                IfdGroup::Ifd0,
+                TIFF_XMP_TAG,
+                EntryValue::Undefined(PACKET.as_bytes().to_vec()),
+            );
+        let xmp: Xmp = parse_sample(writer.write().unwrap()).unwrap();
+        assert_eq!(xmp.packet(), PACKET);
+        assert_eq!(xmp.get(Xmp::NS_XMP, "Rating").unwrap().as_str(), Some("3"));
+
+        parse_sample::<Xmp>(read_sample("tif.tif").unwrap()).unwrap_err();
+    }
+
+    #[test]
+    fn heif_xmp() {
+        let buf = heif_xmp_sample(PACKET.as_bytes());
+        let xmp: Xmp = parse_sample(buf).unwrap();
+        assert_eq!(xmp.packet(), PACKET);
+
+        parse_sample::<Xmp>(read_sample("exif-one-entry.heic").unwrap()).unwrap_err();
+    }
+
+    #[test_case("meta.mov", true)]
+    #[test_case("meta.mp4", false)]
+    fn mov_xmp(path: &str, prepend: bool) {
+        let mut buf = read_sample(path).unwrap();
+        let uuid = bmff_box(b"uuid", &[&mov::XMP_UUID[..], PACKET.as_bytes()].concat());
+        if prepend {
+            // insert after ftyp
+            let pos = u32::from_be_bytes(buf[..4].try_into().unwrap()) as usize;
+            buf.splice(pos..pos, uuid);
+        } else {
+            buf.extend(uuid);
+        }
+
+        let xmp: Xmp = parse_sample(buf).unwrap();
+        assert_eq!(xmp.packet(), PACKET);
+        assert_eq!(xmp.get(Xmp::NS_XMP, "Rating").unwrap().as_str(), Some("3"));
+
+        parse_sample::<Xmp>(read_sample(path).unwrap()).unwrap_err();
+    }
+}
@@ -0,0 +1,325 @@
+//! A minimal non-validating XML parser, just enough for parsing XMP packets.
+//!
+//! - Namespaces are resolved, element/attribute names are represented by
+//!   (namespace URI, local name).
+//! - Processing instructions, comments & DOCTYPE declarations are ignored.
+//! - Only predefined entities & character references are decoded.
+
+const NS_XML: &str = "http://www.w3.org/XML/1998/namespace";
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Element {
+    pub ns: String,
+    pub name: String,
+    pub attrs: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Attribute {
+    pub ns: String,
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    pub fn is(&self, ns: &str, name: &str) -> bool {
+        self.ns == ns && self.name == name
+    }
+
+    pub fn attr(&self, ns: &str, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|a| a.ns == ns && a.name == name)
+            .map(|a| a.value.as_str())
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|x| match x {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|x| match x {
+                Node::Text(s) => Some(s.as_str()),
+                Node::Element(_) => None,
+            })
+            .collect()
+    }
+}
+
+struct RawElement {
+    qname: String,
+    attrs: Vec<(String, String)>,
+    // namespace declarations of this element, (prefix, URI)
+    scope: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+/// Parse `input` and returns the root element.
+pub(crate) fn parse(input: &str) -> Result<Element, String> {
+    let mut stack: Vec<RawElement> = Vec::new();
+    let mut root = None;
+    let mut remain = input;
+
+    while !remain.is_empty() && root.is_none() {
+        if let Some(rem) = remain.strip_prefix("<?") {
+            remain = skip_past(rem, "?>")?;
+        } else if let Some(rem) = remain.strip_prefix("<!--") {
+            remain = skip_past(rem, "-->")?;
+        } else if let Some(rem) = remain.strip_prefix("<![CDATA[") {
+            let end = rem.find("]]>").ok_or("unterminated CDATA section")?;
+            if let Some(e) = stack.last_mut() {
+                e.children.push(Node::Text(rem[..end].to_owned()));
+            }
+            remain = &rem[end + 3..];
+        } else if let Some(rem) = remain.strip_prefix("<!") {
+            // DOCTYPE, skip the internal subset too
+            let end = match (rem.find('['), rem.find('>')) {
+                (Some(b), Some(g)) if b < g => rem.find("]>").map(|x| x + 2),
+                (_, g) => g.map(|x| x + 1),
+            };
+            remain = &rem[end.ok_or("unterminated declaration")?..];
+        } else if let Some(rem) = remain.strip_prefix("</") {
+            let end = rem.find('>').ok_or("unterminated end tag")?;
+            let qname = rem[..end].trim();
+            remain = &rem[end + 1..];
+
+            let e = stack.pop().ok_or("unexpected end tag")?;
+            if e.qname != qname {
+                return Err(format!("mismatched end tag: {qname}, expected {}", e.qname));
+            }
+            let e = resolve(e, &stack)?;
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(Node::Element(e)),
+                None => root = Some(e),
+            }
+        } else if let Some(rem) = remain.strip_prefix('<') {
+            let (rem, e, empty) = parse_start_tag(rem)?;
+            remain = rem;
+            if empty {
+                let e = resolve(e, &stack)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Node::Element(e)),
+                    None => root = Some(e),
+                }
+            } else {
+                if stack.len() >= MAX_DEPTH {
+                    return Err("XML is nested too deeply".into());
+                }
+                stack.push(e);
+            }
+        } else {
+            let end = remain.find('<').unwrap_or(remain.len());
+            let text = &remain[..end];
+            remain = &remain[end..];
+            match stack.last_mut() {
+                Some(e) => e.children.push(Node::Text(unescape(text)?)),
+                None if text.trim().is_empty() => (),
+                None => return Err("text outside of the root element".into()),
+            }
+        }
+    }
+
+    root.ok_or_else(|| "no root element".into())
+}
+
+fn skip_past<'a>(input: &'a str, pat: &str) -> Result<&'a str, String> {
+    input
+        .find(pat)
+        .map(|x| &input[x + pat.len()..])
+        .ok_or_else(|| format!("{pat:?} not found"))
+}
+
+fn is_name_end(c: char) -> bool {
+    c.is_whitespace() || c == '/' || c == '>' || c == '='
+}
+
+/// Returns (remain, element, is empty element)
+fn parse_start_tag(input: &str) -> Result<(&str, RawElement, bool), String> {
+    let end = input.find(is_name_end).ok_or("unterminated start tag")?;
+    let qname = &input[..end];
+    if qname.is_empty() {
+        return Err("empty element name".into());
+    }
+
+    let mut e = RawElement {
+        qname: qname.to_owned(),
+        attrs: Vec::new(),
+        scope: Vec::new(),
+        children: Vec::new(),
+    };
+
+    let mut remain = &input[end..];
+    loop {
+        remain = remain.trim_start();
+        if let Some(rem) = remain.strip_prefix("/>") {
+            return Ok((rem, e, true));
+        } else if let Some(rem) = remain.strip_prefix('>') {
+            return Ok((rem, e, false));
+        }
+
+        let end = remain.find(is_name_end).ok_or("unterminated start tag")?;
+        let name = &remain[..end];
+        let rem = remain[end..].trim_start();
+        let rem = rem
+            .strip_prefix('=')
+            .ok_or_else(|| format!("attribute {name} has no value"))?
+            .trim_start();
+        let quote = rem
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("attribute {name} is not quoted"))?;
+        let rem = &rem[1..];
+        let end = rem
+            .find(quote)
+            .ok_or_else(|| format!("attribute {name} is not terminated"))?;
+        let value = unescape(&rem[..end])?;
+        remain = &rem[end + 1..];
+
+        if name == "xmlns" {
+            e.scope.push((String::new(), value));
+        } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+            e.scope.push((prefix.to_owned(), value));
+        } else {
+            e.attrs.push((name.to_owned(), value));
+        }
+    }
+}
+
+fn lookup<'a>(prefix: &str, e: &'a RawElement, stack: &'a [RawElement]) -> Option<&'a str> {
+    if prefix == "xml" {
+        return Some(NS_XML);
+    }
+    std::iter::once(e)
+        .chain(stack.iter().rev())
+        .flat_map(|x| x.scope.iter().rev())
+        .find(|(p, _)| p == prefix)
+        .map(|(_, uri)| uri.as_str())
+}
+
+fn resolve(e: RawElement, stack: &[RawElement]) -> Result<Element, String> {
+    let split = |qname: &str| match qname.split_once(':') {
+        Some((prefix, name)) => (prefix.to_owned(), name.to_owned()),
+        None => (String::new(), qname.to_owned()),
+    };
+
+    let (prefix, name) = split(&e.qname);
+    let ns = lookup(&prefix, &e, stack);
+    if ns.is_none() && !prefix.is_empty() {
+        return Err(format!("undeclared namespace prefix: {prefix}"));
+    }
+    let ns = ns.unwrap_or_default().to_owned();
+
+    let mut attrs = Vec::with_capacity(e.attrs.len());
+    for (qname, value) in e.attrs.iter() {
+        let (prefix, name) = split(qname);
+        // unprefixed attributes are in no namespace
+        let ns = if prefix.is_empty() {
+            String::new()
+        } else {
+            lookup(&prefix, &e, stack)
+                .ok_or_else(|| format!("undeclared namespace prefix: {prefix}"))?
+                .to_owned()
+        };
+        attrs.push(Attribute {
+            ns,
+            name,
+            value: value.to_owned(),
+        });
+    }
+
+    Ok(Element {
+        ns,
+        name,
+        attrs,
+        children: e.children,
+    })
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    if !s.contains('&') {
+        return Ok(s.to_owned());
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut remain = s;
+    while let Some(start) = remain.find('&') {
+        out.push_str(&remain[..start]);
+        let rem = &remain[start + 1..];
+        let end = rem.find(';').ok_or("unterminated entity reference")?;
+        let entity = &rem[..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("unknown entity: &{entity};"))?
+            }
+        };
+        out.push(c);
+        remain = &rem[end + 1..];
+    }
+    out.push_str(remain);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn parse_xml() {
+        let s = r#"<?xml version="1.0"?>
+<!DOCTYPE a [ <!ENTITY x "y"> ]>
+<!-- comment -->
+<a:root xmlns:a="urn:a" xmlns="urn:default" a:x='1 &amp; 2' y="&#x41;&#66;">
+  <child>text<![CDATA[<raw>]]></child>
+  <a:empty/>
+</a:root>
+trailing"#;
+        let root = parse(s).unwrap();
+        assert!(root.is("urn:a", "root"));
+        assert_eq!(root.attr("urn:a", "x"), Some("1 & 2"));
+        assert_eq!(root.attr("", "y"), Some("AB"));
+
+        let children = root.elements().collect::<Vec<_>>();
+        assert_eq!(children.len(), 2);
+        assert!(children[0].is("urn:default", "child"));
+        assert_eq!(children[0].text(), "text<raw>");
+        assert!(children[1].is("urn:a", "empty"));
+    }
+
+    #[test_case("<a><b></a>")]
+    #[test_case("<a>")]
+    #[test_case("<x:a/>")]
+    #[test_case("<a b=c/>")]
+    #[test_case("<a>&unknown;</a>")]
+    #[test_case("")]
+    fn parse_invalid_xml(s: &str) {
+        parse(s).unwrap_err();
+    }
+}